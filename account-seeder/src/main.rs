@@ -1,19 +1,20 @@
 // src/main.rs for account-seeder
 
-use clap::Parser;
+use bridge_core::{
+    metrics, Account, AccountStore, BridgeError, EndpointArgs, EndpointPool, FeeArgs, Fees, MetricsArgs, NodeArgs,
+    Recorder, RpcProvider, RunArgs, TxRecord, TxStatus,
+};
+use clap::{CommandFactory, Parser};
 use ethers::{
-    core::types::{TransactionRequest, U256, H160},
-    providers::{Http as EthersHttp, Middleware, Provider as EthersProvider},
+    core::types::{TransactionRequest, U256},
+    providers::Middleware,
     signers::{LocalWallet, Signer},
     utils::format_ether,
 };
 use futures::future::join_all;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
-use serde::{Deserialize, Serialize};
 use std::{
-    fs::File,
-    str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -24,10 +25,18 @@ use tokio::time;
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    /// RPC endpoint URL
+    /// RPC endpoint: HTTP or WebSocket URL, or IPC socket path
     #[clap(short = 'u', long, default_value = "http://57.128.116.154:8545")]
     rpc_url: String,
 
+    /// Take the RPC endpoint from a topology or .env node instead of --rpc-url
+    #[clap(flatten)]
+    nodes: NodeArgs,
+
+    /// Several RPC endpoints of the same chain to send funding transfers through, with failover
+    #[clap(flatten)]
+    endpoints: EndpointArgs,
+
     /// Number of accounts to generate and seed
     #[clap(short = 'n', long, default_value_t = 3000)]
     account_count: usize,
@@ -51,13 +60,16 @@ struct Args {
     /// Output file for accounts
     #[clap(short = 'o', long, default_value = "accounts.json")]
     output_file: String,
-}
 
-// Account structure
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Account {
-    address: String,
-    private_key: String,
+    /// Transaction type and fees for funding transfers; eth_gasPrice or eth_feeHistory by default
+    #[clap(flatten)]
+    fees: FeeArgs,
+
+    #[clap(flatten)]
+    metrics: MetricsArgs,
+
+    #[clap(flatten)]
+    runs: RunArgs,
 }
 
 // Error handling
@@ -75,8 +87,8 @@ enum AppError {
     #[error("URL parse error: {0}")]
     UrlParse(#[from] url::ParseError),
     
-    #[error("Address parse error: {0}")]
-    AddressParse(String),
+    #[error(transparent)]
+    Bridge(#[from] BridgeError),
     
     #[error("Semaphore error: {0}")]
     Semaphore(#[from] tokio::sync::AcquireError),
@@ -99,23 +111,14 @@ impl From<ethers::providers::ProviderError> for AppError {
     }
 }
 
-impl From<ethers::contract::ContractError<ethers::providers::Provider<EthersHttp>>> for AppError {
-    fn from(err: ethers::contract::ContractError<ethers::providers::Provider<EthersHttp>>) -> Self {
+impl From<ethers::contract::ContractError<RpcProvider>> for AppError {
+    fn from(err: ethers::contract::ContractError<RpcProvider>) -> Self {
         AppError::Provider(err.to_string())
     }
 }
 
 type Result<T> = std::result::Result<T, AppError>;
 
-// Helper function to parse an address string without ENS resolution
-fn parse_address(address: &str) -> Result<H160> {
-    let addr = address.trim_start_matches("0x");
-    match H160::from_str(addr) {
-        Ok(addr) => Ok(addr),
-        Err(e) => Err(AppError::AddressParse(format!("Failed to parse address: {}", e))),
-    }
-}
-
 // Generate random accounts
 fn generate_accounts(count: usize) -> Vec<Account> {
     let mut rng = StdRng::from_entropy();
@@ -123,11 +126,7 @@ fn generate_accounts(count: usize) -> Vec<Account> {
     println!("Generating {} accounts...", count);
     let mut accounts = Vec::with_capacity(count);
     for i in 0..count {
-        let wallet = LocalWallet::new(&mut rng);
-        accounts.push(Account {
-            address: format!("{:x}", wallet.address()),
-            private_key: hex::encode(wallet.signer().to_bytes()),
-        });
+        accounts.push(Account::generate(&mut rng));
         
         if (i + 1) % 500 == 0 {
             println!("Generated {} accounts", i + 1);
@@ -139,28 +138,26 @@ fn generate_accounts(count: usize) -> Vec<Account> {
 
 // Save accounts to file
 fn save_accounts(senders: &[Account], receivers: &[Account], filename: &str) -> Result<()> {
-    let combined = serde_json::json!({
-        "senders": senders,
-        "receivers": receivers,
-    });
-    
-    let file = File::create(filename)?;
-    serde_json::to_writer_pretty(file, &combined)?;
+    AccountStore::new(senders.to_vec(), receivers.to_vec()).save(filename)?;
     
     println!("Accounts saved to {}", filename);
     Ok(())
 }
 
 // Fund accounts from a funded source account
+#[allow(clippy::too_many_arguments)]
 async fn fund_accounts(
-    provider: Arc<EthersProvider<EthersHttp>>,
+    pool: Arc<EndpointPool>,
     funder_wallet: LocalWallet,
     accounts: &[Account],
     amount_wei: U256,
+    fees: &Fees,
     batch_size: usize,
     concurrency: usize,
+    records: &Recorder,
 ) -> Result<()> {
     // Check funder balance
+    let provider = pool.primary().provider().clone();
     let funder_address = funder_wallet.address();
     let funder_balance = provider.get_balance(funder_address, None).await?;
     
@@ -179,9 +176,7 @@ async fn fund_accounts(
         )));
     }
     
-    // Get current gas price
-    let base_gas_price = provider.get_gas_price().await?;
-    println!("Base gas price: {} gwei", base_gas_price / U256::exp10(9));
+    println!("Base fees: {}", fees);
 
     // Get current nonce
     let mut nonce = provider.get_transaction_count(funder_address, None).await?;
@@ -191,8 +186,8 @@ async fn fund_accounts(
     let batch_count = (accounts.len() + batch_size - 1) / batch_size;
     let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
     
-    let funder_wallet = funder_wallet.with_chain_id(provider.get_chainid().await?.as_u64());
-    let client = ethers::middleware::SignerMiddleware::new(provider.clone(), funder_wallet);
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let funder_wallet = funder_wallet.with_chain_id(chain_id);
     
     println!("Funding accounts in {} batches...", batch_count);
     
@@ -209,42 +204,66 @@ async fn fund_accounts(
             let permit = semaphore.clone().acquire_owned().await?;
             let account_idx = start_idx + i;
             let account = &accounts[account_idx];
-            let client = client.clone();
+            let pool = pool.clone();
+            let funder_wallet = funder_wallet.clone();
+            let records = records.clone();
             let current_nonce = nonce;
             
-            // Calculate gas price with increasing multiplier based on position in batch
-            let gas_price = base_gas_price + (base_gas_price * U256::from(i as u64) / U256::from(10));
+            // Raise fees by 10% per position in the batch
+            let tx_fees = fees.bumped(i as u64 * 10);
             
             // Increment nonce for next transaction
             nonce = nonce + 1;
             
             let future = async move {
-                let addr = account.address.trim_start_matches("0x");
-                let to_address = match H160::from_str(addr) {
-                    Ok(address) => address,
-                    Err(e) => {
-                        return Err(AppError::AddressParse(format!(
-                            "Failed to parse address {}: {}", account.address, e
-                        )));
-                    }
-                };
+                let to_address = account.address()?;
                 
-                // Create transaction with calculated gas price
-                let tx = TransactionRequest::new()
+                // Create transaction with calculated fees
+                let tx = tx_fees.typed(TransactionRequest::new()
                     .to(to_address)
                     .value(amount_wei)
                     .gas(21_000)
-                    .gas_price(gas_price)
-                    .nonce(current_nonce);
+                    .nonce(current_nonce));
                 
                 let start = Instant::now();
+                let record = |status| {
+                    let mut record = TxRecord::new("fund", chain_id, funder_address, status);
+                    record.to = Some(to_address);
+                    record.nonce = Some(current_nonce.as_u64());
+                    record.value_wei = Some(amount_wei);
+                    record.timed(start, None)
+                };
+                
+                // All transfers come from the funder, so they share its endpoint under sticky balancing
+                let lease = pool.acquire(0)?;
+                let client = ethers::middleware::SignerMiddleware::new(lease.provider().clone(), funder_wallet);
+                metrics::submitted(1);
+                let sent = client.send_transaction(tx, None).await;
+                lease.record(&sent);
                 
                 // Send transaction
-                match client.send_transaction(tx, None).await {
+                match sent {
                     Ok(pending_tx) => {
-                        match pending_tx.await {
+                        metrics::accepted(1);
+                        let mut outcome = record(TxStatus::Pending);
+                        outcome.hash = Some(pending_tx.tx_hash());
+                        let confirmed = pending_tx.await;
+                        match &confirmed {
+                            Ok(Some(receipt)) => {
+                                let failed = receipt.status.is_some_and(|status| status.is_zero());
+                                outcome.status = if failed { TxStatus::Reverted } else { TxStatus::Included };
+                                outcome = outcome.timed(start, Some(Instant::now()));
+                                outcome.block_number = receipt.block_number.map(|block| block.as_u64());
+                                outcome.gas_used = receipt.gas_used.map(|gas| gas.as_u64());
+                            }
+                            Ok(None) => {}
+                            Err(e) => outcome.error = Some(e.to_string()),
+                        }
+                        records.record(outcome);
+                        match confirmed {
                             Ok(Some(receipt)) => {
                                 let elapsed = start.elapsed();
+                                metrics::included(elapsed);
                                 println!("Funded account {}/{} in {:?} (tx: {:?})", 
                                          account_idx + 1, accounts.len(), elapsed, receipt.transaction_hash);
                                 Ok(account_idx)
@@ -260,6 +279,10 @@ async fn fund_accounts(
                         }
                     },
                     Err(e) => {
+                        metrics::rejected(1);
+                        let mut rejected = record(TxStatus::Rejected);
+                        rejected.error = Some(e.to_string());
+                        records.record(rejected);
                         println!("Failed to fund account {}: {}", account_idx + 1, e);
                         Err(AppError::Provider(format!("Transaction submission error: {}", e)))
                     }
@@ -289,17 +312,28 @@ async fn fund_accounts(
     }
     
     println!("Funded {} accounts with {} ETH each", accounts.len(), format_ether(amount_wei));
+    if pool.len() > 1 {
+        for endpoint in pool.stats() {
+            println!("    {} ({}): {}", endpoint.url, endpoint.transport, endpoint);
+        }
+    }
     Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command line arguments
-    let args = Args::parse();
+    dotenv::dotenv().ok();
+    let mut args = Args::parse();
+    if args.nodes.node.is_some() {
+        args.rpc_url = args.nodes.node_or("1")?.rpc_url;
+    }
+    let node = args.nodes.node.clone().unwrap_or_else(|| args.endpoints.urls(&args.rpc_url).join(","));
+    args.metrics.serve(&[("tool", "account-seeder"), ("node", &node), ("workload", "fund")]).await?;
     
-    // Create provider
-    let provider = EthersProvider::try_from(args.rpc_url.clone())?;
-    let provider = Arc::new(provider);
+    // Create providers; the first endpoint serves reads
+    let pool = Arc::new(args.endpoints.connect(&args.rpc_url).await?);
+    let provider = pool.primary().provider().clone();
     
     // Get chain ID
     let chain_id = provider.get_chainid().await?.as_u64();
@@ -322,17 +356,40 @@ async fn main() -> Result<()> {
     // Calculate amount in wei
     let amount_wei = ethers::utils::parse_ether(args.amount_eth)?;
     
-    // Fund sender accounts
+    // Fees not given on the command line come from the node
+    let fees = args.fees.resolve(provider.as_ref(), None).await?;
+    
+    // Fund sender accounts, filing the run with a record of every funding transfer
+    let parameters = serde_json::json!({
+        "rpc_url": args.rpc_url,
+        "endpoints": args.endpoints.endpoints,
+        "account_count": args.account_count,
+        "amount_eth": args.amount_eth,
+        "concurrency": args.concurrency,
+        "batch_size": args.batch_size,
+        "output_file": args.output_file,
+    });
+    let nodes: Vec<(String, String)> = args.endpoints.urls(&args.rpc_url).into_iter().enumerate()
+        .map(|(i, url)| match &args.nodes.node {
+            Some(node) if i == 0 => (node.clone(), url),
+            _ => (format!("endpoint{}", i + 1), url),
+        })
+        .collect();
+    let run = args.runs.start("account-seeder", "fund", parameters, args.nodes.topology.topology.as_deref(), &nodes, &Args::command()).await?;
     println!("Funding {} sender accounts with {} ETH each...", 
              sender_accounts.len(), args.amount_eth);
-    fund_accounts(
-        provider.clone(),
+    let funded = fund_accounts(
+        pool.clone(),
         funder_wallet,
         &sender_accounts,
         amount_wei,
+        &fees,
         args.batch_size,
-        args.concurrency
-    ).await?;
+        args.concurrency,
+        &run.recorder(),
+    ).await;
+    run.finish(funded.as_ref().err().map(ToString::to_string))?;
+    funded?;
     
     // Save accounts to file
     save_accounts(&sender_accounts, &receiver_accounts, &args.output_file)?;
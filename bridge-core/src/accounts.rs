@@ -0,0 +1,113 @@
+use crate::error::{BridgeError, Result};
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::Address,
+};
+use rand::{CryptoRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, str::FromStr};
+
+/// A key pair as stored in `accounts.json` and `node-{n}.json`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Account {
+    pub address: String,
+    pub private_key: String,
+}
+
+impl Account {
+    /// Generates a fresh random account.
+    pub fn generate<R: Rng + CryptoRng>(rng: &mut R) -> Self {
+        Self::from_wallet(&LocalWallet::new(rng))
+    }
+
+    pub fn from_wallet(wallet: &LocalWallet) -> Self {
+        Account {
+            address: format!("{:x}", wallet.address()),  // Use lowercase hex without 0x prefix
+            private_key: hex::encode(wallet.signer().to_bytes()),  // Clean hex encoding
+        }
+    }
+
+    pub fn address(&self) -> Result<Address> {
+        parse_address(&self.address)
+    }
+
+    /// Parses the private key (with or without `0x`) into a wallet bound to `chain_id`.
+    pub fn wallet(&self, chain_id: u64) -> Result<LocalWallet> {
+        let key = self.private_key.trim().trim_start_matches("0x");
+        Ok(key.parse::<LocalWallet>()?.with_chain_id(chain_id))
+    }
+}
+
+/// Sender and receiver accounts for a node or a load test.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AccountStore {
+    pub senders: Vec<Account>,
+    pub receivers: Vec<Account>,
+    #[serde(skip)]
+    path: String,
+}
+
+impl AccountStore {
+    pub fn new(senders: Vec<Account>, receivers: Vec<Account>) -> Self {
+        AccountStore { senders, receivers, path: String::new() }
+    }
+
+    /// Generates `sender_count` senders and `receiver_count` receivers.
+    pub fn generate<R: Rng + CryptoRng>(sender_count: usize, receiver_count: usize, rng: &mut R) -> Self {
+        let senders = (0..sender_count).map(|_| Account::generate(rng)).collect();
+        let receivers = (0..receiver_count).map(|_| Account::generate(rng)).collect();
+        Self::new(senders, receivers)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().display().to_string();
+        let content = fs::read_to_string(&path)
+            .map_err(|source| BridgeError::Io { path: path.clone(), source })?;
+        let mut store: AccountStore = serde_json::from_str(&content)
+            .map_err(|source| BridgeError::Json { path: path.clone(), source })?;
+        store.path = path;
+        Ok(store)
+    }
+
+    /// Loads the `node-{n}.json` file written by `seed prepare`.
+    pub fn for_node(node: usize) -> Result<Self> {
+        Self::load(Self::node_file(node))
+    }
+
+    pub fn node_file(node: usize) -> String {
+        format!("node-{}.json", node)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref().display().to_string();
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|source| BridgeError::Json { path: path.clone(), source })?;
+        fs::write(&path, content)
+            .map_err(|source| BridgeError::Io { path, source })
+    }
+
+    pub fn sender(&self, index: usize) -> Result<&Account> {
+        self.senders.get(index).ok_or_else(|| BridgeError::MissingAccount {
+            list: "sender",
+            index,
+            path: self.path.clone(),
+        })
+    }
+
+    pub fn receiver(&self, index: usize) -> Result<&Account> {
+        self.receivers.get(index).ok_or_else(|| BridgeError::MissingAccount {
+            list: "receiver",
+            index,
+            path: self.path.clone(),
+        })
+    }
+}
+
+/// Parses an address with or without `0x`, without ENS resolution.
+pub fn parse_address(address: &str) -> Result<Address> {
+    let trimmed = address.trim().trim_start_matches("0x");
+    Address::from_str(trimmed).map_err(|e| BridgeError::Address {
+        address: address.to_string(),
+        reason: e.to_string(),
+    })
+}
@@ -0,0 +1,17 @@
+//! Typed MonetSmartContract bindings generated from the forge artifact.
+//!
+//! The artifact is gitignored build output, so `forge build` must run in
+//! `reth-contract` before this crate builds, and again after the contract
+//! changes. A function or event whose signature changed then breaks the Rust
+//! call sites at compile time; editing `MonetSmartContract.sol` alone does not.
+
+use ethers::contract::abigen;
+
+abigen!(
+    MonetSmartContract,
+    "../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json",
+    methods {
+        receiveETHfromSourceChainInBatch(uint32,uint32,address[],uint256[]) as receive_eth_from_source_chain_in_batch;
+    },
+    event_derives(serde::Deserialize, serde::Serialize)
+);
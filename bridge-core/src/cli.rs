@@ -0,0 +1,49 @@
+use crate::error::{BridgeError, Result};
+use crate::node::{NodeConfig, NodeRegistry};
+use clap::Args;
+use std::path::PathBuf;
+
+/// `--topology` flag shared by every binary.
+#[derive(Args, Debug, Clone, Default)]
+pub struct TopologyArgs {
+    /// Network topology file (TOML); falls back to NODE{n}_* variables in .env
+    #[arg(long, global = true)]
+    pub topology: Option<PathBuf>,
+}
+
+impl TopologyArgs {
+    pub fn registry(&self) -> Result<NodeRegistry> {
+        NodeRegistry::load(self.topology.as_deref())
+    }
+
+    /// Resolves a node by name or number.
+    ///
+    /// Without a topology file only the selected node's variables are read, so
+    /// a partially filled `.env` still works for single-node tools.
+    pub fn node(&self, selector: &str) -> Result<NodeConfig> {
+        if self.topology.is_some() {
+            return self.registry()?.select(selector).cloned();
+        }
+        let index = selector.trim_start_matches("node").parse()
+            .map_err(|_| BridgeError::UnknownNode(selector.to_string()))?;
+        NodeConfig::from_env(index)
+    }
+}
+
+/// `--topology` plus `--node` for tools that talk to a single chain.
+#[derive(Args, Debug, Clone, Default)]
+pub struct NodeArgs {
+    #[command(flatten)]
+    pub topology: TopologyArgs,
+
+    /// Node to connect to, by name or number
+    #[arg(long)]
+    pub node: Option<String>,
+}
+
+impl NodeArgs {
+    /// The selected node, or `default` when `--node` was not given.
+    pub fn node_or(&self, default: &str) -> Result<NodeConfig> {
+        self.topology.node(self.node.as_deref().unwrap_or(default))
+    }
+}
@@ -0,0 +1,24 @@
+use crate::bindings::{
+    MonetSmartContract, MONETSMARTCONTRACT_ABI, MONETSMARTCONTRACT_DEPLOYED_BYTECODE,
+};
+use ethers::{
+    abi::Abi,
+    providers::Middleware,
+    types::{Address, Bytes},
+};
+use std::sync::Arc;
+
+/// Full ABI, for tools that decode arbitrary calls and logs.
+pub fn monet_abi() -> Abi {
+    MONETSMARTCONTRACT_ABI.clone()
+}
+
+/// Runtime bytecode from the artifact, for comparing against `eth_getCode`.
+pub fn monet_deployed_bytecode() -> Bytes {
+    MONETSMARTCONTRACT_DEPLOYED_BYTECODE.clone()
+}
+
+/// Typed MonetSmartContract handle at `address`.
+pub fn monet_contract<M: Middleware>(address: Address, client: Arc<M>) -> MonetSmartContract<M> {
+    MonetSmartContract::new(address, client)
+}
@@ -0,0 +1,357 @@
+use crate::transport::RpcProvider;
+use ethers::providers::{Middleware, MiddlewareError};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write as _,
+    io::{IsTerminal, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{task::JoinHandle, time};
+
+const REFRESH: Duration = Duration::from_secs(1);
+
+// Seconds of latency history in the sparkline
+const SPARKLINE_SECS: usize = 60;
+
+// Recent blocks listed with their gas
+const RECENT_BLOCKS: usize = 5;
+
+// Error kinds listed; the rest are summed up
+const TOP_ERRORS: usize = 5;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Default)]
+struct Metrics {
+    accepted: usize,
+    failed: usize,
+    in_flight: usize,
+    /// Failures by error message, with numbers and hex blanked out
+    errors: BTreeMap<String, usize>,
+    /// RPC latencies since the last refresh
+    latencies: Vec<Duration>,
+}
+
+/// Cheap handle the sending tasks report to. A default handle belongs to no
+/// dashboard and only counts.
+#[derive(Debug, Clone, Default)]
+pub struct DashboardHandle {
+    metrics: Arc<Mutex<Metrics>>,
+    live: bool,
+}
+
+impl DashboardHandle {
+    /// Whether a dashboard redraws the terminal, so periodic progress lines should be left out.
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    /// Transactions accepted and failed so far.
+    pub fn totals(&self) -> (usize, usize) {
+        let metrics = self.metrics.lock().unwrap();
+        (metrics.accepted, metrics.failed)
+    }
+
+    /// `count` transactions are being sent.
+    pub fn started(&self, count: usize) {
+        self.metrics.lock().unwrap().in_flight += count;
+    }
+
+    /// `count` transactions were accepted, `latency` after they were started.
+    pub fn accepted(&self, count: usize, latency: Duration) {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.in_flight = metrics.in_flight.saturating_sub(count);
+        metrics.accepted += count;
+        metrics.latencies.push(latency);
+    }
+
+    /// A transaction failed with `error`.
+    pub fn failed(&self, error: &str) {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.in_flight = metrics.in_flight.saturating_sub(1);
+        metrics.failed += 1;
+        *metrics.errors.entry(error_kind(error)).or_default() += 1;
+    }
+
+    /// A transaction failed with `error`, counted under its JSON-RPC error message when it has one.
+    pub fn rejected<E: MiddlewareError>(&self, error: &E) {
+        match error.as_error_response() {
+            Some(response) => self.failed(&response.message),
+            None => self.failed(&error.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BlockRow {
+    number: u64,
+    txs: usize,
+    gas_used: u64,
+    gas_limit: u64,
+}
+
+// What the refresh loop keeps between frames
+#[derive(Debug)]
+struct View {
+    title: String,
+    started: Instant,
+    last_refresh: Instant,
+    last_done: usize,
+    /// Last block read, None before the first refresh
+    head: Option<u64>,
+    blocks: VecDeque<BlockRow>,
+    /// Transactions in blocks that arrived since the last refresh
+    included: usize,
+    pool: Option<(u64, u64)>,
+    /// Median RPC latency per refresh, None for refreshes without sends
+    sparkline: VecDeque<Option<Duration>>,
+}
+
+/// Live view of a running load test: send and inclusion rates, in-flight sends, errors,
+/// txpool size, recent blocks and an RPC latency sparkline. Redraws the terminal every
+/// second, or prints a log line a second when stdout is not a terminal.
+pub struct Dashboard {
+    handle: DashboardHandle,
+    stop: Arc<AtomicBool>,
+    refresher: JoinHandle<()>,
+}
+
+impl Dashboard {
+    /// Starts refreshing; blocks and txpool status are read from `provider`.
+    pub fn start(provider: Arc<RpcProvider>, title: &str) -> Self {
+        let handle = DashboardHandle {
+            metrics: Arc::default(),
+            live: std::io::stdout().is_terminal(),
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let now = Instant::now();
+        let view = View {
+            title: title.to_string(),
+            started: now,
+            last_refresh: now,
+            last_done: 0,
+            head: None,
+            blocks: VecDeque::new(),
+            included: 0,
+            pool: None,
+            sparkline: VecDeque::new(),
+        };
+        let refresher = tokio::spawn(refresh(provider, handle.clone(), view, stop.clone()));
+        Dashboard { handle, stop, refresher }
+    }
+
+    pub fn handle(&self) -> DashboardHandle {
+        self.handle.clone()
+    }
+
+    /// Stops after drawing a last frame, which stays on screen.
+    pub async fn finish(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Err(e) = self.refresher.await {
+            eprintln!("Dashboard failed: {}", e);
+        }
+    }
+}
+
+async fn refresh(provider: Arc<RpcProvider>, handle: DashboardHandle, mut view: View, stop: Arc<AtomicBool>) {
+    // Blocks before the start count towards no rate
+    read_chain(&provider, &mut view).await;
+    loop {
+        // Wake early to draw the last frame soon after `stop`
+        let next = Instant::now() + REFRESH;
+        while Instant::now() < next && !stop.load(Ordering::SeqCst) {
+            time::sleep(Duration::from_millis(100)).await;
+        }
+        let stopping = stop.load(Ordering::SeqCst);
+        read_chain(&provider, &mut view).await;
+        let frame = {
+            let mut metrics = handle.metrics.lock().unwrap();
+            let frame = if handle.live { draw(&view, &metrics) } else { log_line(&view, &metrics) };
+            let done = metrics.accepted + metrics.failed;
+            let latencies = std::mem::take(&mut metrics.latencies);
+            view.advance(done, latencies);
+            frame
+        };
+
+        show(&frame, handle.live);
+
+        if stopping {
+            break;
+        }
+    }
+}
+
+fn show(frame: &str, live: bool) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = if live {
+        // Home and clear, so lines printed in between are wiped too
+        write!(stdout, "\x1b[H\x1b[2J{}", frame)
+    } else {
+        writeln!(stdout, "{}", frame)
+    };
+    let _ = stdout.flush();
+}
+
+// New blocks since the last refresh and the txpool size; failed reads keep the old values
+async fn read_chain(provider: &RpcProvider, view: &mut View) {
+    view.included = 0;
+    let head = match time::timeout(REFRESH, provider.get_block_number()).await {
+        Ok(Ok(head)) => head.as_u64(),
+        _ => return,
+    };
+    // Only the last few blocks are read after a gap
+    let from = match view.head {
+        Some(last) => (last + 1).max(head.saturating_sub(RECENT_BLOCKS as u64 - 1)),
+        None => head,
+    };
+    for number in from..=head {
+        match time::timeout(REFRESH, provider.get_block(number)).await {
+            Ok(Ok(Some(block))) => {
+                let row = BlockRow {
+                    number,
+                    txs: block.transactions.len(),
+                    gas_used: block.gas_used.as_u64(),
+                    gas_limit: block.gas_limit.as_u64(),
+                };
+                if view.head.is_some() {
+                    view.included += row.txs;
+                }
+                view.blocks.push_front(row);
+                view.blocks.truncate(RECENT_BLOCKS);
+                view.head = Some(number);
+            }
+            _ => break,
+        }
+    }
+    if let Ok(status) = time::timeout(REFRESH, provider.txpool_status()).await {
+        view.pool = status.ok().map(|status| (status.pending.as_u64(), status.queued.as_u64()));
+    }
+}
+
+impl View {
+    fn rates(&self, done: usize) -> (f64, f64) {
+        let secs = self.last_refresh.elapsed().as_secs_f64().max(0.001);
+        ((done - self.last_done) as f64 / secs, self.included as f64 / secs)
+    }
+
+    fn advance(&mut self, done: usize, mut latencies: Vec<Duration>) {
+        self.last_refresh = Instant::now();
+        self.last_done = done;
+        latencies.sort();
+        self.sparkline.push_back(latencies.get(latencies.len() / 2).copied());
+        if self.sparkline.len() > SPARKLINE_SECS {
+            self.sparkline.pop_front();
+        }
+    }
+}
+
+fn draw(view: &View, metrics: &Metrics) -> String {
+    let done = metrics.accepted + metrics.failed;
+    let (send_rate, inclusion_rate) = view.rates(done);
+    let mut out = String::new();
+    let _ = writeln!(out, "{} - {:.0?} elapsed", view.title, view.started.elapsed());
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Send rate      {:>10.1} tx/s", send_rate);
+    let _ = writeln!(out, "Inclusion rate {:>10.1} tx/s (all transactions in new blocks)", inclusion_rate);
+    let _ = writeln!(out, "Accepted       {:>10}", metrics.accepted);
+    let _ = writeln!(out, "Failed         {:>10}", metrics.failed);
+    let _ = writeln!(out, "In flight      {:>10}", metrics.in_flight);
+    let _ = match view.pool {
+        Some((pending, queued)) => writeln!(out, "Txpool         {:>10} pending, {} queued", pending, queued),
+        None => writeln!(out, "Txpool         {:>10}", "n/a"),
+    };
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "RPC latency (p50 per second, last {}s, max {:.0?})", SPARKLINE_SECS,
+        view.sparkline.iter().flatten().max().copied().unwrap_or_default());
+    let _ = writeln!(out, "  {}", sparkline(&view.sparkline));
+    let _ = writeln!(out);
+
+    let _ = match view.head {
+        Some(head) => writeln!(out, "Latest block {}", head),
+        None => writeln!(out, "Latest block n/a"),
+    };
+    for block in &view.blocks {
+        let percent = if block.gas_limit > 0 { block.gas_used as f64 * 100.0 / block.gas_limit as f64 } else { 0.0 };
+        let _ = writeln!(out, "  #{:<10} {:>6} txs  {:>12} gas ({:.1}%)", block.number, block.txs, block.gas_used, percent);
+    }
+
+    if !metrics.errors.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Errors");
+        let mut errors: Vec<(&String, &usize)> = metrics.errors.iter().collect();
+        errors.sort_by(|a, b| b.1.cmp(a.1));
+        for (error, count) in errors.iter().take(TOP_ERRORS) {
+            let _ = writeln!(out, "  {:>8}  {}", count, error);
+        }
+        if errors.len() > TOP_ERRORS {
+            let rest: usize = errors[TOP_ERRORS..].iter().map(|(_, count)| **count).sum();
+            let _ = writeln!(out, "  {:>8}  ({} other errors)", rest, errors.len() - TOP_ERRORS);
+        }
+    }
+    out
+}
+
+fn log_line(view: &View, metrics: &Metrics) -> String {
+    let done = metrics.accepted + metrics.failed;
+    let (send_rate, inclusion_rate) = view.rates(done);
+    let mut line = format!(
+        "[{:>5.0}s] sent {:.1}/s, included {:.1}/s, accepted {}, failed {}, in flight {}",
+        view.started.elapsed().as_secs_f64(), send_rate, inclusion_rate,
+        metrics.accepted, metrics.failed, metrics.in_flight,
+    );
+    if let Some((pending, queued)) = view.pool {
+        let _ = write!(line, ", pool {}/{}", pending, queued);
+    }
+    if let Some(block) = view.blocks.front() {
+        let _ = write!(line, ", block {} ({} txs, {} gas)", block.number, block.txs, block.gas_used);
+    }
+    if let Some(Some(latency)) = view.sparkline.back() {
+        let _ = write!(line, ", p50 {:.0?}", latency);
+    }
+    if let Some((error, count)) = metrics.errors.iter().max_by_key(|(_, count)| **count) {
+        let _ = write!(line, ", top error {}x {:?}", count, error);
+    }
+    line
+}
+
+fn sparkline(samples: &VecDeque<Option<Duration>>) -> String {
+    let max = samples.iter().flatten().max().copied().unwrap_or_default().as_secs_f64();
+    samples.iter()
+        .map(|sample| match sample {
+            Some(latency) if max > 0.0 => {
+                let level = (latency.as_secs_f64() / max * (SPARKS.len() - 1) as f64).round() as usize;
+                SPARKS[level.min(SPARKS.len() - 1)]
+            }
+            Some(_) => SPARKS[0],
+            None => ' ',
+        })
+        .collect()
+}
+
+// Groups messages that only differ in numbers, hashes or addresses
+fn error_kind(message: &str) -> String {
+    let mut kind = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '0' && chars.peek() == Some(&'x') {
+            chars.next();
+            while chars.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                chars.next();
+            }
+            kind.push_str("0x…");
+        } else if c.is_ascii_digit() {
+            while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                chars.next();
+            }
+            kind.push('N');
+        } else {
+            kind.push(c);
+        }
+    }
+    kind
+}
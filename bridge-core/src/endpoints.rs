@@ -0,0 +1,376 @@
+use crate::error::{BridgeError, Result};
+use crate::rpc_batch::BatchClient;
+use crate::transport::{self, RpcProvider, TransportKind};
+use clap::{Args, ValueEnum};
+use ethers::providers::MiddlewareError;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// How requests are spread over the endpoints.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Balance {
+    /// Each request goes to the next endpoint in turn
+    RoundRobin,
+    /// Each request goes to the endpoint with the fewest requests in flight
+    LeastInflight,
+    /// Each sender always uses the same endpoint, so its nonces arrive in order
+    #[default]
+    Sticky,
+}
+
+/// `--endpoints` and the balancing and failover flags shared by the load tools.
+#[derive(Args, Debug, Clone, Default)]
+pub struct EndpointArgs {
+    /// Comma-separated RPC endpoints to spread transactions over instead of the single
+    /// RPC endpoint; the first one also serves reads such as nonces and new blocks
+    #[arg(long, value_delimiter = ',', alias = "replay-endpoints")]
+    pub endpoints: Vec<String>,
+
+    /// How transactions are spread over --endpoints
+    #[arg(long, value_enum, default_value_t = Balance::Sticky)]
+    pub balance: Balance,
+
+    /// Drop an endpoint after this many consecutive requests without an answer
+    #[arg(long, default_value_t = 5)]
+    pub max_endpoint_errors: usize,
+
+    /// Try a dropped endpoint again after this many seconds; 0 drops it for good
+    #[arg(long, default_value_t = 30)]
+    pub endpoint_retry_secs: u64,
+}
+
+impl EndpointArgs {
+    /// `--endpoints`, or `default` alone when none were given.
+    pub fn urls(&self, default: &str) -> Vec<String> {
+        if self.endpoints.is_empty() {
+            vec![default.to_string()]
+        } else {
+            self.endpoints.clone()
+        }
+    }
+
+    pub async fn connect(&self, default: &str) -> Result<EndpointPool> {
+        let retry_after = (self.endpoint_retry_secs > 0).then(|| Duration::from_secs(self.endpoint_retry_secs));
+        EndpointPool::connect(&self.urls(default), self.balance, self.max_endpoint_errors.max(1), retry_after).await
+    }
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    consecutive_errors: usize,
+    down_since: Option<Instant>,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    requests: usize,
+    accepted: usize,
+    rejected: usize,
+    failed: usize,
+    latency: Duration,
+    max_inflight: usize,
+    drops: usize,
+}
+
+/// One RPC endpoint of a pool.
+#[derive(Debug)]
+pub struct Endpoint {
+    url: String,
+    provider: Arc<RpcProvider>,
+    /// JSON-RPC batches are only sent over HTTP
+    batch: Option<BatchClient>,
+    inflight: AtomicUsize,
+    health: Mutex<Health>,
+    counters: Mutex<Counters>,
+    max_errors: usize,
+}
+
+impl Endpoint {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn kind(&self) -> TransportKind {
+        transport::transport_kind(&self.provider)
+    }
+
+    pub fn provider(&self) -> &Arc<RpcProvider> {
+        &self.provider
+    }
+
+    pub fn batch(&self) -> Option<&BatchClient> {
+        self.batch.as_ref()
+    }
+
+    fn available(&self, retry_after: Option<Duration>) -> bool {
+        match self.health.lock().unwrap().down_since {
+            None => true,
+            Some(since) => retry_after.is_some_and(|retry| since.elapsed() >= retry),
+        }
+    }
+
+    fn answered(&self) {
+        let mut health = self.health.lock().unwrap();
+        if health.down_since.take().is_some() {
+            println!("Endpoint {} is answering again", self.url);
+        }
+        health.consecutive_errors = 0;
+    }
+
+    fn failed(&self, reason: String) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_errors += 1;
+        // A dropped endpoint on retry is dropped again by its first failure
+        if health.consecutive_errors >= self.max_errors {
+            if health.down_since.is_none() {
+                eprintln!("Dropping endpoint {} after {} consecutive errors: {}",
+                    self.url, health.consecutive_errors, reason);
+                self.counters.lock().unwrap().drops += 1;
+            }
+            health.down_since = Some(Instant::now());
+        }
+        health.last_error = Some(reason);
+    }
+}
+
+/// Requests sent through one endpoint, for the per-endpoint report.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointStats {
+    pub url: String,
+    pub transport: TransportKind,
+    pub requests: usize,
+    /// Transactions (or calls) the node accepted
+    pub accepted: usize,
+    /// Transactions the node answered with a JSON-RPC error
+    pub rejected: usize,
+    /// Requests that got no answer, e.g. connection errors and timeouts
+    pub failed: usize,
+    pub avg_latency_ms: f64,
+    pub max_inflight: usize,
+    /// Times the endpoint was dropped for failing
+    pub drops: usize,
+    pub healthy: bool,
+    pub last_error: Option<String>,
+}
+
+impl fmt::Display for EndpointStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requests, {} accepted, {} rejected, {} failed, avg latency {:.2} ms, max {} in flight",
+            self.requests, self.accepted, self.rejected, self.failed, self.avg_latency_ms, self.max_inflight
+        )?;
+        if self.drops > 0 {
+            write!(f, ", dropped {} times", self.drops)?;
+        }
+        if !self.healthy {
+            write!(f, ", DOWN")?;
+        }
+        Ok(())
+    }
+}
+
+/// Spreads requests over several endpoints of the same chain and drops the ones that
+/// stop answering, trying them again after a while.
+#[derive(Debug)]
+pub struct EndpointPool {
+    endpoints: Vec<Arc<Endpoint>>,
+    balance: Balance,
+    retry_after: Option<Duration>,
+    next: AtomicUsize,
+}
+
+impl EndpointPool {
+    pub async fn connect(
+        urls: &[String],
+        balance: Balance,
+        max_errors: usize,
+        retry_after: Option<Duration>,
+    ) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(BridgeError::InvalidConfig {
+                field: "endpoints".to_string(),
+                reason: "at least one endpoint is required".to_string(),
+            });
+        }
+        let mut endpoints = Vec::with_capacity(urls.len());
+        for url in urls {
+            let provider = Arc::new(transport::connect(url).await?);
+            let batch = (transport::transport_kind(&provider) == TransportKind::Http)
+                .then(|| BatchClient::new(url.clone()));
+            endpoints.push(Arc::new(Endpoint {
+                url: url.clone(),
+                provider,
+                batch,
+                inflight: AtomicUsize::new(0),
+                health: Mutex::new(Health::default()),
+                counters: Mutex::new(Counters::default()),
+                max_errors,
+            }));
+        }
+        Ok(EndpointPool { endpoints, balance, retry_after, next: AtomicUsize::new(0) })
+    }
+
+    pub fn endpoints(&self) -> &[Arc<Endpoint>] {
+        &self.endpoints
+    }
+
+    /// The first endpoint, used for reads that need a single view of the chain.
+    pub fn primary(&self) -> &Arc<Endpoint> {
+        &self.endpoints[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// Picks an endpoint for one request from sender number `sender`.
+    pub fn acquire(&self, sender: usize) -> Result<Lease> {
+        Ok(self.lease(self.pick(sender)?, 1))
+    }
+
+    /// Assigns a batch of `(sender, item)` pairs to endpoints: per sender with sticky
+    /// balancing, otherwise all to one endpoint. Each lease counts its items in flight.
+    pub fn split<T>(&self, items: Vec<(usize, T)>) -> Result<Vec<(Lease, Vec<T>)>> {
+        let mut groups: BTreeMap<usize, Vec<T>> = BTreeMap::new();
+        if self.balance == Balance::Sticky {
+            for (sender, item) in items {
+                groups.entry(self.pick(sender)?).or_default().push(item);
+            }
+        } else if !items.is_empty() {
+            let index = self.pick(0)?;
+            groups.insert(index, items.into_iter().map(|(_, item)| item).collect());
+        }
+        Ok(groups.into_iter()
+            .map(|(index, items)| (self.lease(index, items.len()), items))
+            .collect())
+    }
+
+    pub fn stats(&self) -> Vec<EndpointStats> {
+        self.endpoints.iter()
+            .map(|endpoint| {
+                let counters = endpoint.counters.lock().unwrap();
+                let health = endpoint.health.lock().unwrap();
+                EndpointStats {
+                    url: endpoint.url.clone(),
+                    transport: endpoint.kind(),
+                    requests: counters.requests,
+                    accepted: counters.accepted,
+                    rejected: counters.rejected,
+                    failed: counters.failed,
+                    avg_latency_ms: if counters.requests > 0 {
+                        counters.latency.as_secs_f64() * 1000.0 / counters.requests as f64
+                    } else {
+                        0.0
+                    },
+                    max_inflight: counters.max_inflight,
+                    drops: counters.drops,
+                    healthy: health.down_since.is_none(),
+                    last_error: health.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+
+    fn pick(&self, sender: usize) -> Result<usize> {
+        let count = self.endpoints.len();
+        let available = |index: &usize| self.endpoints[*index].available(self.retry_after);
+        let start = match self.balance {
+            Balance::Sticky => sender % count,
+            Balance::RoundRobin | Balance::LeastInflight => self.next.fetch_add(1, Ordering::Relaxed) % count,
+        };
+        // Walk on from the preferred endpoint, so a dropped endpoint only moves its own senders
+        let mut candidates = (start..start + count).map(|i| i % count).filter(available);
+        let picked = match self.balance {
+            Balance::LeastInflight => candidates
+                .min_by_key(|index| self.endpoints[*index].inflight.load(Ordering::Relaxed)),
+            Balance::RoundRobin | Balance::Sticky => candidates.next(),
+        };
+        picked.ok_or(BridgeError::EndpointsDown(count))
+    }
+
+    fn lease(&self, index: usize, requests: usize) -> Lease {
+        let endpoint = self.endpoints[index].clone();
+        let inflight = endpoint.inflight.fetch_add(requests, Ordering::Relaxed) + requests;
+        let mut counters = endpoint.counters.lock().unwrap();
+        counters.max_inflight = counters.max_inflight.max(inflight);
+        drop(counters);
+        Lease { endpoint, requests, started: Instant::now() }
+    }
+}
+
+/// An endpoint picked for one request; the request counts as in flight until the
+/// lease is dropped. Record its result with `answered`, `failed` or `record`.
+#[derive(Debug)]
+pub struct Lease {
+    endpoint: Arc<Endpoint>,
+    requests: usize,
+    started: Instant,
+}
+
+impl Lease {
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    pub fn provider(&self) -> &Arc<RpcProvider> {
+        &self.endpoint.provider
+    }
+
+    /// Batch client of an HTTP endpoint.
+    pub fn batch(&self) -> Option<&BatchClient> {
+        self.endpoint.batch.as_ref()
+    }
+
+    /// The node answered, accepting `accepted` and rejecting `rejected` transactions.
+    pub fn answered(self, accepted: usize, rejected: usize) {
+        self.count(|counters| {
+            counters.accepted += accepted;
+            counters.rejected += rejected;
+        });
+        self.endpoint.answered();
+    }
+
+    /// The request got no answer; enough of these in a row drop the endpoint.
+    pub fn failed(self, reason: impl fmt::Display) {
+        self.count(|counters| counters.failed += 1);
+        self.endpoint.failed(reason.to_string());
+    }
+
+    /// Records the result of a single send. A JSON-RPC error response means the node
+    /// rejected the transaction, which says nothing against the endpoint.
+    pub fn record<T, E: MiddlewareError>(self, result: &std::result::Result<T, E>) {
+        match result {
+            Ok(_) => self.answered(1, 0),
+            Err(e) if e.as_error_response().is_some() => self.answered(0, 1),
+            Err(e) => self.failed(e),
+        }
+    }
+
+    fn count(&self, update: impl FnOnce(&mut Counters)) {
+        let mut counters = self.endpoint.counters.lock().unwrap();
+        counters.requests += 1;
+        counters.latency += self.started.elapsed();
+        update(&mut counters);
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.endpoint.inflight.fetch_sub(self.requests, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,62 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BridgeError {
+    #[error("{0} not set in .env")]
+    MissingEnv(String),
+
+    #[error("Invalid {field}: {reason}")]
+    InvalidConfig { field: String, reason: String },
+
+    #[error("Node {0} is not configured")]
+    UnknownNode(String),
+
+    #[error("{path}: invalid {field}: {reason}")]
+    Topology { path: String, field: String, reason: String },
+
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse {path}: {source}")]
+    Json {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("No {list} account {index} in {path}")]
+    MissingAccount { list: &'static str, index: usize, path: String },
+
+    #[error("Failed to parse address {address}: {reason}")]
+    Address { address: String, reason: String },
+
+    #[error("Wallet error: {0}")]
+    Wallet(#[from] ethers::signers::WalletError),
+
+    #[error("ABI error: {0}")]
+    Abi(#[from] ethers::abi::Error),
+
+    #[error("Provider error: {0}")]
+    Provider(#[from] ethers::providers::ProviderError),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Failed to connect to {endpoint}: {reason}")]
+    Connect { endpoint: String, reason: String },
+
+    #[error("All {0} RPC endpoints are down")]
+    EndpointsDown(usize),
+
+    #[error("RPC error: {0}")]
+    Rpc(String),
+
+    #[error("No nonce tracked for {0:?}")]
+    UntrackedSender(ethers::types::Address),
+}
+
+pub type Result<T> = std::result::Result<T, BridgeError>;
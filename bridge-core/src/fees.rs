@@ -0,0 +1,215 @@
+use crate::error::{BridgeError, Result};
+use clap::{Args, ValueEnum};
+use ethers::{
+    providers::Middleware,
+    types::{
+        transaction::{eip2718::TypedTransaction, eip2930::{AccessList, Eip2930TransactionRequest}},
+        Eip1559TransactionRequest, Transaction, TransactionRequest, U256,
+    },
+    utils::parse_units,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs};
+
+/// Transaction envelope to send.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxType {
+    /// Type 0, fixed gas price
+    #[default]
+    Legacy,
+    /// Type 1, fixed gas price plus access list
+    Eip2930,
+    /// Type 2, maxFeePerGas/maxPriorityFeePerGas plus access list
+    Eip1559,
+}
+
+/// `--tx-type` and fee flags shared by the load tools.
+#[derive(Args, Debug, Clone, Default)]
+pub struct FeeArgs {
+    /// Transaction type to send
+    #[arg(long, value_enum, default_value_t = TxType::Legacy, global = true)]
+    pub tx_type: TxType,
+
+    /// Gas price for legacy and EIP-2930 transactions
+    #[arg(long, global = true)]
+    pub gas_price_gwei: Option<f64>,
+
+    /// maxFeePerGas for EIP-1559 transactions; derived from eth_feeHistory if unset
+    #[arg(long, global = true)]
+    pub max_fee_gwei: Option<f64>,
+
+    /// maxPriorityFeePerGas for EIP-1559 transactions; derived from eth_feeHistory if unset
+    #[arg(long, global = true)]
+    pub priority_fee_gwei: Option<f64>,
+
+    /// JSON access list (`[{"address": ..., "storageKeys": [...]}]`) for EIP-2930 and EIP-1559
+    #[arg(long, global = true)]
+    pub access_list: Option<String>,
+}
+
+/// Resolved fees for one run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fees {
+    pub tx_type: TxType,
+    /// Legacy and EIP-2930 only
+    pub gas_price: U256,
+    /// EIP-1559 only
+    pub max_fee: U256,
+    pub priority_fee: U256,
+    pub access_list: AccessList,
+}
+
+impl FeeArgs {
+    /// Fills in unset fees. Legacy and EIP-2930 fall back to `default_gas_price`, or
+    /// `eth_gasPrice` when the tool has none. EIP-1559 fees come from `eth_feeHistory`,
+    /// except that a zero default (tools written for zero-fee chains) keeps them at zero.
+    pub async fn resolve<M: Middleware>(&self, client: &M, default_gas_price: Option<U256>) -> Result<Fees> {
+        let access_list = match &self.access_list {
+            Some(path) => {
+                let content = fs::read_to_string(path)
+                    .map_err(|source| BridgeError::Io { path: path.clone(), source })?;
+                serde_json::from_str(&content)
+                    .map_err(|source| BridgeError::Json { path: path.clone(), source })?
+            }
+            None => AccessList::default(),
+        };
+        if self.tx_type == TxType::Legacy && !access_list.0.is_empty() {
+            return Err(BridgeError::InvalidConfig {
+                field: "access-list".to_string(),
+                reason: "legacy transactions cannot carry an access list".to_string(),
+            });
+        }
+
+        let mut fees = Fees { tx_type: self.tx_type, access_list, ..Fees::default() };
+        match self.tx_type {
+            TxType::Legacy | TxType::Eip2930 => {
+                fees.gas_price = match (gwei(self.gas_price_gwei, "gas-price-gwei")?, default_gas_price) {
+                    (Some(price), _) | (None, Some(price)) => price,
+                    (None, None) => client.get_gas_price().await
+                        .map_err(|e| BridgeError::Rpc(format!("eth_gasPrice failed: {}", e)))?,
+                };
+            }
+            TxType::Eip1559 => {
+                let max_fee = gwei(self.max_fee_gwei, "max-fee-gwei")?;
+                let priority_fee = gwei(self.priority_fee_gwei, "priority-fee-gwei")?;
+                let (estimated_max, estimated_priority) = match (max_fee, priority_fee, default_gas_price) {
+                    (Some(_), Some(_), _) => (U256::zero(), U256::zero()),
+                    (_, _, Some(price)) if price.is_zero() => (U256::zero(), U256::zero()),
+                    _ => client.estimate_eip1559_fees(None).await
+                        .map_err(|e| BridgeError::Rpc(format!("eth_feeHistory failed: {}", e)))?,
+                };
+                fees.priority_fee = priority_fee.unwrap_or(estimated_priority);
+                fees.max_fee = max_fee.unwrap_or(estimated_max).max(fees.priority_fee);
+            }
+        }
+        Ok(fees)
+    }
+}
+
+impl Fees {
+    /// Fees and access list of a transaction seen on the node, e.g. to replace it.
+    pub fn of(tx: &Transaction) -> Self {
+        let tx_type = match tx.transaction_type.map(|kind| kind.as_u64()) {
+            Some(2) => TxType::Eip1559,
+            Some(1) => TxType::Eip2930,
+            _ => TxType::Legacy,
+        };
+        Fees {
+            tx_type,
+            gas_price: tx.gas_price.unwrap_or_default(),
+            max_fee: tx.max_fee_per_gas.unwrap_or_default(),
+            priority_fee: tx.max_priority_fee_per_gas.unwrap_or_default(),
+            access_list: tx.access_list.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Converts `request`, built with legacy-style fields, into the configured type.
+    /// Any gas price already on `request` is replaced.
+    pub fn typed(&self, request: TransactionRequest) -> TypedTransaction {
+        match self.tx_type {
+            TxType::Legacy => request.gas_price(self.gas_price).into(),
+            TxType::Eip2930 => {
+                Eip2930TransactionRequest::new(request.gas_price(self.gas_price), self.access_list.clone()).into()
+            }
+            TxType::Eip1559 => Eip1559TransactionRequest {
+                from: request.from,
+                to: request.to,
+                gas: request.gas,
+                value: request.value,
+                data: request.data,
+                nonce: request.nonce,
+                access_list: self.access_list.clone(),
+                max_priority_fee_per_gas: Some(self.priority_fee),
+                max_fee_per_gas: Some(self.max_fee),
+                chain_id: request.chain_id,
+            }
+            .into(),
+        }
+    }
+
+    /// Re-types an already built transaction, such as a contract call's `tx`.
+    pub fn apply(&self, tx: &TypedTransaction) -> TypedTransaction {
+        let request = TransactionRequest {
+            from: tx.from().copied(),
+            to: tx.to().cloned(),
+            gas: tx.gas().copied(),
+            value: tx.value().copied(),
+            data: tx.data().cloned(),
+            nonce: tx.nonce().copied(),
+            chain_id: tx.chain_id(),
+            ..TransactionRequest::default()
+        };
+        self.typed(request)
+    }
+
+    /// Highest price per gas a transaction can pay, for balance checks.
+    pub fn max_price_per_gas(&self) -> U256 {
+        match self.tx_type {
+            TxType::Legacy | TxType::Eip2930 => self.gas_price,
+            TxType::Eip1559 => self.max_fee,
+        }
+    }
+
+    /// The same fees raised by `percent`, rounded up, e.g. to order a batch or replace
+    /// a transaction.
+    pub fn bumped(&self, percent: u64) -> Self {
+        let bump = |value: U256| value + (value * U256::from(percent) + U256::from(99)) / U256::from(100);
+        Fees {
+            gas_price: bump(self.gas_price),
+            max_fee: bump(self.max_fee),
+            priority_fee: bump(self.priority_fee),
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for Fees {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tx_type {
+            TxType::Legacy => write!(f, "legacy, gas price {} wei", self.gas_price)?,
+            TxType::Eip2930 => write!(f, "EIP-2930, gas price {} wei", self.gas_price)?,
+            TxType::Eip1559 => write!(
+                f,
+                "EIP-1559, max fee {} wei, priority fee {} wei",
+                self.max_fee, self.priority_fee
+            )?,
+        }
+        if !self.access_list.0.is_empty() {
+            write!(f, ", {} access list entries", self.access_list.0.len())?;
+        }
+        Ok(())
+    }
+}
+
+fn gwei(value: Option<f64>, field: &str) -> Result<Option<U256>> {
+    value
+        .map(|gwei| {
+            parse_units(gwei, "gwei").map(Into::into).map_err(|e| BridgeError::InvalidConfig {
+                field: field.to_string(),
+                reason: e.to_string(),
+            })
+        })
+        .transpose()
+}
@@ -0,0 +1,106 @@
+use crate::error::Result;
+use crate::transport::{transport_kind, RpcProvider};
+use ethers::providers::{Middleware, StreamExt};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::{oneshot, watch},
+    task::JoinHandle,
+    time,
+};
+
+/// Follows the chain head: subscribes to `newHeads` when the transport allows it,
+/// and polls `eth_blockNumber` otherwise or once the subscription ends.
+#[derive(Debug)]
+pub struct HeadWatcher {
+    heads: watch::Receiver<u64>,
+    follower: JoinHandle<()>,
+    subscribed: bool,
+}
+
+impl HeadWatcher {
+    /// Starts following from the current head.
+    pub async fn start(provider: Arc<RpcProvider>, poll_interval: Duration) -> Result<Self> {
+        let head = provider.get_block_number().await?.as_u64();
+        let (sender, heads) = watch::channel(head);
+        let (ready, subscribed) = oneshot::channel();
+        let follower = tokio::spawn(follow(provider, sender, poll_interval, ready));
+
+        Ok(HeadWatcher {
+            heads,
+            follower,
+            subscribed: subscribed.await.unwrap_or(false),
+        })
+    }
+
+    /// Latest head seen.
+    pub fn head(&self) -> u64 {
+        *self.heads.borrow()
+    }
+
+    /// Waits for a head above the one last returned (or the start head), skipping
+    /// any in between.
+    pub async fn next(&mut self) -> u64 {
+        if self.heads.changed().await.is_err() {
+            // The follower is gone; behave like a slow poll rather than spin
+            time::sleep(Duration::from_secs(1)).await;
+        }
+        *self.heads.borrow_and_update()
+    }
+
+    /// Whether heads arrive over a `newHeads` subscription rather than polling.
+    pub fn subscribed(&self) -> bool {
+        self.subscribed
+    }
+}
+
+impl Drop for HeadWatcher {
+    fn drop(&mut self) {
+        self.follower.abort();
+    }
+}
+
+async fn follow(
+    provider: Arc<RpcProvider>,
+    heads: watch::Sender<u64>,
+    poll_interval: Duration,
+    ready: oneshot::Sender<bool>,
+) {
+    let advance = |number: u64| {
+        heads.send_if_modified(|head| {
+            let higher = number > *head;
+            if higher {
+                *head = number;
+            }
+            higher
+        });
+    };
+
+    let mut ready = Some(ready);
+    if transport_kind(&provider).supports_pubsub() {
+        match provider.subscribe_blocks().await {
+            Ok(mut blocks) => {
+                if let Some(ready) = ready.take() {
+                    let _ = ready.send(true);
+                }
+                while let Some(block) = blocks.next().await {
+                    if let Some(number) = block.number {
+                        advance(number.as_u64());
+                    }
+                }
+                eprintln!("newHeads subscription ended, polling for blocks instead");
+            }
+            Err(e) => eprintln!("Failed to subscribe to newHeads ({}), polling for blocks instead", e),
+        }
+    }
+    if let Some(ready) = ready.take() {
+        let _ = ready.send(false);
+    }
+
+    while !heads.is_closed() {
+        time::sleep(poll_interval).await;
+        match provider.get_block_number().await {
+            Ok(number) => advance(number.as_u64()),
+            Err(e) => eprintln!("Failed to get block number: {}", e),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Shared building blocks for the reth scaling tools: node configuration,
+//! account files, typed MonetSmartContract bindings and a common error type.
+
+pub mod accounts;
+pub mod bindings;
+pub mod cli;
+pub mod contract;
+pub mod dashboard;
+pub mod endpoints;
+pub mod error;
+pub mod fees;
+pub mod heads;
+pub mod metrics;
+pub mod node;
+pub mod nonce;
+pub mod records;
+pub mod replace;
+pub mod rpc_batch;
+pub mod topology;
+pub mod transport;
+pub mod txpool;
+
+pub use accounts::{parse_address, Account, AccountStore};
+pub use bindings::{MonetSmartContract, MonetSmartContractCalls, MonetSmartContractEvents};
+pub use cli::{NodeArgs, TopologyArgs};
+pub use contract::{monet_abi, monet_contract};
+pub use dashboard::{Dashboard, DashboardHandle};
+pub use endpoints::{Balance, Endpoint, EndpointArgs, EndpointPool, EndpointStats, Lease};
+pub use error::{BridgeError, Result};
+pub use fees::{FeeArgs, Fees, TxType};
+pub use heads::HeadWatcher;
+pub use metrics::MetricsArgs;
+pub use node::{NodeConfig, NodeRegistry};
+pub use nonce::{NonceManager, NonceStats, StuckSender};
+pub use records::{Recorder, Run, RunArgs, TxRecord, TxStatus};
+pub use replace::{ReplaceOutcome, PoolEntry, PoolTx, ReplaceMode, Replacement};
+pub use rpc_batch::{BatchClient, BatchResponse, RpcError};
+pub use topology::Topology;
+pub use transport::{connect, transport_kind, RpcProvider, Transport, TransportError, TransportKind};
+pub use txpool::{TxpoolArgs, TxpoolSampler, TxpoolSummary};
@@ -0,0 +1,308 @@
+use crate::error::{BridgeError, Result};
+use clap::Args;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    net::SocketAddr,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+// Upper bounds in seconds of the latency histogram buckets
+const RPC_BUCKETS: &[f64] = &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+const INCLUSION_BUCKETS: &[f64] = &[0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 15.0, 30.0, 60.0, 120.0, 300.0];
+
+// Largest request head read before answering a scrape
+const MAX_REQUEST: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Counter,
+    Histogram(&'static [f64]),
+}
+
+#[derive(Debug)]
+struct Metric {
+    name: &'static str,
+    help: &'static str,
+    kind: Kind,
+}
+
+const SUBMITTED: Metric = Metric {
+    name: "loadtest_transactions_submitted_total",
+    help: "Transactions handed to an RPC endpoint",
+    kind: Kind::Counter,
+};
+const ACCEPTED: Metric = Metric {
+    name: "loadtest_transactions_accepted_total",
+    help: "Transactions the RPC endpoint accepted",
+    kind: Kind::Counter,
+};
+const REJECTED: Metric = Metric {
+    name: "loadtest_transactions_rejected_total",
+    help: "Transactions the RPC endpoint rejected or that could not be sent",
+    kind: Kind::Counter,
+};
+const INCLUDED: Metric = Metric {
+    name: "loadtest_transactions_included_total",
+    help: "Submitted transactions seen in a block",
+    kind: Kind::Counter,
+};
+const INCLUSION_LATENCY: Metric = Metric {
+    name: "loadtest_inclusion_latency_seconds",
+    help: "Time from submission to inclusion in a block",
+    kind: Kind::Histogram(INCLUSION_BUCKETS),
+};
+const RPC_DURATION: Metric = Metric {
+    name: "loadtest_rpc_request_duration_seconds",
+    help: "JSON-RPC call latency by method",
+    kind: Kind::Histogram(RPC_BUCKETS),
+};
+const RPC_ERRORS: Metric = Metric {
+    name: "loadtest_rpc_errors_total",
+    help: "JSON-RPC calls that failed, by method",
+    kind: Kind::Counter,
+};
+const NONCE_RESYNCS: Metric = Metric {
+    name: "loadtest_nonce_resyncs_total",
+    help: "Nonce repairs: re-reads from the node, re-issued and filled rejected nonces",
+    kind: Kind::Counter,
+};
+const CROSSCHAIN_MESSAGES: Metric = Metric {
+    name: "loadtest_crosschain_messages_total",
+    help: "Cross-chain messages by source and destination chain, and outcome",
+    kind: Kind::Counter,
+};
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug)]
+enum Series {
+    Counter(f64),
+    Histogram { buckets: Vec<u64>, sum: f64, count: u64 },
+}
+
+#[derive(Debug)]
+struct Family {
+    help: &'static str,
+    kind: Kind,
+    series: BTreeMap<Labels, Series>,
+}
+
+#[derive(Debug)]
+struct Registry {
+    /// Labels put on every series, such as the node and workload
+    labels: Vec<(String, String)>,
+    families: Mutex<BTreeMap<&'static str, Family>>,
+}
+
+// Set once the endpoint is served; until then recording is a no-op
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// `--metrics-addr` flag of the load tools.
+#[derive(Args, Debug, Clone, Default)]
+pub struct MetricsArgs {
+    /// Serve Prometheus metrics at http://ADDR/metrics while running (e.g. 0.0.0.0:9100)
+    #[arg(long, global = true)]
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+impl MetricsArgs {
+    /// Starts the metrics endpoint when `--metrics-addr` is given, putting `labels`
+    /// on every series. Must be called inside a Tokio runtime, which serves the scrapes.
+    pub async fn serve(&self, labels: &[(&str, &str)]) -> Result<()> {
+        match self.metrics_addr {
+            Some(addr) => serve(addr, labels).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Binds `addr` and answers every HTTP request on it with the metrics in the
+/// Prometheus text format. Only the first call registers `labels`.
+pub async fn serve(addr: SocketAddr, labels: &[(&str, &str)]) -> Result<()> {
+    let listener = TcpListener::bind(addr).await
+        .map_err(|e| BridgeError::Io { path: addr.to_string(), source: e })?;
+    REGISTRY.get_or_init(|| Registry {
+        labels: labels.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+        families: Mutex::new(BTreeMap::new()),
+    });
+    println!("Metrics: http://{}/metrics", addr);
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(answer(stream));
+                }
+                Err(e) => eprintln!("Metrics endpoint failed to accept a connection: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn answer(mut stream: TcpStream) {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+    let body = render();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// `count` transactions were handed to an endpoint.
+pub fn submitted(count: usize) {
+    add(&SUBMITTED, Vec::new(), count as f64);
+}
+
+/// `count` transactions were accepted by the endpoint.
+pub fn accepted(count: usize) {
+    add(&ACCEPTED, Vec::new(), count as f64);
+}
+
+/// `count` transactions were rejected or could not be sent.
+pub fn rejected(count: usize) {
+    add(&REJECTED, Vec::new(), count as f64);
+}
+
+/// A submitted transaction was included, `latency` after it was sent.
+pub fn included(latency: Duration) {
+    add(&INCLUDED, Vec::new(), 1.0);
+    observe(&INCLUSION_LATENCY, Vec::new(), latency);
+}
+
+/// A JSON-RPC call to `method` took `elapsed`.
+pub fn rpc_call(method: &str, elapsed: Duration, ok: bool) {
+    if REGISTRY.get().is_none() {
+        return;
+    }
+    observe(&RPC_DURATION, vec![("method", method.to_string())], elapsed);
+    if !ok {
+        add(&RPC_ERRORS, vec![("method", method.to_string())], 1.0);
+    }
+}
+
+/// A nonce was repaired: `reason` is `resync`, `reissued` or `filled`.
+pub fn nonce_resync(reason: &'static str, count: usize) {
+    add(&NONCE_RESYNCS, vec![("reason", reason.to_string())], count as f64);
+}
+
+/// A cross-chain message from `source` to `destination` reached `status`
+/// (`sent`, `rejected`, `succeeded` or `reverted`).
+pub fn crosschain_message(source: u64, destination: u64, status: &'static str) {
+    add(&CROSSCHAIN_MESSAGES, vec![
+        ("source_chain", source.to_string()),
+        ("destination_chain", destination.to_string()),
+        ("status", status.to_string()),
+    ], 1.0);
+}
+
+fn add(metric: &Metric, labels: Labels, by: f64) {
+    if by == 0.0 {
+        return;
+    }
+    record(metric, labels, |series| {
+        if let Series::Counter(value) = series {
+            *value += by;
+        }
+    });
+}
+
+fn observe(metric: &Metric, labels: Labels, value: Duration) {
+    let Kind::Histogram(bounds) = metric.kind else { return };
+    let secs = value.as_secs_f64();
+    record(metric, labels, |series| {
+        if let Series::Histogram { buckets, sum, count } = series {
+            if let Some(bucket) = bounds.iter().position(|bound| secs <= *bound) {
+                buckets[bucket] += 1;
+            }
+            *sum += secs;
+            *count += 1;
+        }
+    });
+}
+
+fn record(metric: &Metric, labels: Labels, update: impl FnOnce(&mut Series)) {
+    let Some(registry) = REGISTRY.get() else { return };
+    let mut families = registry.families.lock().unwrap();
+    let family = families.entry(metric.name).or_insert_with(|| Family {
+        help: metric.help,
+        kind: metric.kind,
+        series: BTreeMap::new(),
+    });
+    let series = family.series.entry(labels).or_insert_with(|| match metric.kind {
+        Kind::Counter => Series::Counter(0.0),
+        Kind::Histogram(bounds) => Series::Histogram { buckets: vec![0; bounds.len()], sum: 0.0, count: 0 },
+    });
+    update(series);
+}
+
+/// Everything recorded so far, in the Prometheus text exposition format.
+pub fn render() -> String {
+    let Some(registry) = REGISTRY.get() else { return String::new() };
+    let families = registry.families.lock().unwrap();
+    let mut out = String::new();
+    for (name, family) in families.iter() {
+        let kind = match family.kind {
+            Kind::Counter => "counter",
+            Kind::Histogram(_) => "histogram",
+        };
+        let _ = writeln!(out, "# HELP {} {}", name, family.help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, series) in &family.series {
+            let labels: Vec<(&str, &str)> = registry.labels.iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .chain(labels.iter().map(|(name, value)| (*name, value.as_str())))
+                .collect();
+            match series {
+                Series::Counter(value) => {
+                    let _ = writeln!(out, "{}{} {}", name, label_set(&labels, None), value);
+                }
+                Series::Histogram { buckets, sum, count } => {
+                    let Kind::Histogram(bounds) = family.kind else { continue };
+                    let mut cumulative = 0;
+                    for (bound, n) in bounds.iter().zip(buckets) {
+                        cumulative += n;
+                        let _ = writeln!(out, "{}_bucket{} {}", name, label_set(&labels, Some(&bound.to_string())), cumulative);
+                    }
+                    let _ = writeln!(out, "{}_bucket{} {}", name, label_set(&labels, Some("+Inf")), count);
+                    let _ = writeln!(out, "{}_sum{} {}", name, label_set(&labels, None), sum);
+                    let _ = writeln!(out, "{}_count{} {}", name, label_set(&labels, None), count);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn label_set(labels: &[(&str, &str)], le: Option<&str>) -> String {
+    let pairs: Vec<String> = labels.iter()
+        .map(|(name, value)| (*name, *value))
+        .chain(le.map(|le| ("le", le)))
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,162 @@
+use crate::accounts::{parse_address, AccountStore};
+use crate::error::{BridgeError, Result};
+use crate::heads::HeadWatcher;
+use crate::topology::Topology;
+use crate::transport::{self, transport_kind, RpcProvider};
+use ethers::types::Address;
+use std::{env, path::Path, sync::Arc, time::Duration};
+
+/// Connection details for one chain, from a topology file or `NODE{n}_*` variables.
+#[derive(Debug, Clone)]
+pub struct NodeConfig {
+    /// 1-based position in the topology, or `n` in `NODE{n}_*`
+    pub index: usize,
+    pub name: String,
+    pub chain_id: u32,
+    pub rpc_url: String,
+    pub ws_url: Option<String>,
+    pub contract: Address,
+    pub relayer_keys: Vec<String>,
+    pub account_file: String,
+}
+
+impl NodeConfig {
+    pub fn from_env(index: usize) -> Result<Self> {
+        let chain_var = format!("NODE{}_CHAINID", index);
+        let chain_id = required_env(&chain_var)?
+            .parse()
+            .map_err(|_| invalid(&chain_var, "chain ID must be a u32"))?;
+
+        let contract_var = format!("NODE{}_CONTRACT", index);
+        let contract = parse_address(&required_env(&contract_var)?)
+            .map_err(|e| invalid(&contract_var, &e.to_string()))?;
+
+        let rpc_url = required_env(&format!("NODE{}_RPC", index))?;
+        let ws_url = env::var(format!("NODE{}_WS", index)).ok();
+
+        // A node-specific relayer key takes precedence over the shared one
+        let relayer_keys = env::var(format!("NODE{}_RELAYER_KEY", index))
+            .or_else(|_| env::var("RELAYER_KEY"))
+            .into_iter()
+            .collect();
+
+        Ok(NodeConfig {
+            index,
+            name: format!("node{}", index),
+            chain_id,
+            rpc_url,
+            ws_url,
+            contract,
+            relayer_keys,
+            account_file: AccountStore::node_file(index),
+        })
+    }
+
+    /// Connects to the RPC endpoint over HTTP, WebSocket or IPC as its URL says.
+    pub async fn provider(&self) -> Result<RpcProvider> {
+        transport::connect(&self.rpc_url).await
+    }
+
+    /// Provider for subscriptions: `provider` itself unless it is HTTP, in which case
+    /// `ws_url` is connected if set. `None` means the caller has to poll.
+    pub async fn pubsub(&self, provider: &Arc<RpcProvider>) -> Result<Option<Arc<RpcProvider>>> {
+        if transport_kind(provider).supports_pubsub() {
+            return Ok(Some(provider.clone()));
+        }
+        match &self.ws_url {
+            Some(url) => Ok(Some(Arc::new(transport::connect(url).await?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Follows the chain head over `pubsub`'s provider, polling `provider` every
+    /// `poll_interval` when there is none.
+    pub async fn watch_heads(&self, provider: &Arc<RpcProvider>, poll_interval: Duration) -> Result<HeadWatcher> {
+        let provider = self.pubsub(provider).await?.unwrap_or_else(|| provider.clone());
+        HeadWatcher::start(provider, poll_interval).await
+    }
+
+    /// First configured relayer key, if any.
+    pub fn relayer_key(&self) -> Option<&str> {
+        self.relayer_keys.first().map(String::as_str)
+    }
+
+    /// Sender/receiver accounts from the node's account file.
+    pub fn accounts(&self) -> Result<AccountStore> {
+        AccountStore::load(&self.account_file)
+    }
+
+    /// Whether `selector` names this node, either by name or by number.
+    pub fn matches(&self, selector: &str) -> bool {
+        self.name == selector || selector.parse::<usize>().is_ok_and(|n| n == self.index)
+    }
+}
+
+/// All nodes configured in the environment, ordered by node number.
+#[derive(Debug, Clone, Default)]
+pub struct NodeRegistry {
+    nodes: Vec<NodeConfig>,
+}
+
+impl NodeRegistry {
+    /// Loads `topology` if given, otherwise falls back to `NODE{n}_*` variables.
+    pub fn load(topology: Option<&Path>) -> Result<Self> {
+        match topology {
+            Some(path) => Self::from_topology(path),
+            None => Self::from_env(),
+        }
+    }
+
+    pub fn from_topology(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let nodes = Topology::load(path)?.into_nodes(&path.display().to_string())?;
+        Ok(NodeRegistry { nodes })
+    }
+
+    /// Loads every node that has a `NODE{n}_RPC` variable.
+    pub fn from_env() -> Result<Self> {
+        let mut indexes: Vec<usize> = env::vars()
+            .filter_map(|(key, _)| {
+                key.strip_prefix("NODE")?
+                    .strip_suffix("_RPC")?
+                    .parse()
+                    .ok()
+            })
+            .collect();
+        indexes.sort_unstable();
+
+        let nodes = indexes.into_iter()
+            .map(NodeConfig::from_env)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(NodeRegistry { nodes })
+    }
+
+    pub fn nodes(&self) -> &[NodeConfig] {
+        &self.nodes
+    }
+
+    pub fn node(&self, index: usize) -> Result<&NodeConfig> {
+        self.nodes.iter()
+            .find(|n| n.index == index)
+            .ok_or_else(|| BridgeError::UnknownNode(index.to_string()))
+    }
+
+    /// Looks a node up by name or number.
+    pub fn select(&self, selector: &str) -> Result<&NodeConfig> {
+        self.nodes.iter()
+            .find(|n| n.matches(selector))
+            .ok_or_else(|| BridgeError::UnknownNode(selector.to_string()))
+    }
+
+    pub fn by_chain_id(&self, chain_id: u32) -> Option<&NodeConfig> {
+        self.nodes.iter().find(|n| n.chain_id == chain_id)
+    }
+}
+
+fn required_env(var: &str) -> Result<String> {
+    env::var(var).map_err(|_| BridgeError::MissingEnv(var.to_string()))
+}
+
+fn invalid(field: &str, reason: &str) -> BridgeError {
+    BridgeError::InvalidConfig { field: field.to_string(), reason: reason.to_string() }
+}
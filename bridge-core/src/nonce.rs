@@ -0,0 +1,238 @@
+use crate::error::{BridgeError, Result};
+use crate::fees::Fees;
+use crate::metrics;
+use ethers::{
+    providers::Middleware,
+    signers::{LocalWallet, Signer},
+    types::{Address, BlockNumber, TransactionRequest, H256},
+    utils::keccak256,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// Rejection reasons meaning the nonce is already taken, so there is no gap to repair
+const NONCE_USED: &[&str] = &["nonce too low", "already known", "known transaction", "replacement transaction underpriced"];
+
+#[derive(Debug)]
+struct SenderNonces {
+    /// Lowest nonce never handed out
+    next: u64,
+    /// Handed-out nonces whose submission was rejected; issued again before `next`
+    gaps: BTreeSet<u64>,
+    /// Mined nonce count last seen, and when it last moved
+    mined: u64,
+    progressed_at: Instant,
+}
+
+/// A sender whose mined nonce has stopped short of the nonces handed out.
+#[derive(Debug, Clone, Serialize)]
+pub struct StuckSender {
+    pub address: Address,
+    /// Nonce count in the latest block
+    pub mined: u64,
+    /// Nonces handed out so far
+    pub issued: u64,
+    /// Rejected nonces not yet re-issued or filled
+    pub gaps: Vec<u64>,
+    pub stalled_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct NonceStats {
+    /// Rejected nonces handed out again to later transactions
+    pub reissued: usize,
+    /// Rejected nonces filled with self-transfers
+    pub filled: usize,
+    /// Rejected nonces still open
+    pub open_gaps: usize,
+}
+
+/// Hands out nonces per sender and keeps the sequence free of gaps: a nonce whose
+/// submission is rejected goes to that sender's next transaction, or is filled
+/// with a zero-value self-transfer by `fill_gaps`.
+#[derive(Debug)]
+pub struct NonceManager<M> {
+    client: Arc<M>,
+    senders: Mutex<HashMap<Address, SenderNonces>>,
+    reissued: AtomicUsize,
+    filled: AtomicUsize,
+}
+
+impl<M: Middleware> NonceManager<M> {
+    pub fn new(client: Arc<M>) -> Self {
+        NonceManager {
+            client,
+            senders: Mutex::new(HashMap::new()),
+            reissued: AtomicUsize::new(0),
+            filled: AtomicUsize::new(0),
+        }
+    }
+
+    /// Starts tracking `address` from its pending nonce; a no-op if already tracked.
+    /// Returns the next nonce to be handed out.
+    pub async fn track(&self, address: Address) -> Result<u64> {
+        if let Some(sender) = self.senders.lock().unwrap().get(&address) {
+            return Ok(sender.next);
+        }
+        let pending = self.nonce_at(address, BlockNumber::Pending).await?;
+        self.track_from(address, pending);
+        Ok(pending)
+    }
+
+    /// Starts tracking `address` from `nonce` without asking the node.
+    pub fn track_from(&self, address: Address, nonce: u64) {
+        self.senders.lock().unwrap().insert(address, SenderNonces {
+            next: nonce,
+            gaps: BTreeSet::new(),
+            mined: nonce,
+            progressed_at: Instant::now(),
+        });
+    }
+
+    /// Drops what is known about `address` and restarts it from its pending nonce.
+    pub async fn resync(&self, address: Address) -> Result<u64> {
+        let pending = self.nonce_at(address, BlockNumber::Pending).await?;
+        self.track_from(address, pending);
+        metrics::nonce_resync("resync", 1);
+        Ok(pending)
+    }
+
+    pub async fn resync_all(&self) -> Result<()> {
+        let addresses: Vec<Address> = self.senders.lock().unwrap().keys().copied().collect();
+        for address in addresses {
+            self.resync(address).await?;
+        }
+        Ok(())
+    }
+
+    /// Next nonce for `address`, re-issuing the lowest rejected one first.
+    pub fn next(&self, address: Address) -> Result<u64> {
+        let mut senders = self.senders.lock().unwrap();
+        let sender = senders.get_mut(&address).ok_or(BridgeError::UntrackedSender(address))?;
+        if let Some(nonce) = sender.gaps.pop_first() {
+            self.reissued.fetch_add(1, Ordering::Relaxed);
+            metrics::nonce_resync("reissued", 1);
+            return Ok(nonce);
+        }
+        sender.next += 1;
+        Ok(sender.next - 1)
+    }
+
+    /// Records that the transaction from `address` at `nonce` was rejected with `reason`.
+    /// Unless the reason shows the nonce is already in use, it becomes a gap to repair.
+    pub fn rejected(&self, address: Address, nonce: u64, reason: &str) {
+        let reason = reason.to_lowercase();
+        if NONCE_USED.iter().any(|used| reason.contains(used)) {
+            return;
+        }
+        if let Some(sender) = self.senders.lock().unwrap().get_mut(&address) {
+            if nonce < sender.next {
+                sender.gaps.insert(nonce);
+            }
+        }
+    }
+
+    /// Rejected nonces not yet handed out again, by sender.
+    pub fn gaps(&self) -> Vec<(Address, Vec<u64>)> {
+        self.senders.lock().unwrap().iter()
+            .filter(|(_, sender)| !sender.gaps.is_empty())
+            .map(|(address, sender)| (*address, sender.gaps.iter().copied().collect()))
+            .collect()
+    }
+
+    pub fn stats(&self) -> NonceStats {
+        NonceStats {
+            reissued: self.reissued.load(Ordering::Relaxed),
+            filled: self.filled.load(Ordering::Relaxed),
+            open_gaps: self.gaps().iter().map(|(_, gaps)| gaps.len()).sum(),
+        }
+    }
+
+    /// Sends a zero-value self-transfer at every open gap of the senders in `wallets`,
+    /// so the transactions queued behind it can be mined. Returns the gaps filled.
+    pub async fn fill_gaps(&self, wallets: &[LocalWallet], fees: &Fees) -> Result<usize> {
+        let mut filled = 0;
+        for (address, gaps) in self.gaps() {
+            let Some(wallet) = wallets.iter().find(|wallet| wallet.address() == address) else {
+                continue;
+            };
+            for nonce in gaps {
+                let tx = fees.typed(TransactionRequest::new()
+                    .from(address)
+                    .to(address)
+                    .value(0)
+                    .gas(21_000)
+                    .nonce(nonce)
+                    .chain_id(wallet.chain_id()));
+                let signature = wallet.sign_transaction_sync(&tx)?;
+                let raw = tx.rlp_signed(&signature);
+                let hash = H256::from(keccak256(&raw));
+
+                let result = self.client.send_raw_transaction(raw).await;
+                let used = match &result {
+                    Ok(_) => true,
+                    Err(e) => {
+                        let reason = e.to_string().to_lowercase();
+                        NONCE_USED.iter().any(|used| reason.contains(used))
+                    }
+                };
+                if used {
+                    if let Some(sender) = self.senders.lock().unwrap().get_mut(&address) {
+                        sender.gaps.remove(&nonce);
+                    }
+                }
+                match result {
+                    Ok(_) => {
+                        println!("Filled nonce gap {} of {:?} with {:?}", nonce, address, hash);
+                        filled += 1;
+                    }
+                    Err(e) if !used => eprintln!("Failed to fill nonce gap {} of {:?}: {}", nonce, address, e),
+                    Err(_) => {}
+                }
+            }
+        }
+        self.filled.fetch_add(filled, Ordering::Relaxed);
+        metrics::nonce_resync("filled", filled);
+        Ok(filled)
+    }
+
+    /// Senders whose mined nonce is behind the nonces handed out and has not moved
+    /// for `stalled_after`. Each call refreshes the mined nonce of every sender.
+    pub async fn stuck(&self, stalled_after: Duration) -> Result<Vec<StuckSender>> {
+        let addresses: Vec<Address> = self.senders.lock().unwrap().keys().copied().collect();
+        let mut stuck = Vec::new();
+        for address in addresses {
+            let mined = self.nonce_at(address, BlockNumber::Latest).await?;
+            let mut senders = self.senders.lock().unwrap();
+            let Some(sender) = senders.get_mut(&address) else { continue };
+            if mined != sender.mined {
+                sender.mined = mined;
+                sender.progressed_at = Instant::now();
+            }
+            let stalled = sender.progressed_at.elapsed();
+            if mined < sender.next && stalled >= stalled_after {
+                stuck.push(StuckSender {
+                    address,
+                    mined,
+                    issued: sender.next,
+                    gaps: sender.gaps.iter().copied().collect(),
+                    stalled_secs: stalled.as_secs(),
+                });
+            }
+        }
+        Ok(stuck)
+    }
+
+    async fn nonce_at(&self, address: Address, block: BlockNumber) -> Result<u64> {
+        let nonce = self.client.get_transaction_count(address, Some(block.into())).await
+            .map_err(|e| BridgeError::Rpc(format!("Failed to get nonce of {:?}: {}", address, e)))?;
+        Ok(nonce.as_u64())
+    }
+}
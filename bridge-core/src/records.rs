@@ -0,0 +1,510 @@
+use crate::error::{BridgeError, Result};
+use crate::transport;
+use clap::Args;
+use ethers::{
+    providers::Middleware,
+    types::{Address, H256, U256},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Version of the transaction record and manifest formats; bumped whenever a field
+/// changes meaning or is removed. New optional fields do not bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// File names inside a run directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const RECORDS_FILE: &str = "transactions.jsonl";
+
+/// `--runs-dir` flag of the load tools.
+#[derive(Args, Debug, Clone)]
+pub struct RunArgs {
+    /// Directory every run is filed under, one subdirectory per run holding its
+    /// manifest.json, transactions.jsonl and copies of its other output files
+    #[arg(long, global = true, default_value = "runs")]
+    pub runs_dir: PathBuf,
+}
+
+/// Where a transaction ended up by the time the tool stopped following it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxStatus {
+    /// Accepted by the RPC endpoint; the tool does not follow inclusion
+    Accepted,
+    /// Rejected by the RPC endpoint, or never sent
+    Rejected,
+    /// Mined, and succeeded where the receipt was read
+    Included,
+    /// Mined with a failed receipt status
+    Reverted,
+    /// Accepted but not mined when the tool stopped waiting
+    Pending,
+    /// Accepted, then no longer known to the node
+    Dropped,
+}
+
+impl fmt::Display for TxStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            TxStatus::Accepted => "accepted",
+            TxStatus::Rejected => "rejected",
+            TxStatus::Included => "included",
+            TxStatus::Reverted => "reverted",
+            TxStatus::Pending => "pending",
+            TxStatus::Dropped => "dropped",
+        };
+        f.write_str(status)
+    }
+}
+
+/// One line of `transactions.jsonl`: a transaction of a run in its final state.
+/// Every tool writes the same fields; the ones a tool does not know are left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxRecord {
+    pub schema_version: u32,
+    pub run_id: String,
+    /// Search trial or compared endpoint the transaction belongs to, from 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trial: Option<u32>,
+    /// Round or iteration of the commands that repeat their sends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round: Option<usize>,
+    /// Load profile phase, from 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<usize>,
+    /// What the transaction does, e.g. `transfer`, `send-eth` or `fund`
+    pub kind: String,
+    /// Contention lane of tx-generator traffic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lane: Option<String>,
+    pub chain_id: u64,
+    /// Destination chain of a cross-chain transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_chain_id: Option<u64>,
+    pub from: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Recipient on the destination chain of a cross-chain transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "decimal")]
+    pub value_wei: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<H256>,
+    pub status: TxStatus,
+    /// Why the transaction was rejected or could not be confirmed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Unix time in milliseconds the transaction was handed to the endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_at_ms: Option<u64>,
+    /// Unix time in milliseconds the tool saw the transaction mined
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub included_at_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// Message ID of the ETHSentToDestinationChain event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<u64>,
+}
+
+impl TxRecord {
+    /// A record with only the required fields set; the recorder fills in the run.
+    pub fn new(kind: impl Into<String>, chain_id: u64, from: Address, status: TxStatus) -> Self {
+        TxRecord {
+            schema_version: SCHEMA_VERSION,
+            run_id: String::new(),
+            trial: None,
+            round: None,
+            phase: None,
+            kind: kind.into(),
+            lane: None,
+            chain_id,
+            destination_chain_id: None,
+            from,
+            to: None,
+            recipient: None,
+            nonce: None,
+            value_wei: None,
+            hash: None,
+            status,
+            error: None,
+            sent_at_ms: None,
+            included_at_ms: None,
+            latency_ms: None,
+            block_number: None,
+            gas_used: None,
+            message_id: None,
+        }
+    }
+
+    /// Sets `sent_at_ms` from the moment it was sent, and for a mined transaction,
+    /// `included_at_ms` and `latency_ms` from the moment it was seen in a block.
+    pub fn timed(mut self, sent_at: Instant, included_at: Option<Instant>) -> Self {
+        self.sent_at_ms = Some(unix_ms(sent_at));
+        if let Some(included_at) = included_at {
+            self.included_at_ms = Some(unix_ms(included_at));
+            self.latency_ms = Some(included_at.saturating_duration_since(sent_at).as_millis() as u64);
+        }
+        self
+    }
+}
+
+/// One node a run talked to, as it described itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestNode {
+    pub name: String,
+    pub rpc_url: String,
+    pub chain_id: Option<u64>,
+    /// `web3_clientVersion`, e.g. `reth/v1.1.0-...`
+    pub client_version: Option<String>,
+}
+
+/// `manifest.json` of a run: what was run, against what, and when.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub schema_version: u32,
+    pub run_id: String,
+    pub tool: String,
+    pub command: String,
+    /// Command line, with private keys left out
+    pub args: Vec<String>,
+    /// Tool-specific parameters, after defaults and profiles are resolved
+    pub parameters: Value,
+    pub git_revision: Option<String>,
+    /// Uncommitted changes in the source tree the revision was read from
+    pub git_dirty: Option<bool>,
+    pub topology: Option<PathBuf>,
+    pub nodes: Vec<ManifestNode>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_secs: Option<f64>,
+    /// Error the run stopped with, if it failed
+    pub error: Option<String>,
+    /// Transaction records written, by status
+    pub records: BTreeMap<TxStatus, usize>,
+    /// Other output files of the run, copied next to the manifest
+    pub files: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Sink {
+    out: BufWriter<File>,
+    path: PathBuf,
+    counts: BTreeMap<TxStatus, usize>,
+    failed: bool,
+}
+
+/// Cheap handle that writes transaction records into a run. The default handle
+/// writes nothing, for callers that do not file a run.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    run_id: String,
+    trial: Option<u32>,
+    sink: Option<Arc<Mutex<Sink>>>,
+}
+
+impl Recorder {
+    /// The same run, with every record marked as part of `trial`.
+    pub fn trial(&self, trial: u32) -> Recorder {
+        Recorder { trial: Some(trial), ..self.clone() }
+    }
+
+    pub fn record(&self, mut record: TxRecord) {
+        let Some(sink) = &self.sink else { return };
+        record.schema_version = SCHEMA_VERSION;
+        record.run_id.clone_from(&self.run_id);
+        record.trial = record.trial.or(self.trial);
+
+        let mut sink = sink.lock().unwrap();
+        *sink.counts.entry(record.status).or_default() += 1;
+        let written = serde_json::to_writer(&mut sink.out, &record)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writeln!(sink.out));
+        // Report the first failure only, rather than once per transaction
+        if let Err(e) = written {
+            if !sink.failed {
+                eprintln!("Failed to write transaction records to {}: {}", sink.path.display(), e);
+                sink.failed = true;
+            }
+        }
+    }
+
+    /// Writes buffered records out, e.g. between iterations of a run without an end.
+    pub fn flush(&self) {
+        if let Some(sink) = &self.sink {
+            let _ = sink.lock().unwrap().out.flush();
+        }
+    }
+}
+
+/// A run being filed: its directory, manifest and transaction records.
+#[derive(Debug)]
+pub struct Run {
+    dir: PathBuf,
+    manifest: Manifest,
+    started: Instant,
+    recorder: Recorder,
+}
+
+impl RunArgs {
+    /// Creates the run directory under `--runs-dir`, asks each of `nodes` (name and RPC
+    /// URL) for its chain ID and client version, and writes a first manifest, so an
+    /// interrupted run is still filed. `cli` is the tool's clap command, whose key
+    /// flags are redacted from the recorded command line.
+    pub async fn start(
+        &self,
+        tool: &str,
+        command: &str,
+        parameters: Value,
+        topology: Option<&Path>,
+        nodes: &[(String, String)],
+        cli: &clap::Command,
+    ) -> Result<Run> {
+        let now = chrono::Utc::now();
+        let (dir, run_id) = create_run_dir(&self.runs_dir, &format!("{}-{}-{}", tool, command, now.format("%Y%m%d-%H%M%S")))?;
+
+        let records_path = dir.join(RECORDS_FILE);
+        let out = File::create(&records_path)
+            .map_err(|source| BridgeError::Io { path: records_path.display().to_string(), source })?;
+        let sink = Sink { out: BufWriter::new(out), path: records_path, counts: BTreeMap::new(), failed: false };
+
+        let mut manifest_nodes = Vec::with_capacity(nodes.len());
+        for (name, url) in nodes {
+            manifest_nodes.push(describe_node(name, url).await);
+        }
+        let (git_revision, git_dirty) = git_revision();
+
+        let run = Run {
+            manifest: Manifest {
+                schema_version: SCHEMA_VERSION,
+                run_id: run_id.clone(),
+                tool: tool.to_string(),
+                command: command.to_string(),
+                args: redacted_args(std::env::args(), &key_flags(cli)),
+                parameters,
+                git_revision,
+                git_dirty,
+                topology: topology.map(Path::to_path_buf),
+                nodes: manifest_nodes,
+                started_at: now.to_rfc3339(),
+                finished_at: None,
+                duration_secs: None,
+                error: None,
+                records: BTreeMap::new(),
+                files: Vec::new(),
+            },
+            dir,
+            started: Instant::now(),
+            recorder: Recorder { run_id, trial: None, sink: Some(Arc::new(Mutex::new(sink))) },
+        };
+        run.save()?;
+        println!("Filing this run under {}", run.dir.display());
+        Ok(run)
+    }
+}
+
+impl Run {
+    pub fn id(&self) -> &str {
+        &self.manifest.run_id
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn recorder(&self) -> Recorder {
+        self.recorder.clone()
+    }
+
+    /// Copies `path`, another output of the run such as tx_stats.json, into the run
+    /// directory when the run finishes. Missing files are skipped.
+    pub fn attach(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref().display().to_string();
+        if !self.manifest.files.contains(&path) {
+            self.manifest.files.push(path);
+        }
+    }
+
+    /// Flushes the records, copies the attached files and writes the final manifest
+    /// with the end time, record counts and `error` if the run failed.
+    pub fn finish(mut self, error: Option<String>) -> Result<PathBuf> {
+        self.recorder.flush();
+        if let Some(sink) = &self.recorder.sink {
+            self.manifest.records = sink.lock().unwrap().counts.clone();
+        }
+        self.manifest.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        self.manifest.duration_secs = Some(self.started.elapsed().as_secs_f64());
+        self.manifest.error = error;
+
+        let mut files = Vec::with_capacity(self.manifest.files.len());
+        for file in &self.manifest.files {
+            let source = Path::new(file);
+            let Some(name) = source.file_name() else { continue };
+            if !source.exists() {
+                continue;
+            }
+            fs::copy(source, self.dir.join(name))
+                .map_err(|e| BridgeError::Io { path: file.clone(), source: e })?;
+            files.push(name.to_string_lossy().into_owned());
+        }
+        self.manifest.files = files;
+
+        self.save()?;
+        let total: usize = self.manifest.records.values().sum();
+        println!("Run {} filed under {} ({} transaction records)", self.manifest.run_id, self.dir.display(), total);
+        Ok(self.dir)
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self.dir.join(MANIFEST_FILE);
+        let json = serde_json::to_string_pretty(&self.manifest)
+            .map_err(|source| BridgeError::Json { path: path.display().to_string(), source })?;
+        fs::write(&path, json).map_err(|source| BridgeError::Io { path: path.display().to_string(), source })
+    }
+}
+
+/// Unix time in milliseconds of `at`, a moment in the past or now.
+pub fn unix_ms(at: Instant) -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    now.saturating_sub(at.elapsed()).as_millis() as u64
+}
+
+// A fresh directory named after the run, with a counter if one started in the same second
+fn create_run_dir(runs_dir: &Path, name: &str) -> Result<(PathBuf, String)> {
+    fs::create_dir_all(runs_dir)
+        .map_err(|source| BridgeError::Io { path: runs_dir.display().to_string(), source })?;
+    let mut run_id = name.to_string();
+    for attempt in 2.. {
+        let dir = runs_dir.join(&run_id);
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok((dir, run_id)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => run_id = format!("{}-{}", name, attempt),
+            Err(source) => return Err(BridgeError::Io { path: dir.display().to_string(), source }),
+        }
+    }
+    unreachable!("the run directory counter is unbounded")
+}
+
+// A node that cannot be reached is still listed, without its chain ID and version
+async fn describe_node(name: &str, url: &str) -> ManifestNode {
+    let mut node = ManifestNode {
+        name: name.to_string(),
+        rpc_url: url.to_string(),
+        chain_id: None,
+        client_version: None,
+    };
+    match transport::connect(url).await {
+        Ok(provider) => {
+            node.chain_id = provider.get_chainid().await.ok().map(|id| id.as_u64());
+            match provider.client_version().await {
+                Ok(version) => node.client_version = Some(version),
+                Err(e) => eprintln!("Failed to get the client version of {}: {}", name, e),
+            }
+        }
+        Err(e) => eprintln!("Failed to connect to {} for the run manifest: {}", name, e),
+    }
+    node
+}
+
+// Revision of the source tree the tool was built from, and whether it had local changes
+fn git_revision() -> (Option<String>, Option<bool>) {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(env!("CARGO_MANIFEST_DIR"))
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let revision = git(&["rev-parse", "HEAD"]);
+    let dirty = revision.as_ref().and_then(|_| git(&["status", "--porcelain", "--untracked-files=no"]))
+        .map(|status| !status.is_empty());
+    (revision, dirty)
+}
+
+// Long and short forms of every flag of `cli` and its subcommands named like a key,
+// e.g. `--funder-key` and its `-f`
+fn key_flags(cli: &clap::Command) -> HashSet<String> {
+    let mut flags = HashSet::new();
+    for arg in cli.get_arguments() {
+        let id = arg.get_id().as_str();
+        if !(id.ends_with("key") || id.ends_with("keys")) {
+            continue;
+        }
+        flags.extend(arg.get_long_and_visible_aliases().into_iter().flatten().map(|long| format!("--{}", long)));
+        flags.extend(arg.get_short_and_visible_aliases().into_iter().flatten().map(|short| format!("-{}", short)));
+    }
+    for subcommand in cli.get_subcommands() {
+        flags.extend(key_flags(subcommand));
+    }
+    flags
+}
+
+// Private keys stay out of the manifest: the values of `key_flags` are replaced, whether
+// they follow the flag, come after `=`, or are attached to a short flag (`-fVALUE`)
+fn redacted_args(args: impl Iterator<Item = String>, key_flags: &HashSet<String>) -> Vec<String> {
+    let mut redacted = Vec::new();
+    let mut hide_next = false;
+    for arg in args {
+        if hide_next {
+            redacted.push("<redacted>".to_string());
+            hide_next = false;
+            continue;
+        }
+        let attached = match arg.split_once('=') {
+            Some((flag, _)) if flag.starts_with("--") => Some((flag, "=")),
+            _ if !arg.starts_with("--") && arg.len() > 2 && arg.is_char_boundary(2) => {
+                Some((&arg[..2], if arg[2..].starts_with('=') { "=" } else { "" }))
+            }
+            _ => None,
+        };
+        match attached {
+            Some((flag, separator)) if key_flags.contains(flag) => {
+                redacted.push(format!("{}{}<redacted>", flag, separator));
+            }
+            _ => {
+                hide_next = key_flags.contains(&arg);
+                redacted.push(arg);
+            }
+        }
+    }
+    redacted
+}
+
+// Wei amounts as decimal strings, which do not lose precision in JSON tools
+mod decimal {
+    use ethers::types::U256;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<U256>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<U256>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| U256::from_dec_str(&value).map_err(D::Error::custom))
+            .transpose()
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::{BridgeError, Result};
+use crate::fees::Fees;
+use clap::ValueEnum;
+use ethers::{
+    providers::Middleware,
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip2930::AccessList, Address, BlockNumber, Transaction, TransactionRequest, H256},
+    utils::keccak256,
+};
+use std::collections::HashSet;
+
+/// What to send in place of a stuck transaction.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaceMode {
+    /// Same transaction with higher fees
+    SpeedUp,
+    /// Zero-value self-transfer with higher fees
+    Cancel,
+}
+
+/// A transaction waiting in the node's pool.
+#[derive(Debug, Clone)]
+pub struct PoolTx {
+    pub tx: Transaction,
+    /// In the queued pool, i.e. behind a nonce gap
+    pub queued: bool,
+}
+
+/// One line of `txpool_inspect` for a sender.
+#[derive(Debug, Clone)]
+pub struct PoolEntry {
+    pub from: Address,
+    pub nonce: u64,
+    pub queued: bool,
+    pub summary: String,
+}
+
+/// Pending and queued transactions of `senders` from `txpool_content`, by sender and nonce.
+pub async fn pool_transactions<M: Middleware>(client: &M, senders: &[Address]) -> Result<Vec<PoolTx>> {
+    let content = client.txpool_content().await
+        .map_err(|e| BridgeError::Rpc(format!("txpool_content failed: {}", e)))?;
+    let senders: HashSet<&Address> = senders.iter().collect();
+
+    let mut txs = Vec::new();
+    for (pool, queued) in [(content.pending, false), (content.queued, true)] {
+        for (from, by_nonce) in pool {
+            if senders.contains(&from) {
+                txs.extend(by_nonce.into_values().map(|tx| PoolTx { tx, queued }));
+            }
+        }
+    }
+    txs.sort_by_key(|pool_tx| (pool_tx.tx.from, pool_tx.tx.nonce));
+    Ok(txs)
+}
+
+/// Summary of the pool transactions of `senders` from `txpool_inspect`, which stays
+/// small on a busy node.
+pub async fn pool_summary<M: Middleware>(client: &M, senders: &[Address]) -> Result<Vec<PoolEntry>> {
+    let inspect = client.txpool_inspect().await
+        .map_err(|e| BridgeError::Rpc(format!("txpool_inspect failed: {}", e)))?;
+    let senders: HashSet<&Address> = senders.iter().collect();
+
+    let mut entries = Vec::new();
+    for (pool, queued) in [(inspect.pending, false), (inspect.queued, true)] {
+        for (from, by_nonce) in pool {
+            if !senders.contains(&from) {
+                continue;
+            }
+            for (nonce, summary) in by_nonce {
+                entries.push(PoolEntry {
+                    from,
+                    nonce: nonce.parse().unwrap_or_default(),
+                    queued,
+                    summary: format!(
+                        "{}: {} wei + {} gas x {} wei",
+                        summary.to.map_or("contract creation".to_string(), |to| format!("{:?}", to)),
+                        summary.value, summary.gas, summary.gas_price
+                    ),
+                });
+            }
+        }
+    }
+    entries.sort_by_key(|entry| (entry.from, entry.nonce));
+    Ok(entries)
+}
+
+/// A transaction sent, possibly several times with rising fees, in place of a stuck one.
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub from: Address,
+    pub nonce: u64,
+    pub original: H256,
+    /// Every replacement sent, oldest first
+    pub hashes: Vec<H256>,
+    request: TransactionRequest,
+    fees: Fees,
+}
+
+/// How a replaced nonce was finally used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaceOutcome {
+    /// One of the replacements was mined
+    Replaced { hash: H256, block: u64 },
+    /// The original was mined after all
+    Original { block: u64 },
+    /// The nonce was used by a transaction this replacement does not know about
+    Other,
+}
+
+impl Replacement {
+    /// Replaces `stuck` at the same nonce with fees raised by `bump_percent`, as required
+    /// for the pool to accept it.
+    pub async fn send<M: Middleware>(
+        client: &M,
+        wallet: &LocalWallet,
+        stuck: &Transaction,
+        mode: ReplaceMode,
+        bump_percent: u64,
+    ) -> Result<Self> {
+        let mut fees = Fees::of(stuck).bumped(bump_percent);
+        let request = match mode {
+            ReplaceMode::SpeedUp => {
+                let mut request = TransactionRequest::new()
+                    .from(stuck.from)
+                    .value(stuck.value)
+                    .gas(stuck.gas)
+                    .data(stuck.input.clone())
+                    .nonce(stuck.nonce);
+                if let Some(to) = stuck.to {
+                    request = request.to(to);
+                }
+                request
+            }
+            ReplaceMode::Cancel => {
+                fees.access_list = AccessList::default();
+                TransactionRequest::new()
+                    .from(stuck.from)
+                    .to(stuck.from)
+                    .value(0)
+                    .gas(21_000)
+                    .nonce(stuck.nonce)
+            }
+        };
+
+        let mut replacement = Replacement {
+            from: stuck.from,
+            nonce: stuck.nonce.as_u64(),
+            original: stuck.hash,
+            hashes: Vec::new(),
+            request: request.chain_id(wallet.chain_id()),
+            fees,
+        };
+        replacement.resend(client, wallet).await?;
+        Ok(replacement)
+    }
+
+    /// Sends the replacement again with fees raised by another `bump_percent`.
+    pub async fn bump<M: Middleware>(&mut self, client: &M, wallet: &LocalWallet, bump_percent: u64) -> Result<H256> {
+        self.fees = self.fees.bumped(bump_percent);
+        self.resend(client, wallet).await
+    }
+
+    /// Fees of the latest replacement.
+    pub fn fees(&self) -> &Fees {
+        &self.fees
+    }
+
+    /// Whether the nonce has been mined, and by which transaction.
+    pub async fn outcome<M: Middleware>(&self, client: &M) -> Result<Option<ReplaceOutcome>> {
+        let mined = client.get_transaction_count(self.from, Some(BlockNumber::Latest.into())).await
+            .map_err(|e| BridgeError::Rpc(format!("Failed to get nonce of {:?}: {}", self.from, e)))?;
+        if mined.as_u64() <= self.nonce {
+            return Ok(None);
+        }
+
+        for hash in self.hashes.iter().rev().chain(std::iter::once(&self.original)) {
+            let receipt = client.get_transaction_receipt(*hash).await
+                .map_err(|e| BridgeError::Rpc(format!("Failed to get receipt of {:?}: {}", hash, e)))?;
+            if let Some(receipt) = receipt {
+                let block = receipt.block_number.unwrap_or_default().as_u64();
+                return Ok(Some(if *hash == self.original {
+                    ReplaceOutcome::Original { block }
+                } else {
+                    ReplaceOutcome::Replaced { hash: *hash, block }
+                }));
+            }
+        }
+        Ok(Some(ReplaceOutcome::Other))
+    }
+
+    async fn resend<M: Middleware>(&mut self, client: &M, wallet: &LocalWallet) -> Result<H256> {
+        let tx = self.fees.typed(self.request.clone());
+        let signature = wallet.sign_transaction_sync(&tx)?;
+        let raw = tx.rlp_signed(&signature);
+        let hash = H256::from(keccak256(&raw));
+
+        client.send_raw_transaction(raw).await
+            .map_err(|e| BridgeError::Rpc(format!("Replacement of nonce {} rejected: {}", self.nonce, e)))?;
+        self.hashes.push(hash);
+        Ok(hash)
+    }
+}
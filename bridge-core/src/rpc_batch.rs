@@ -0,0 +1,150 @@
+use crate::error::{BridgeError, Result};
+use crate::metrics;
+use ethers::types::{Bytes, H256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+/// Error object returned for one item of a JSON-RPC batch.
+#[derive(Debug, Clone, Deserialize, thiserror::Error)]
+#[error("JSON-RPC error {code}: {message}")]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    id: Value,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// Results of one batch, in the same order as the requests.
+#[derive(Debug)]
+pub struct BatchResponse<T> {
+    pub results: Vec<std::result::Result<T, RpcError>>,
+    /// Size of the POST body, to compare against `--rpc.max-request-size`
+    pub request_bytes: usize,
+}
+
+/// Sends JSON-RPC 2.0 batch arrays over HTTP: many calls in a single POST.
+#[derive(Debug)]
+pub struct BatchClient {
+    http: reqwest::Client,
+    url: String,
+    next_id: AtomicU64,
+}
+
+impl BatchClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        BatchClient {
+            http: reqwest::Client::new(),
+            url: url.into(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Sends every `(method, params)` pair in one request. A transport failure
+    /// or a non-batch reply fails the whole call; per-call errors are returned
+    /// in place of the matching result.
+    pub async fn call(&self, calls: &[(&str, Value)]) -> Result<BatchResponse<Value>> {
+        if calls.is_empty() {
+            return Ok(BatchResponse { results: Vec::new(), request_bytes: 0 });
+        }
+
+        // Timed under the batch's method when every call shares one
+        let method = if calls.iter().all(|(method, _)| *method == calls[0].0) {
+            format!("batch:{}", calls[0].0)
+        } else {
+            "batch".to_string()
+        };
+        let started = Instant::now();
+        let result = self.post(calls).await;
+        metrics::rpc_call(&method, started.elapsed(), result.is_ok());
+        result
+    }
+
+    async fn post(&self, calls: &[(&str, Value)]) -> Result<BatchResponse<Value>> {
+        let first_id = self.next_id.fetch_add(calls.len() as u64, Ordering::Relaxed);
+        let requests: Vec<Value> = calls.iter()
+            .enumerate()
+            .map(|(i, (method, params))| json!({
+                "jsonrpc": "2.0",
+                "id": first_id + i as u64,
+                "method": method,
+                "params": params,
+            }))
+            .collect();
+        let body = serde_json::to_vec(&requests)
+            .map_err(|source| BridgeError::Json { path: self.url.clone(), source })?;
+        let request_bytes = body.len();
+
+        let response = self.http.post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(BridgeError::Rpc(format!("HTTP {} from {}: {}", status, self.url, text.trim())));
+        }
+
+        // Servers answer a rejected batch (e.g. too large) with a single error object
+        let responses: Vec<RpcResponse> = match serde_json::from_str(&text) {
+            Ok(responses) => responses,
+            Err(_) => {
+                let single: RpcResponse = serde_json::from_str(&text)
+                    .map_err(|source| BridgeError::Json { path: self.url.clone(), source })?;
+                let reason = single.error.map(|e| e.to_string()).unwrap_or(text);
+                return Err(BridgeError::Rpc(format!("Batch rejected by {}: {}", self.url, reason)));
+            }
+        };
+
+        // Responses may come back in any order, so match them up by ID
+        let mut by_id: HashMap<u64, RpcResponse> = responses.into_iter()
+            .filter_map(|r| r.id.as_u64().map(|id| (id, r)))
+            .collect();
+        let results = (0..calls.len() as u64)
+            .map(|i| match by_id.remove(&(first_id + i)) {
+                Some(RpcResponse { error: Some(error), .. }) => Err(error),
+                Some(RpcResponse { result, .. }) => Ok(result.unwrap_or(Value::Null)),
+                None => Err(RpcError {
+                    code: -32603,
+                    message: "no response for this request in the batch".to_string(),
+                    data: None,
+                }),
+            })
+            .collect();
+
+        Ok(BatchResponse { results, request_bytes })
+    }
+
+    /// `eth_sendRawTransaction` for each signed transaction, in one request.
+    pub async fn send_raw_transactions(&self, raw_txs: &[Bytes]) -> Result<BatchResponse<H256>> {
+        let calls: Vec<(&str, Value)> = raw_txs.iter()
+            .map(|raw| ("eth_sendRawTransaction", json!([raw])))
+            .collect();
+        let response = self.call(&calls).await?;
+
+        let results = response.results.into_iter()
+            .map(|result| result.and_then(|value| {
+                serde_json::from_value(value.clone()).map_err(|e| RpcError {
+                    code: -32700,
+                    message: format!("unexpected transaction hash {}: {}", value, e),
+                    data: None,
+                })
+            }))
+            .collect();
+        Ok(BatchResponse { results, request_bytes: response.request_bytes })
+    }
+}
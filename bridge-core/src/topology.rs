@@ -0,0 +1,129 @@
+use crate::accounts::{parse_address, AccountStore};
+use crate::error::{BridgeError, Result};
+use crate::node::NodeConfig;
+use ethers::signers::LocalWallet;
+use serde::Deserialize;
+use std::{collections::HashSet, fs, path::Path};
+
+/// Network topology file, one `[[chain]]` table per chain:
+///
+/// ```toml
+/// [[chain]]
+/// name = "node1"
+/// chain_id = 9011
+/// rpc = "http://127.0.0.1:8545"
+/// ws = "ws://127.0.0.1:8546"
+/// contract = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+/// relayer_keys = ["0x..."]
+/// accounts = "node-1.json"
+/// ```
+///
+/// `rpc` may also be a `ws://` URL or an `ipc://` socket path; `ws` is then not needed
+/// for subscriptions.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Topology {
+    #[serde(rename = "chain")]
+    pub chains: Vec<ChainEntry>,
+}
+
+/// One chain as written in the topology file, before validation.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainEntry {
+    pub name: String,
+    pub chain_id: u32,
+    pub rpc: String,
+    #[serde(default)]
+    pub ws: Option<String>,
+    pub contract: String,
+    #[serde(default)]
+    pub relayer_keys: Vec<String>,
+    /// Defaults to `node-{n}.json` for the n-th chain
+    #[serde(default)]
+    pub accounts: Option<String>,
+}
+
+impl Topology {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().display().to_string();
+        let content = fs::read_to_string(&path)
+            .map_err(|source| BridgeError::Io { path: path.clone(), source })?;
+        Self::parse(&content, &path)
+    }
+
+    /// Parses topology TOML; `path` is only used in error messages.
+    pub fn parse(content: &str, path: &str) -> Result<Self> {
+        let deserializer = toml::Deserializer::new(content);
+        serde_path_to_error::deserialize(deserializer).map_err(|e| BridgeError::Topology {
+            path: path.to_string(),
+            field: e.path().to_string(),
+            reason: e.inner().message().to_string(),
+        })
+    }
+
+    /// Validates every chain and turns it into a `NodeConfig`, numbered from 1
+    /// in file order.
+    pub fn into_nodes(self, path: &str) -> Result<Vec<NodeConfig>> {
+        let error = |field: String, reason: String| BridgeError::Topology {
+            path: path.to_string(),
+            field,
+            reason,
+        };
+
+        let mut names = HashSet::new();
+        let mut chain_ids = HashSet::new();
+        let mut nodes = Vec::with_capacity(self.chains.len());
+
+        for (i, chain) in self.chains.into_iter().enumerate() {
+            let field = |name: &str| format!("chain[{}].{}", i, name);
+
+            if chain.name.trim().is_empty() {
+                return Err(error(field("name"), "must not be empty".to_string()));
+            }
+            if !names.insert(chain.name.clone()) {
+                return Err(error(field("name"), format!("duplicate chain name {:?}", chain.name)));
+            }
+            if !chain_ids.insert(chain.chain_id) {
+                return Err(error(field("chain_id"), format!("duplicate chain ID {}", chain.chain_id)));
+            }
+            check_url(&chain.rpc, &["http://", "https://", "ws://", "wss://", "ipc://"])
+                .map_err(|reason| error(field("rpc"), reason))?;
+            if let Some(ws) = &chain.ws {
+                check_url(ws, &["ws://", "wss://"])
+                    .map_err(|reason| error(field("ws"), reason))?;
+            }
+            let contract = parse_address(&chain.contract)
+                .map_err(|e| error(field("contract"), e.to_string()))?;
+            for (k, key) in chain.relayer_keys.iter().enumerate() {
+                key.trim().trim_start_matches("0x").parse::<LocalWallet>()
+                    .map_err(|e| error(format!("chain[{}].relayer_keys[{}]", i, k), e.to_string()))?;
+            }
+
+            let index = i + 1;
+            nodes.push(NodeConfig {
+                index,
+                name: chain.name,
+                chain_id: chain.chain_id,
+                rpc_url: chain.rpc,
+                ws_url: chain.ws,
+                contract,
+                relayer_keys: chain.relayer_keys,
+                account_file: chain.accounts.unwrap_or_else(|| AccountStore::node_file(index)),
+            });
+        }
+
+        if nodes.is_empty() {
+            return Err(error("chain".to_string(), "at least one chain is required".to_string()));
+        }
+        Ok(nodes)
+    }
+}
+
+fn check_url(url: &str, schemes: &[&str]) -> std::result::Result<(), String> {
+    if schemes.iter().any(|s| url.starts_with(s)) {
+        Ok(())
+    } else {
+        Err(format!("{:?} must start with {}", url, schemes.join(" or ")))
+    }
+}
@@ -0,0 +1,185 @@
+use crate::error::{BridgeError, Result};
+use crate::metrics;
+use async_trait::async_trait;
+use ethers::{
+    providers::{
+        Http, HttpClientError, Ipc, IpcError, JsonRpcClient, JsonRpcError, Provider, ProviderError,
+        PubsubClient, Ws, WsClientError,
+    },
+    types::U256,
+};
+use futures::Stream;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::value::RawValue;
+use std::{fmt, path::Path, pin::Pin, str::FromStr, time::Instant};
+use thiserror::Error;
+
+/// Provider over whichever transport the endpoint names.
+pub type RpcProvider = Provider<Transport>;
+
+/// Connects to an `http(s)://` or `ws(s)://` URL, or an IPC socket: `ipc://` followed by
+/// its path, or a bare path that is absolute or ends in `.ipc`.
+pub async fn connect(endpoint: &str) -> Result<RpcProvider> {
+    Ok(Provider::new(Transport::connect(endpoint).await?))
+}
+
+/// Transport kind behind `provider`.
+pub fn transport_kind(provider: &RpcProvider) -> TransportKind {
+    provider.as_ref().kind()
+}
+
+/// How an endpoint is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    Http,
+    Ws,
+    Ipc,
+}
+
+impl TransportKind {
+    /// Rejects anything else, so a mistyped URL such as `htp://` or `localhost:8545`
+    /// is reported as such instead of failing as a missing IPC socket.
+    pub fn of(endpoint: &str) -> Result<Self> {
+        if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            Ok(TransportKind::Http)
+        } else if endpoint.starts_with("ws://") || endpoint.starts_with("wss://") {
+            Ok(TransportKind::Ws)
+        } else if endpoint.starts_with("ipc://") || Path::new(endpoint).is_absolute() || endpoint.ends_with(".ipc") {
+            Ok(TransportKind::Ipc)
+        } else {
+            Err(BridgeError::Connect {
+                endpoint: endpoint.to_string(),
+                reason: "unsupported endpoint scheme; expected http(s)://, ws(s)://, ipc:// or an IPC socket path"
+                    .to_string(),
+            })
+        }
+    }
+
+    /// Whether `eth_subscribe` works over this transport.
+    pub fn supports_pubsub(self) -> bool {
+        self != TransportKind::Http
+    }
+}
+
+impl fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TransportKind::Http => "http",
+            TransportKind::Ws => "ws",
+            TransportKind::Ipc => "ipc",
+        })
+    }
+}
+
+/// JSON-RPC client over HTTP, WebSocket or IPC, so every tool can take any endpoint.
+/// HTTP has no subscriptions; `subscribe` fails and callers fall back to polling.
+#[derive(Debug, Clone)]
+pub enum Transport {
+    Http(Http),
+    Ws(Ws),
+    Ipc(Ipc),
+}
+
+impl Transport {
+    pub async fn connect(endpoint: &str) -> Result<Self> {
+        let failed = |reason: String| BridgeError::Connect { endpoint: endpoint.to_string(), reason };
+        Ok(match TransportKind::of(endpoint)? {
+            TransportKind::Http => Transport::Http(Http::from_str(endpoint).map_err(|e| failed(e.to_string()))?),
+            TransportKind::Ws => Transport::Ws(Ws::connect(endpoint).await.map_err(|e| failed(e.to_string()))?),
+            TransportKind::Ipc => {
+                let path = endpoint.strip_prefix("ipc://").unwrap_or(endpoint);
+                Transport::Ipc(Ipc::connect(path).await.map_err(|e| failed(e.to_string()))?)
+            }
+        })
+    }
+
+    pub fn kind(&self) -> TransportKind {
+        match self {
+            Transport::Http(_) => TransportKind::Http,
+            Transport::Ws(_) => TransportKind::Ws,
+            Transport::Ipc(_) => TransportKind::Ipc,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TransportError {
+    #[error(transparent)]
+    Http(#[from] HttpClientError),
+
+    #[error(transparent)]
+    Ws(#[from] WsClientError),
+
+    #[error(transparent)]
+    Ipc(#[from] IpcError),
+
+    #[error("{0} endpoints do not support subscriptions")]
+    NoPubsub(TransportKind),
+}
+
+impl ethers::providers::RpcError for TransportError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            TransportError::Http(e) => e.as_error_response(),
+            TransportError::Ws(e) => e.as_error_response(),
+            TransportError::Ipc(e) => e.as_error_response(),
+            TransportError::NoPubsub(_) => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            TransportError::Http(e) => e.as_serde_error(),
+            TransportError::Ws(e) => e.as_serde_error(),
+            TransportError::Ipc(e) => e.as_serde_error(),
+            TransportError::NoPubsub(_) => None,
+        }
+    }
+}
+
+impl From<TransportError> for ProviderError {
+    fn from(e: TransportError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(e))
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for Transport {
+    type Error = TransportError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> std::result::Result<R, TransportError>
+    where
+        T: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let started = Instant::now();
+        let result = match self {
+            Transport::Http(http) => http.request(method, params).await.map_err(TransportError::from),
+            Transport::Ws(ws) => ws.request(method, params).await.map_err(TransportError::from),
+            Transport::Ipc(ipc) => ipc.request(method, params).await.map_err(TransportError::from),
+        };
+        metrics::rpc_call(method, started.elapsed(), result.is_ok());
+        result
+    }
+}
+
+impl PubsubClient for Transport {
+    type NotificationStream = Pin<Box<dyn Stream<Item = Box<RawValue>> + Send>>;
+
+    fn subscribe<T: Into<U256>>(&self, id: T) -> std::result::Result<Self::NotificationStream, TransportError> {
+        Ok(match self {
+            Transport::Http(_) => return Err(TransportError::NoPubsub(TransportKind::Http)),
+            Transport::Ws(ws) => Box::pin(ws.subscribe(id)?),
+            Transport::Ipc(ipc) => Box::pin(ipc.subscribe(id)?),
+        })
+    }
+
+    fn unsubscribe<T: Into<U256>>(&self, id: T) -> std::result::Result<(), TransportError> {
+        match self {
+            Transport::Http(_) => Err(TransportError::NoPubsub(TransportKind::Http)),
+            Transport::Ws(ws) => Ok(ws.unsubscribe(id)?),
+            Transport::Ipc(ipc) => Ok(ipc.unsubscribe(id)?),
+        }
+    }
+}
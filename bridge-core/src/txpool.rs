@@ -0,0 +1,358 @@
+use crate::dashboard::DashboardHandle;
+use crate::error::{BridgeError, Result};
+use crate::transport::{RpcProvider, Transport};
+use clap::Args;
+use ethers::{
+    providers::{JsonRpcClient, Middleware, ProviderError},
+    types::Address,
+};
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    fs::File,
+    io::{BufWriter, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::{task::JoinHandle, time};
+
+const SAMPLE_HEADER: &str = "unix_ms,elapsed_ms,pending,queued,senders,queued_senders,max_depth,max_depth_sender,\
+max_gap,max_gap_sender,accepted,failed,send_rate";
+const SENDER_HEADER: &str = "unix_ms,elapsed_ms,sender,pending,queued,lowest_nonce,highest_nonce,mined_nonce,gap";
+
+/// `--txpool-sample-ms` flags of the load tools.
+#[derive(Args, Debug, Clone)]
+pub struct TxpoolArgs {
+    /// Sample txpool_status and txpool_content this often during the run (needs the txpool API)
+    #[arg(long, global = true)]
+    pub txpool_sample_ms: Option<u64>,
+
+    /// CSV file of the txpool samples; the deepest senders per sample go to the same
+    /// name with a `-senders` suffix
+    #[arg(long, global = true, default_value = "txpool.csv")]
+    pub txpool_samples: String,
+
+    /// Senders written per sample, both the deepest queues and the largest nonce gaps
+    #[arg(long, global = true, default_value_t = 10)]
+    pub txpool_top_senders: usize,
+}
+
+impl TxpoolArgs {
+    /// Starts sampling when `--txpool-sample-ms` is given, next to the send counts of `board`.
+    pub fn start(&self, provider: Arc<RpcProvider>, board: DashboardHandle) -> Result<Option<TxpoolSampler>> {
+        match self.txpool_sample_ms {
+            Some(ms) => TxpoolSampler::start(
+                provider,
+                board,
+                Duration::from_millis(ms.max(1)),
+                &self.txpool_samples,
+                self.txpool_top_senders,
+            ).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// CSV files the sampler writes, when sampling.
+    pub fn files(&self) -> Vec<String> {
+        match self.txpool_sample_ms {
+            Some(_) => vec![self.txpool_samples.clone(), senders_path(&self.txpool_samples)],
+            None => Vec::new(),
+        }
+    }
+}
+
+fn senders_path(samples_path: &str) -> String {
+    match samples_path.strip_suffix(".csv") {
+        Some(stem) => format!("{}-senders.csv", stem),
+        None => format!("{}-senders", samples_path),
+    }
+}
+
+// txpool_content keeps only the nonces, so a large pool is cheap to read
+#[derive(Debug, Deserialize)]
+struct PoolNonces {
+    pending: HashMap<Address, BTreeMap<String, IgnoredAny>>,
+    queued: HashMap<Address, BTreeMap<String, IgnoredAny>>,
+}
+
+#[derive(Debug, Default)]
+struct SenderQueue {
+    pending: usize,
+    queued: usize,
+    nonces: Vec<u64>,
+    /// Lowest nonce is queued, so the gap reaches below the pool to the mined nonce
+    queued_first: bool,
+}
+
+impl SenderQueue {
+    fn depth(&self) -> usize {
+        self.pending + self.queued
+    }
+
+    // Nonces missing between the lowest and highest in the pool
+    fn holes(&self) -> u64 {
+        match (self.nonces.first(), self.nonces.last()) {
+            (Some(lowest), Some(highest)) => highest - lowest + 1 - self.nonces.len() as u64,
+            _ => 0,
+        }
+    }
+}
+
+/// Pool size at its peak and the largest nonce gap seen over a run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TxpoolSummary {
+    pub samples: usize,
+    pub failed_samples: usize,
+    pub peak_pending: u64,
+    pub peak_pending_at_ms: u128,
+    pub peak_queued: u64,
+    pub peak_queued_at_ms: u128,
+    pub peak_queued_senders: usize,
+    pub deepest_sender: Option<Address>,
+    pub deepest_queue: usize,
+    pub largest_gap_sender: Option<Address>,
+    pub largest_gap: u64,
+    /// Send rate over the sample interval in which pending peaked
+    pub send_rate_at_peak: f64,
+}
+
+impl fmt::Display for TxpoolSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Samples: {} ({} failed)", self.samples, self.failed_samples)?;
+        writeln!(f, "Peak pending: {} at {:.1}s, while sending {:.1} tx/s",
+            self.peak_pending, self.peak_pending_at_ms as f64 / 1000.0, self.send_rate_at_peak)?;
+        writeln!(f, "Peak queued: {} at {:.1}s, {} senders with queued transactions at most",
+            self.peak_queued, self.peak_queued_at_ms as f64 / 1000.0, self.peak_queued_senders)?;
+        if let Some(sender) = self.deepest_sender {
+            writeln!(f, "Deepest sender queue: {} transactions ({:?})", self.deepest_queue, sender)?;
+        }
+        match self.largest_gap_sender {
+            Some(sender) => write!(f, "Largest nonce gap: {} ({:?})", self.largest_gap, sender),
+            None => write!(f, "Largest nonce gap: none"),
+        }
+    }
+}
+
+/// Polls `txpool_status` and `txpool_content` at a fixed interval and writes a CSV row
+/// per sample: pending and queued counts, queue depths, the largest nonce gap, and the
+/// send counts of the load tool at that moment, so the pool can be lined up with the
+/// send rate. The deepest queues and largest gaps get a row per sender in a second CSV.
+pub struct TxpoolSampler {
+    stop: Arc<AtomicBool>,
+    sampler: JoinHandle<Result<TxpoolSummary>>,
+}
+
+impl TxpoolSampler {
+    pub fn start(
+        provider: Arc<RpcProvider>,
+        board: DashboardHandle,
+        interval: Duration,
+        path: &str,
+        top_senders: usize,
+    ) -> Result<Self> {
+        let samples_path = path.to_string();
+        let senders_path = senders_path(path);
+        let samples = csv_file(&samples_path, SAMPLE_HEADER)?;
+        let senders = csv_file(&senders_path, SENDER_HEADER)?;
+        println!("Sampling the txpool every {:?} into {} and {}", interval, samples_path, senders_path);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let sampler = Sampler {
+            provider,
+            board,
+            interval,
+            top_senders,
+            samples,
+            samples_path,
+            senders,
+            senders_path,
+        };
+        let sampler = tokio::spawn(sampler.run(stop.clone()));
+        Ok(TxpoolSampler { stop, sampler })
+    }
+
+    /// Takes a last sample and stops.
+    pub async fn finish(self) -> Option<TxpoolSummary> {
+        self.stop.store(true, Ordering::SeqCst);
+        match self.sampler.await {
+            Ok(Ok(summary)) => Some(summary),
+            Ok(Err(e)) => {
+                eprintln!("Txpool sampler failed: {}", e);
+                None
+            }
+            Err(e) => {
+                eprintln!("Txpool sampler failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+fn csv_file(path: &str, header: &str) -> Result<BufWriter<File>> {
+    let mut file = BufWriter::new(File::create(path).map_err(io_error(path))?);
+    writeln!(file, "{}", header).map_err(io_error(path))?;
+    Ok(file)
+}
+
+fn io_error(path: &str) -> impl Fn(std::io::Error) -> BridgeError + '_ {
+    move |source| BridgeError::Io { path: path.to_string(), source }
+}
+
+struct Sampler {
+    provider: Arc<RpcProvider>,
+    board: DashboardHandle,
+    interval: Duration,
+    top_senders: usize,
+    samples: BufWriter<File>,
+    samples_path: String,
+    senders: BufWriter<File>,
+    senders_path: String,
+}
+
+impl Sampler {
+    async fn run(mut self, stop: Arc<AtomicBool>) -> Result<TxpoolSummary> {
+        let started = Instant::now();
+        let mut summary = TxpoolSummary::default();
+        let mut last = (started, 0);
+        loop {
+            let stopping = stop.load(Ordering::SeqCst);
+            let sampled_at = Instant::now();
+            let (accepted, failed) = self.board.totals();
+            let secs = sampled_at.duration_since(last.0).as_secs_f64();
+            let send_rate = if secs > 0.0 { (accepted + failed - last.1) as f64 / secs } else { 0.0 };
+            last = (sampled_at, accepted + failed);
+
+            match self.sample(started, accepted, failed, send_rate, &mut summary).await {
+                Ok(()) => summary.samples += 1,
+                Err(e) => {
+                    if summary.failed_samples == 0 {
+                        eprintln!("Txpool sample failed: {}", e);
+                    }
+                    summary.failed_samples += 1;
+                }
+            }
+            if stopping {
+                break;
+            }
+
+            // Wake early to take the last sample soon after `stop`
+            let next = sampled_at + self.interval;
+            while Instant::now() < next && !stop.load(Ordering::SeqCst) {
+                time::sleep((next - Instant::now()).min(Duration::from_millis(100))).await;
+            }
+        }
+        self.samples.flush().map_err(io_error(&self.samples_path))?;
+        self.senders.flush().map_err(io_error(&self.senders_path))?;
+        Ok(summary)
+    }
+
+    async fn sample(
+        &mut self,
+        started: Instant,
+        accepted: usize,
+        failed: usize,
+        send_rate: f64,
+        summary: &mut TxpoolSummary,
+    ) -> Result<()> {
+        let status = self.provider.txpool_status().await?;
+        let transport: &Transport = (*self.provider).as_ref();
+        let content: PoolNonces = transport.request("txpool_content", ()).await
+            .map_err(ProviderError::from)?;
+        let unix_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let elapsed_ms = started.elapsed().as_millis();
+
+        let mut queues: HashMap<Address, SenderQueue> = HashMap::new();
+        for (pool, queued) in [(&content.pending, false), (&content.queued, true)] {
+            for (sender, by_nonce) in pool {
+                let queue = queues.entry(*sender).or_default();
+                let nonces = by_nonce.keys().filter_map(|nonce| nonce.parse::<u64>().ok());
+                if queued {
+                    queue.queued += by_nonce.len();
+                } else {
+                    queue.pending += by_nonce.len();
+                }
+                queue.nonces.extend(nonces);
+            }
+        }
+        for (sender, queue) in queues.iter_mut() {
+            // A nonce can be listed as both pending and queued while it is being replaced
+            queue.nonces.sort_unstable();
+            queue.nonces.dedup();
+            let lowest = queue.nonces.first().copied();
+            queue.queued_first = content.queued.get(sender)
+                .is_some_and(|by_nonce| by_nonce.keys().any(|nonce| nonce.parse::<u64>().ok() == lowest));
+        }
+
+        // The deepest queues, then the senders most likely to have the largest gaps: those
+        // with the most queued transactions, whose gap below the pool needs their mined nonce
+        let mut by_depth: Vec<(&Address, &SenderQueue)> = queues.iter().collect();
+        by_depth.sort_by(|a, b| b.1.depth().cmp(&a.1.depth()).then(a.0.cmp(b.0)));
+        let mut by_queued: Vec<(&Address, &SenderQueue)> = queues.iter().filter(|(_, queue)| queue.queued > 0).collect();
+        by_queued.sort_by(|a, b| b.1.queued.cmp(&a.1.queued).then(a.0.cmp(b.0)));
+
+        let mut rows: Vec<(Address, Option<u64>, u64)> = Vec::new();
+        for (sender, queue) in by_depth.iter().take(self.top_senders).chain(by_queued.iter().take(self.top_senders)) {
+            if rows.iter().any(|(address, _, _)| address == *sender) {
+                continue;
+            }
+            let mut gap = queue.holes();
+            let mut mined = None;
+            if queue.queued_first {
+                let nonce = self.provider.get_transaction_count(**sender, None).await?.as_u64();
+                gap += queue.nonces.first().map_or(0, |lowest| lowest.saturating_sub(nonce));
+                mined = Some(nonce);
+            }
+            rows.push((**sender, mined, gap));
+        }
+
+        for (sender, mined, gap) in &rows {
+            let queue = &queues[sender];
+            writeln!(self.senders, "{},{},{:?},{},{},{},{},{},{}",
+                unix_ms, elapsed_ms, sender, queue.pending, queue.queued,
+                queue.nonces.first().copied().unwrap_or_default(),
+                queue.nonces.last().copied().unwrap_or_default(),
+                mined.map(|nonce| nonce.to_string()).unwrap_or_default(),
+                gap,
+            ).map_err(io_error(&self.senders_path))?;
+        }
+
+        let deepest = by_depth.first().map(|(sender, queue)| (**sender, queue.depth()));
+        let widest = rows.iter().filter(|(_, _, gap)| *gap > 0).max_by_key(|(_, _, gap)| *gap)
+            .map(|(sender, _, gap)| (*sender, *gap));
+        let pending = status.pending.as_u64();
+        let queued = status.queued.as_u64();
+        let queued_senders = by_queued.len();
+        writeln!(self.samples, "{},{},{},{},{},{},{},{},{},{},{},{},{:.1}",
+            unix_ms, elapsed_ms, pending, queued, queues.len(), queued_senders,
+            deepest.map(|(_, depth)| depth).unwrap_or_default(),
+            deepest.map(|(sender, _)| format!("{:?}", sender)).unwrap_or_default(),
+            widest.map(|(_, gap)| gap).unwrap_or_default(),
+            widest.map(|(sender, _)| format!("{:?}", sender)).unwrap_or_default(),
+            accepted, failed, send_rate,
+        ).map_err(io_error(&self.samples_path))?;
+
+        if pending > summary.peak_pending || summary.samples == 0 {
+            summary.peak_pending = pending;
+            summary.peak_pending_at_ms = elapsed_ms;
+            summary.send_rate_at_peak = send_rate;
+        }
+        if queued > summary.peak_queued || summary.samples == 0 {
+            summary.peak_queued = queued;
+            summary.peak_queued_at_ms = elapsed_ms;
+        }
+        summary.peak_queued_senders = summary.peak_queued_senders.max(queued_senders);
+        if let Some((sender, depth)) = deepest.filter(|(_, depth)| *depth > summary.deepest_queue) {
+            summary.deepest_sender = Some(sender);
+            summary.deepest_queue = depth;
+        }
+        if let Some((sender, gap)) = widest.filter(|(_, gap)| *gap > summary.largest_gap) {
+            summary.largest_gap_sender = Some(sender);
+            summary.largest_gap = gap;
+        }
+        Ok(())
+    }
+}
@@ -1,16 +1,29 @@
+use bridge_core::bindings::{EthsentToDestinationChainFilter, SendETHToDestinationChainCall};
+use bridge_core::{NodeArgs, RpcProvider};
+use clap::Parser;
 use ethers::{
     prelude::*,
     types::{H256, Bytes, TransactionReceipt, Log, Address, EIP1186ProofResponse},
     utils::{keccak256, rlp},
-    abi::AbiEncode,
+    abi::{AbiDecode, AbiEncode},
+    contract::{EthCall, EthEvent},
 };
 use web3::types::Proof;
 use eyre::Result;
 use std::sync::Arc;
-use std::env;
 use hex;
 use serde_json;
 
+/// Generates and checks the storage proofs for a cross-chain transfer
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+    /// Hash of the sendETHToDestinationChain transaction
+    tx_hash: H256,
+}
+
 #[derive(Debug)]
 struct CrossChainProof {
     receipt_proof: EIP1186ProofResponse,
@@ -41,27 +54,23 @@ async fn main() -> Result<()> {
 
     // Get RPC URL from .env
     dotenv::dotenv().ok();
-    let rpc_url = env::var("NODE5_RPC")?;
-    let provider = Provider::<Http>::try_from(rpc_url)?;
-    let client = Arc::new(provider);
-
-    // Get transaction hash from command line
-    let tx_hash = env::args()
-        .nth(1)
-        .expect("Transaction hash required")
-        .parse::<H256>()?;
+    let cli = Cli::parse();
+    let node = cli.nodes.node_or("5")?;
+    let client = Arc::new(node.provider().await?);
+    let tx_hash = cli.tx_hash;
 
     println!("Generating proof for transaction: {:?}", tx_hash);
 
     // Generate and verify proof
-    let proof = generate_proof(&client, tx_hash).await?;
+    let proof = generate_proof(&client, node.contract, tx_hash).await?;
     verify_proof(&proof)?;
 
     Ok(())
 }
 
 async fn generate_proof(
-    client: &Provider<Http>,
+    client: &RpcProvider,
+    contract_addr: Address,
     tx_hash: H256,
 ) -> Result<CrossChainProof> {
     // First verify the chain/network
@@ -83,8 +92,7 @@ async fn generate_proof(
     println!("Receipts Root: {:?}", block.receipts_root);
 
     // Get event details
-    let event_signature_str = "ETHSentToDestinationChain(uint32,address,address,uint32,uint256)";
-    let event_signature = H256::from(keccak256(event_signature_str.as_bytes()));
+    let event_signature = EthsentToDestinationChainFilter::signature();
     let event = receipt.logs.iter()
         .find(|log| log.topics[0] == event_signature)
         .expect("Cross-chain event not found");
@@ -137,10 +145,6 @@ async fn generate_proof(
             .unwrap_or(0)
     );
 
-    // Get contract address from environment
-    let contract_addr = env::var("NODE5_CONTRACT")?
-        .parse::<Address>()
-        .expect("Invalid contract address in CONTRACT");
     let tx_addr = receipt.to.unwrap();
     
     println!("\nContract addresses:");
@@ -177,22 +181,19 @@ async fn generate_proof(
     println!("Block time: {}", block.timestamp);
 
     // Debug event signature calculation
-    let event_signature_str = "ETHSentToDestinationChain(uint32,address,address,uint32,uint256)";
-    let event_signature = H256::from(keccak256(event_signature_str.as_bytes()));
     println!("\nLooking for event signature:");
-    println!("Event signature string: {}", event_signature_str);
+    println!("Event signature string: {}", EthsentToDestinationChainFilter::abi_signature());
     println!("Calculated signature: 0x{:x}", event_signature);
 
     // Decode function call
-    let function_sig = "sendETHToDestinationChain(uint32,address)";
-    let function_selector = &keccak256(function_sig.as_bytes())[0..4];
     println!("\nFunction details:");
-    println!("Function signature: {}", function_sig);
-    println!("Expected selector: 0x{}", hex::encode(function_selector));
+    println!("Function signature: {}", SendETHToDestinationChainCall::abi_signature());
+    println!("Expected selector: 0x{}", hex::encode(SendETHToDestinationChainCall::selector()));
     println!("Actual selector: 0x{}", hex::encode(&tx.input[0..4]));
+    let call = SendETHToDestinationChainCall::decode(&tx.input)?;
     println!("Function arguments:");
-    println!("  chainId (uint32): 0x{}", hex::encode(&tx.input[4..36]));
-    println!("  recipient (address): 0x{}", hex::encode(&tx.input[36..68]));
+    println!("  chainId (uint32): {}", call.chain_id);
+    println!("  recipient (address): {:#x}", call.recipient);
 
     let event_clone = event.clone();  // Clone for TransactionInfo
 
@@ -227,7 +228,7 @@ async fn generate_proof(
 }
 
 async fn generate_receipt_proof(
-    client: &Provider<Http>,
+    client: &RpcProvider,
     receipt: &TransactionReceipt,
     block_number: u64,
 ) -> Result<EIP1186ProofResponse> {
@@ -247,7 +248,7 @@ async fn generate_receipt_proof(
 }
 
 async fn generate_event_proof(
-    client: &Provider<Http>,
+    client: &RpcProvider,
     event: &Log,
     block_number: u64,
 ) -> Result<EIP1186ProofResponse> {
@@ -267,7 +268,7 @@ async fn generate_event_proof(
 }
 
 async fn generate_state_proof(
-    client: &Provider<Http>,
+    client: &RpcProvider,
     contract: Address,
     chain_id: H256,
     block_number: u64,
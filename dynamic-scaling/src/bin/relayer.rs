@@ -0,0 +1,418 @@
+use bridge_core::bindings::EthsentToDestinationChainFilter;
+use bridge_core::{monet_contract, MonetSmartContract, NodeConfig, RpcProvider, TopologyArgs};
+use clap::Parser;
+use dotenv::dotenv;
+use ethers::{
+    prelude::*,
+    types::{Address, U256},
+};
+use log::{debug, info, warn};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tokio::time::{timeout, Duration, Instant};
+
+type RelayerClient = SignerMiddleware<Arc<RpcProvider>, LocalWallet>;
+
+/// Watches every configured chain for `ETHSentToDestinationChain` events and
+/// delivers them to the destination chain with `receiveETHFromSourceChain`.
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(flatten)]
+    topology: TopologyArgs,
+    /// Relay between the first n nodes (default: every configured node)
+    #[arg(long)]
+    num_nodes: Option<usize>,
+    /// Block to start scanning source chains from
+    #[arg(long, default_value = "0")]
+    start_block: u64,
+    /// Seconds to wait between polling rounds; on WebSocket/IPC endpoints a new
+    /// bridge event starts the next round right away
+    #[arg(long, default_value = "2")]
+    poll_secs: u64,
+    /// Maximum block range per eth_getLogs request
+    #[arg(long, default_value = "1000")]
+    log_chunk_size: u64,
+    /// Deliver with zero gas price (for rootvx nodes)
+    #[arg(long)]
+    zero_gas_price: bool,
+    /// Deliver contiguous message runs with receiveETHfromSourceChainInBatch
+    #[arg(long)]
+    batch: bool,
+    /// Maximum number of messages per batch
+    #[arg(long, default_value = "50")]
+    batch_size: usize,
+    /// Deliver a partial batch once its oldest message has waited this long
+    #[arg(long, default_value = "5000")]
+    max_batch_wait_ms: u64,
+}
+
+struct RelayChain {
+    node: String,
+    chain_id: u32,
+    contract_addr: Address,
+    contract: MonetSmartContract<RelayerClient>,
+    next_block: U64,
+    /// Bridge events arrive over a log subscription rather than only by polling
+    subscribed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct CrossChainMessage {
+    source_chain: u32,
+    dest_chain: u32,
+    sender: Address,
+    recipient: Address,
+    message_id: u32,
+    amount: U256,
+    tx_hash: H256,
+    seen_at: Instant,
+}
+
+impl CrossChainMessage {
+    fn from_event(source_chain: u32, event: EthsentToDestinationChainFilter, tx_hash: H256) -> Self {
+        CrossChainMessage {
+            source_chain,
+            dest_chain: event.chain_id,
+            sender: event.sender,
+            recipient: event.recipient,
+            message_id: event.message_id,
+            amount: event.amount,
+            tx_hash,
+            seen_at: Instant::now(),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    env_logger::init();
+    dotenv().ok();
+    let cli = Cli::parse();
+    if cli.batch_size == 0 {
+        return Err(eyre::eyre!("--batch-size must be at least 1"));
+    }
+
+    let gas_price = if cli.zero_gas_price {
+        U256::zero()
+    } else {
+        U256::from(1_000_000_000)  // 1 gwei
+    };
+
+    let registry = cli.topology.registry()?;
+    let num_nodes = cli.num_nodes.unwrap_or(registry.nodes().len());
+    if num_nodes > registry.nodes().len() {
+        return Err(eyre::eyre!("--num-nodes {} but only {} nodes are configured",
+            num_nodes, registry.nodes().len()));
+    }
+
+    // Signalled by the log subscriptions whenever a source chain emits a bridge event
+    let activity = Arc::new(Notify::new());
+    let mut chains = Vec::with_capacity(num_nodes);
+    for config in &registry.nodes()[..num_nodes] {
+        chains.push(connect_chain(config, cli.start_block, &activity).await?);
+    }
+
+    println!("Relaying between {} chains, starting at block {}", chains.len(), cli.start_block);
+    if cli.batch {
+        println!("Batch delivery: up to {} messages, max wait {} ms", cli.batch_size, cli.max_batch_wait_ms);
+    }
+    for chain in &chains {
+        println!("  {}: chain ID {}, contract {:#x}, relayer {:#x}, events by {}",
+            chain.node, chain.chain_id, chain.contract_addr, chain.contract.client().address(),
+            if chain.subscribed { "subscription" } else { "polling" });
+    }
+
+    // Undelivered messages keyed by (source chain, destination chain), ordered by message ID
+    let mut pending: HashMap<(u32, u32), BTreeMap<u32, CrossChainMessage>> = HashMap::new();
+    let mut total_delivered = 0usize;
+
+    loop {
+        for idx in 0..chains.len() {
+            let dest_ids: Vec<u32> = chains.iter()
+                .filter(|c| c.chain_id != chains[idx].chain_id)
+                .map(|c| c.chain_id)
+                .collect();
+
+            match scan_source_chain(&mut chains[idx], &dest_ids, cli.log_chunk_size).await {
+                Ok(messages) => {
+                    for msg in messages {
+                        pending.entry((msg.source_chain, msg.dest_chain))
+                            .or_default()
+                            .insert(msg.message_id, msg);
+                    }
+                }
+                Err(e) => warn!("Failed to scan {} for messages: {}", chains[idx].node, e),
+            }
+        }
+
+        for ((source_chain, dest_chain), queue) in pending.iter_mut() {
+            if queue.is_empty() {
+                continue;
+            }
+            let Some(dest) = chains.iter().find(|c| c.chain_id == *dest_chain) else {
+                continue;
+            };
+
+            let result = if cli.batch {
+                let max_wait = Duration::from_millis(cli.max_batch_wait_ms);
+                deliver_batches(dest, *source_chain, queue, gas_price, cli.batch_size, max_wait).await
+            } else {
+                deliver_pending(dest, *source_chain, queue, gas_price).await
+            };
+
+            match result {
+                Ok(delivered) => total_delivered += delivered,
+                Err(e) => warn!("Delivery {} -> {} failed: {}", source_chain, dest_chain, e),
+            }
+        }
+
+        let backlog: usize = pending.values().map(|q| q.len()).sum();
+        debug!("Round complete: {} delivered in total, {} pending", total_delivered, backlog);
+        let _ = timeout(Duration::from_secs(cli.poll_secs), activity.notified()).await;
+    }
+}
+
+async fn connect_chain(config: &NodeConfig, start_block: u64, activity: &Arc<Notify>) -> eyre::Result<RelayChain> {
+    let node = config.name.clone();
+    let chain_id = config.chain_id;
+    let contract_addr = config.contract;
+
+    let relayer_key = config.relayer_key()
+        .ok_or_else(|| eyre::eyre!("No relayer key configured for {} (relayer_keys, NODE{}_RELAYER_KEY or RELAYER_KEY)",
+            node, config.index))?;
+
+    let provider = Arc::new(config.provider().await?);
+    let network_chain_id = provider.get_chainid().await?.as_u64();
+
+    let subscribed = match config.pubsub(&provider).await {
+        Ok(Some(pubsub)) => {
+            tokio::spawn(watch_events(node.clone(), pubsub, contract_addr, activity.clone()));
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            warn!("{}: no event subscription, polling only: {}", node, e);
+            false
+        }
+    };
+    let wallet = relayer_key.trim_start_matches("0x").parse::<LocalWallet>()
+        .map_err(|e| eyre::eyre!("Invalid relayer key for {}: {}", node, e))?
+        .with_chain_id(network_chain_id);
+
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+    let contract = monet_contract(contract_addr, client);
+
+    let whitelisted = contract
+        .relayer_whitelist_map(contract.client().address())
+        .call()
+        .await?;
+    if !whitelisted {
+        warn!("Relayer {:#x} is not whitelisted on {}, deliveries to it will revert",
+            contract.client().address(), node);
+    }
+
+    Ok(RelayChain {
+        node,
+        chain_id,
+        contract_addr,
+        contract,
+        next_block: U64::from(start_block),
+        subscribed,
+    })
+}
+
+/// Signals `activity` for every `ETHSentToDestinationChain` log of `contract`, so
+/// messages are picked up without waiting out the poll interval. The logs themselves
+/// are still read by `scan_source_chain`, which keeps catch-up and ordering in one place.
+async fn watch_events(node: String, provider: Arc<RpcProvider>, contract: Address, activity: Arc<Notify>) {
+    let filter = Filter::new()
+        .address(contract)
+        .topic0(EthsentToDestinationChainFilter::signature());
+    match provider.subscribe_logs(&filter).await {
+        Ok(mut logs) => {
+            while logs.next().await.is_some() {
+                activity.notify_one();
+            }
+            warn!("{}: event subscription ended, falling back to polling", node);
+        }
+        Err(e) => warn!("{}: failed to subscribe to bridge events, polling only: {}", node, e),
+    }
+}
+
+async fn scan_source_chain(
+    chain: &mut RelayChain,
+    dest_ids: &[u32],
+    chunk_size: u64,
+) -> eyre::Result<Vec<CrossChainMessage>> {
+    let latest = chain.contract.client().get_block_number().await?;
+    let mut messages = Vec::new();
+
+    if dest_ids.is_empty() {
+        chain.next_block = latest + 1;
+        return Ok(messages);
+    }
+
+    // chainID is the first indexed parameter, so filter on it directly
+    let dest_topics: Vec<H256> = dest_ids.iter()
+        .map(|id| H256::from_low_u64_be(*id as u64))
+        .collect();
+
+    while chain.next_block <= latest {
+        let to_block = std::cmp::min(chain.next_block + chunk_size.saturating_sub(1), latest);
+        let query = chain.contract
+            .eth_sent_to_destination_chain_filter()
+            .topic1(dest_topics.clone())
+            .from_block(chain.next_block)
+            .to_block(to_block)
+            .query_with_meta()
+            .await;
+        // Keep what earlier chunks found; the failed chunk is scanned again next round
+        let events = match query {
+            Ok(events) => events,
+            Err(e) if messages.is_empty() => return Err(e.into()),
+            Err(e) => {
+                warn!("{}: failed to scan blocks {}..={}, retrying next round: {}",
+                    chain.node, chain.next_block, to_block, e);
+                break;
+            }
+        };
+        debug!("{}: {} messages in blocks {}..={}", chain.node, events.len(), chain.next_block, to_block);
+
+        for (event, meta) in events {
+            messages.push(CrossChainMessage::from_event(chain.chain_id, event, meta.transaction_hash));
+        }
+
+        chain.next_block = to_block + 1;
+    }
+
+    Ok(messages)
+}
+
+/// Delivers queued messages for one (source, destination) pair in message ID
+/// order. Messages the destination has already processed are dropped first.
+/// Gaps are expected: `sendMessageToDestinationChain` takes IDs from the same
+/// counter, and the contract only needs each ID to be above the last processed.
+async fn deliver_pending(
+    dest: &RelayChain,
+    source_chain: u32,
+    queue: &mut BTreeMap<u32, CrossChainMessage>,
+    gas_price: U256,
+) -> eyre::Result<usize> {
+    prune_processed(dest, source_chain, queue).await?;
+
+    let mut delivered = 0;
+    while let Some(msg) = queue.first_key_value().map(|(_, m)| m.clone()) {
+        let call = dest.contract
+            .receive_eth_from_source_chain(msg.source_chain, msg.sender, msg.recipient, msg.message_id, msg.amount)
+            .gas(200_000)
+            .gas_price(gas_price);
+
+        let pending_tx = call.send().await?;
+        let tx_hash = pending_tx.tx_hash();
+        let receipt = pending_tx.await?
+            .ok_or_else(|| eyre::eyre!("Delivery {:#x} dropped from mempool", tx_hash))?;
+
+        if receipt.status.unwrap_or_default().as_u64() != 1 {
+            return Err(eyre::eyre!("Delivery of message {} reverted in {:#x}", msg.message_id, tx_hash));
+        }
+
+        info!("Delivered message {} ({} wei to {:#x}) from chain {} to chain {}: source tx {:#x}, delivery tx {:#x}",
+            msg.message_id, msg.amount, msg.recipient, source_chain, dest.chain_id, msg.tx_hash, tx_hash);
+
+        queue.remove(&msg.message_id);
+        delivered += 1;
+    }
+
+    Ok(delivered)
+}
+
+/// Delivers queued messages for one (source, destination) pair as
+/// `receiveETHfromSourceChainInBatch` calls. The contract marks the IDs from the
+/// first one on as processed, so a batch is always the contiguous run starting at
+/// the lowest queued ID. A run that ends at a gap (an ID taken by
+/// `sendMessageToDestinationChain`) cannot grow and is sent right away; the last
+/// run is sent once it is full or its oldest message has waited `max_wait`.
+async fn deliver_batches(
+    dest: &RelayChain,
+    source_chain: u32,
+    queue: &mut BTreeMap<u32, CrossChainMessage>,
+    gas_price: U256,
+    batch_size: usize,
+    max_wait: Duration,
+) -> eyre::Result<usize> {
+    prune_processed(dest, source_chain, queue).await?;
+
+    let mut delivered = 0;
+    while let Some(&first_id) = queue.keys().next() {
+        let batch: Vec<CrossChainMessage> = queue.values()
+            .zip(first_id..)
+            .take_while(|(msg, expected)| msg.message_id == *expected)
+            .map(|(msg, _)| msg.clone())
+            .take(batch_size)
+            .collect();
+
+        let oldest_wait = batch.iter().map(|m| m.seen_at.elapsed()).max().unwrap_or_default();
+        let ends_at_gap = queue.len() > batch.len();
+        if batch.len() < batch_size && !ends_at_gap && oldest_wait < max_wait {
+            debug!("Chain {} -> {}: holding {} messages, oldest waited {:?}",
+                source_chain, dest.chain_id, batch.len(), oldest_wait);
+            break;
+        }
+
+        let last_id = first_id + batch.len() as u32 - 1;
+        let recipients: Vec<Address> = batch.iter().map(|m| m.recipient).collect();
+        let amounts: Vec<U256> = batch.iter().map(|m| m.amount).collect();
+
+        // Each recipient costs one value transfer on top of the base call
+        let gas_limit = 100_000 + 40_000 * batch.len() as u64;
+        let call = dest.contract
+            .receive_eth_from_source_chain_in_batch(source_chain, first_id, recipients, amounts)
+            .gas(gas_limit)
+            .gas_price(gas_price);
+
+        let send_start = Instant::now();
+        let pending_tx = call.send().await?;
+        let tx_hash = pending_tx.tx_hash();
+        let receipt = pending_tx.await?
+            .ok_or_else(|| eyre::eyre!("Batch delivery {:#x} dropped from mempool", tx_hash))?;
+
+        if receipt.status.unwrap_or_default().as_u64() != 1 {
+            return Err(eyre::eyre!("Batch delivery of messages {}..={} reverted in {:#x}",
+                first_id, last_id, tx_hash));
+        }
+
+        let gas_used = receipt.gas_used.unwrap_or_default();
+        let latencies: Vec<Duration> = batch.iter().map(|m| m.seen_at.elapsed()).collect();
+        let avg_latency = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+        let max_latency = latencies.iter().max().copied().unwrap_or_default();
+
+        info!("Delivered batch of {} messages ({}..={}) from chain {} to chain {} in {:#x}: \
+            gas used {} ({} per message), confirmation {:?}, latency avg {:?} max {:?}",
+            batch.len(), first_id, last_id, source_chain, dest.chain_id, tx_hash,
+            gas_used, gas_used / U256::from(batch.len()), send_start.elapsed(), avg_latency, max_latency);
+
+        for msg in &batch {
+            queue.remove(&msg.message_id);
+        }
+        delivered += batch.len();
+    }
+
+    Ok(delivered)
+}
+
+/// Drops queued messages the destination has already processed.
+async fn prune_processed(
+    dest: &RelayChain,
+    source_chain: u32,
+    queue: &mut BTreeMap<u32, CrossChainMessage>,
+) -> eyre::Result<()> {
+    let last_processed = dest.contract
+        .get_last_processed_message_id_by_source_chain(source_chain)
+        .call()
+        .await?;
+
+    *queue = queue.split_off(&(last_processed + 1));
+    Ok(())
+}
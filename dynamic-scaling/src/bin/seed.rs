@@ -1,28 +1,40 @@
-use clap::{Parser, Subcommand};
+use bridge_core::bindings::{EthsentToDestinationChainFilter, SendETHToDestinationChainCall};
+use bridge_core::{
+    metrics, monet_contract, Account, AccountStore, Dashboard, DashboardHandle, FeeArgs, Fees, MetricsArgs, NonceManager,
+    Recorder, RpcProvider, RunArgs, TopologyArgs, TxRecord, TxStatus, TxpoolArgs,
+};
+use clap::{CommandFactory, Parser, Subcommand};
 use ethers::{
     prelude::*,
     types::{Address, TransactionRequest, transaction::eip2718::TypedTransaction, U256},
-    abi::{RawLog, Token},
+    abi::AbiEncode,
+    contract::parse_log,
 };
-use serde::{Deserialize, Serialize};
-use serde_json::{Value, json};
-use std::{fs, sync::Arc};
+use std::{fs, future::Future, sync::Arc};
 use tokio::time::Instant;
 use dotenv::dotenv;
 use std::env;
-use std::time::{SystemTime, UNIX_EPOCH};
-use std::fs::OpenOptions;
-use std::io::{BufWriter, Write};
-use std::collections::HashMap;
-use tokio::time::{sleep, Duration};
+use std::collections::{hash_map::Entry, HashMap};
+use tokio::time::{sleep, timeout, Duration};
 use tokio::sync::Semaphore;
 use log::{debug, info, warn};
-use env_logger;
-use rand;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    #[command(flatten)]
+    topology: TopologyArgs,
+    /// Transaction type and fees; zero-fee legacy unless set
+    #[command(flatten)]
+    fees: FeeArgs,
+    #[command(flatten)]
+    metrics: MetricsArgs,
+    /// Txpool sampling during the burst commands
+    #[command(flatten)]
+    txpool: TxpoolArgs,
+    /// Where the send commands file their manifest and transaction records
+    #[command(flatten)]
+    runs: RunArgs,
     #[command(subcommand)]
     command: Commands,
 }
@@ -38,15 +50,15 @@ enum Commands {
     /// Defund all accounts in a specific node back to master wallet
     DefundNode {
         #[arg(long)]
-        node: usize,
+        node: String,
     },
     /// Send ETH cross-chain one-way between two nodes
     #[command(name = "send-eth-1way")]
     SendEth1way {
         #[arg(long)]
-        from_node: usize,  // Source node ID
+        from_node: String,  // Source node name or number
         #[arg(long)]
-        to_node: usize,    // Destination node ID
+        to_node: String,    // Destination node name or number
         #[arg(long)]
         num_accounts: usize,
         #[arg(long)]
@@ -57,14 +69,14 @@ enum Commands {
     /// Fund sender accounts of a specific node
     FundNode {
         #[arg(long)]
-        node: usize,
+        node: String,
         #[arg(long)]
         amount_eth: f64,
     },
     /// Get balances of all sender accounts for a specific node
     NodeBalances {
         #[arg(long)]
-        node: usize,
+        node: String,
     },
     /// Send ETH cross-chain N-way between all nodes
     #[command(name = "send-eth-nway")]
@@ -81,52 +93,28 @@ enum Commands {
     /// Send many transactions quickly using same sender and receiver
     #[command(name = "send-eth-burst")]
     SendEthBurst {
-        #[arg(long)]
-        from_node: usize,
-        #[arg(long)]
-        to_node: usize,
-        #[arg(long)]
-        num_txs: usize,
-        #[arg(long)]
-        amount_wei: U256,
-        #[arg(long)]
-        zero_gas_price: bool,  // This will be treated as a flag
+        #[command(flatten)]
+        burst: BurstArgs,
     },
     /// Generate new sender and receiver accounts for a single node
     #[command(name = "prepare-new")]
     PrepareNew {
         #[arg(long)]
-        node: usize,
+        node: String,
         #[arg(long)]
         num_accounts: usize,
     },
     /// Send many transactions quickly without waiting for receipts
     #[command(name = "send-eth-burst-no-receipt")]
     SendEthBurstNoReceipt {
-        #[arg(long)]
-        from_node: usize,
-        #[arg(long)]
-        to_node: usize,
-        #[arg(long)]
-        num_txs: usize,
-        #[arg(long)]
-        amount_wei: U256,
-        #[arg(long)]
-        zero_gas_price: bool,
+        #[command(flatten)]
+        burst: BurstArgs,
     },
     /// Send transactions continuously for extended periods without receipt checking
     #[command(name = "send-eth-coh-no-receipt")]
     SendEthCohNoReceipt {
-        #[arg(long)]
-        from_node: usize,
-        #[arg(long)]
-        to_node: usize,
-        #[arg(long)]
-        num_txs: usize,
-        #[arg(long)]
-        amount_wei: U256,
-        #[arg(long)]
-        zero_gas_price: bool,
+        #[command(flatten)]
+        burst: BurstArgs,
         #[arg(long)]
         iterations: Option<usize>,  // None means run indefinitely
         #[arg(long, default_value = "0")]
@@ -134,15 +122,93 @@ enum Commands {
     },
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Account {
-    private_key: String,
-    address: String,
+impl Commands {
+    // Workload and node labels of the metrics
+    fn labels(&self) -> (&'static str, String) {
+        match self {
+            Commands::Prepare { .. } => ("prepare", "all".to_string()),
+            Commands::DefundNode { node } => ("defund-node", node.clone()),
+            Commands::SendEth1way { from_node, .. } => ("send-eth-1way", from_node.clone()),
+            Commands::FundNode { node, .. } => ("fund-node", node.clone()),
+            Commands::NodeBalances { node } => ("node-balances", node.clone()),
+            Commands::SendEthNway { num_nodes, .. } => ("send-eth-nway", format!("1-{}", num_nodes)),
+            Commands::SendEthBurst { burst } => ("send-eth-burst", burst.from_node.clone()),
+            Commands::PrepareNew { node, .. } => ("prepare-new", node.clone()),
+            Commands::SendEthBurstNoReceipt { burst } => ("send-eth-burst-no-receipt", burst.from_node.clone()),
+            Commands::SendEthCohNoReceipt { burst, .. } => ("send-eth-coh-no-receipt", burst.from_node.clone()),
+        }
+    }
+
+    // Parameters of the send commands for their run manifest, and the nodes they talk to
+    fn run_spec(&self) -> (serde_json::Value, Vec<String>) {
+        match self {
+            Commands::SendEth1way { from_node, to_node, num_accounts, amount_wei, rounds } => (
+                serde_json::json!({
+                    "from_node": from_node,
+                    "to_node": to_node,
+                    "num_accounts": num_accounts,
+                    "amount_wei": amount_wei.to_string(),
+                    "rounds": rounds,
+                }),
+                vec![from_node.clone(), to_node.clone()],
+            ),
+            Commands::SendEthNway { num_nodes, num_accounts, amount_wei, rounds } => (
+                serde_json::json!({
+                    "num_nodes": num_nodes,
+                    "num_accounts": num_accounts,
+                    "amount_wei": amount_wei.to_string(),
+                    "rounds": rounds,
+                }),
+                (1..=*num_nodes).map(|n| n.to_string()).collect(),
+            ),
+            Commands::SendEthBurst { burst } | Commands::SendEthBurstNoReceipt { burst } => {
+                (burst.parameters(), vec![burst.from_node.clone(), burst.to_node.clone()])
+            }
+            Commands::SendEthCohNoReceipt { burst, iterations, delay_secs } => {
+                let mut parameters = burst.parameters();
+                parameters["iterations"] = serde_json::json!(iterations);
+                parameters["delay_secs"] = serde_json::json!(delay_secs);
+                (parameters, vec![burst.from_node.clone(), burst.to_node.clone()])
+            }
+            _ => (serde_json::Value::Null, Vec::new()),
+        }
+    }
+}
+
+/// Flags shared by the burst commands, which send from the first sender of one node
+/// to the first receiver of another.
+#[derive(clap::Args)]
+struct BurstArgs {
+    #[arg(long)]
+    from_node: String,
+    #[arg(long)]
+    to_node: String,
+    #[arg(long)]
+    num_txs: usize,
+    #[arg(long)]
+    amount_wei: U256,
+    #[arg(long)]
+    zero_gas_price: bool,  // This will be treated as a flag
+    /// Show a live dashboard of send rate, errors, txpool and blocks, refreshed every
+    /// second; a log line a second when stdout is not a terminal
+    #[arg(long)]
+    dashboard: bool,
+}
+
+impl BurstArgs {
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "from_node": self.from_node,
+            "to_node": self.to_node,
+            "num_txs": self.num_txs,
+            "amount_wei": self.amount_wei.to_string(),
+            "zero_gas_price": self.zero_gas_price,
+        })
+    }
 }
 
 #[derive(Debug)]
 struct TxInfo {
-    #[allow(dead_code)]
     round: usize,
     hash: H256,
     from_chain: u32,
@@ -150,6 +216,37 @@ struct TxInfo {
     from_addr: Address,
     to_addr: Address,
     amount: U256,
+    contract: Address,
+    nonce: u64,
+    sent_at: Instant,
+}
+
+impl TxInfo {
+    // Transaction record of the run, without the outcome details
+    fn record(&self, status: TxStatus) -> TxRecord {
+        let mut record = transfer_record(self.from_chain, self.to_chain, self.from_addr, self.to_addr, self.amount, status);
+        record.round = Some(self.round);
+        record.to = Some(self.contract);
+        record.nonce = Some(self.nonce);
+        record.hash = (!self.hash.is_zero()).then_some(self.hash);
+        record.timed(self.sent_at.into_std(), None)
+    }
+}
+
+// Record of a sendETHToDestinationChain call, the transaction all send commands make
+fn transfer_record(from_chain: u32, to_chain: u32, from: Address, recipient: Address, amount: U256, status: TxStatus) -> TxRecord {
+    let mut record = TxRecord::new("send-eth", from_chain as u64, from, status);
+    record.destination_chain_id = Some(to_chain as u64);
+    record.recipient = Some(recipient);
+    record.value_wei = Some(amount);
+    record
+}
+
+// Message ID of the ETHSentToDestinationChain event in a receipt
+fn message_id(receipt: &TransactionReceipt) -> Option<u64> {
+    receipt.logs.iter()
+        .find_map(|log| parse_log::<EthsentToDestinationChainFilter>(log.clone()).ok())
+        .map(|event| event.message_id as u64)
 }
 
 #[derive(Debug)]
@@ -171,16 +268,34 @@ fn main() {
     
     dotenv().ok();
     let cli = Cli::parse();
+    let topology = cli.topology;
+    let fees = cli.fees;
+    let txpool = cli.txpool;
+    let runs = cli.runs;
+
+    // Scrapes are answered on a runtime of their own, which outlives each command's runtime
+    let _metrics_runtime = cli.metrics.metrics_addr.map(|_| {
+        let runtime = tokio::runtime::Runtime::new()
+            .expect("Failed to create Tokio runtime");
+        let (workload, node) = cli.command.labels();
+        if let Err(err) = runtime.block_on(cli.metrics.serve(&[("tool", "seed"), ("node", &node), ("workload", workload)])) {
+            eprintln!("Error serving metrics: {}", err);
+        }
+        runtime
+    });
 
-    match cli.command {
+    let command = cli.command;
+    match &command {
         Commands::Prepare { num_accounts, num_nodes } => {
-            prepare_node_accounts(num_accounts, num_nodes);
+            if let Err(err) = prepare_node_accounts(&topology, *num_accounts, *num_nodes) {
+                eprintln!("Error preparing node accounts: {}", err);
+            }
         }
         Commands::DefundNode { node } => {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(defund_node(node)) {
+            if let Err(err) = runtime.block_on(defund_node(&topology, &fees, node)) {
                 eprintln!("Error defunding node {}: {}", node, err);
             }
         }
@@ -188,9 +303,10 @@ fn main() {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(send_eth_crosschain(
-                from_node, to_node, num_accounts, amount_wei, rounds
-            )) {
+            let (topology, fees) = (&topology, &fees);
+            if let Err(err) = runtime.block_on(filed(&runs, topology, &command, Vec::new(), |records| async move {
+                send_eth_crosschain(topology, fees, from_node, to_node, *num_accounts, *amount_wei, *rounds, &records).await
+            })) {
                 eprintln!("Error sending cross-chain ETH: {}", err);
             }
         }
@@ -198,7 +314,7 @@ fn main() {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(fund_node(node, amount_eth)) {
+            if let Err(err) = runtime.block_on(fund_node(&topology, &fees, node, *amount_eth)) {
                 eprintln!("Error funding node {}: {}", node, err);
             }
         }
@@ -206,7 +322,7 @@ fn main() {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(check_node_balances(node)) {
+            if let Err(err) = runtime.block_on(check_node_balances(&topology, node)) {
                 eprintln!("Error checking balances for node {}: {}", node, err);
             }
         }
@@ -214,83 +330,174 @@ fn main() {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(send_eth_crosschain_loop(num_nodes, num_accounts, amount_wei, &rounds)) {
+            let (topology, fees) = (&topology, &fees);
+            if let Err(err) = runtime.block_on(filed(&runs, topology, &command, Vec::new(), |records| async move {
+                send_eth_crosschain_loop(topology, fees, *num_nodes, *num_accounts, *amount_wei, rounds, &records).await
+            })) {
                 eprintln!("Error in N-way ETH transfer: {}", err);
             }
         }
-        Commands::SendEthBurst { from_node, to_node, num_txs, amount_wei, zero_gas_price } => {
+        Commands::SendEthBurst { burst } => {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(send_eth_burst(from_node, to_node, num_txs, amount_wei, zero_gas_price)) {
+            let (topology, fees, txpool) = (&topology, &fees, &txpool);
+            if let Err(err) = runtime.block_on(filed(&runs, topology, &command, txpool.files(), |records| async move {
+                with_dashboard(topology, burst, txpool, "send-eth-burst", |board| {
+                    send_eth_burst(topology, fees, burst, board, &records)
+                }).await
+            })) {
                 eprintln!("Error in ETH burst transfer: {}", err);
             }
         }
         Commands::PrepareNew { node, num_accounts } => {
-            prepare_new_accounts(node, num_accounts);
+            if let Err(err) = prepare_new_accounts(&topology, node, *num_accounts) {
+                eprintln!("Error preparing accounts for node {}: {}", node, err);
+            }
         }
-        Commands::SendEthBurstNoReceipt { from_node, to_node, num_txs, amount_wei, zero_gas_price } => {
+        Commands::SendEthBurstNoReceipt { burst } => {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(send_eth_burst_no_receipt(
-                from_node, 
-                to_node, 
-                num_txs, 
-                amount_wei, 
-                zero_gas_price
-            )) {
+            let (topology, fees, txpool) = (&topology, &fees, &txpool);
+            if let Err(err) = runtime.block_on(filed(&runs, topology, &command, txpool.files(), |records| async move {
+                with_dashboard(topology, burst, txpool, "send-eth-burst-no-receipt", |board| {
+                    send_eth_burst_no_receipt(topology, fees, burst, board, &records, 1)
+                }).await
+            })) {
                 eprintln!("Error in ETH burst transfer: {}", err);
             }
         }
-        Commands::SendEthCohNoReceipt { 
-            from_node, 
-            to_node, 
-            num_txs, 
-            amount_wei, 
-            zero_gas_price,
-            iterations,
-            delay_secs,
-        } => {
+        Commands::SendEthCohNoReceipt { burst, iterations, delay_secs } => {
             let runtime = tokio::runtime::Runtime::new()
                 .expect("Failed to create Tokio runtime");
             
-            if let Err(err) = runtime.block_on(send_eth_coh_no_receipt(
-                from_node, 
-                to_node, 
-                num_txs, 
-                amount_wei, 
-                zero_gas_price,
-                iterations,
-                delay_secs,
-            )) {
+            let (topology, fees, txpool) = (&topology, &fees, &txpool);
+            if let Err(err) = runtime.block_on(filed(&runs, topology, &command, txpool.files(), |records| async move {
+                with_dashboard(topology, burst, txpool, "send-eth-coh-no-receipt", |board| {
+                    send_eth_coh_no_receipt(topology, fees, burst, *iterations, *delay_secs, board, &records)
+                }).await
+            })) {
                 eprintln!("Error in continuous ETH transfer: {}", err);
             }
         }
     }
 }
 
+// Account file named in the topology, or node-{n}.json when using .env
+fn node_account_file(topology: &TopologyArgs, node: &str) -> eyre::Result<String> {
+    if topology.topology.is_some() {
+        return Ok(topology.node(node)?.account_file);
+    }
+    let index: usize = node.trim_start_matches("node").parse()
+        .map_err(|_| eyre::eyre!("Invalid node number: {}", node))?;
+    Ok(AccountStore::node_file(index))
+}
+
+// Run a burst command, with a live dashboard and a txpool sampler over the source node if asked for
+async fn with_dashboard<F, Fut>(
+    topology: &TopologyArgs,
+    burst: &BurstArgs,
+    txpool: &TxpoolArgs,
+    title: &str,
+    run: F,
+) -> eyre::Result<()>
+where
+    F: FnOnce(DashboardHandle) -> Fut,
+    Fut: Future<Output = eyre::Result<()>>,
+{
+    if !burst.dashboard && txpool.txpool_sample_ms.is_none() {
+        return run(DashboardHandle::default()).await;
+    }
+    let client = Arc::new(topology.node(&burst.from_node)?.provider().await?);
+    let dashboard = burst.dashboard.then(|| Dashboard::start(client.clone(), title));
+    let board = dashboard.as_ref().map(Dashboard::handle).unwrap_or_default();
+    let sampler = txpool.start(client, board.clone())?;
+    let result = run(board).await;
+    if let Some(dashboard) = dashboard {
+        dashboard.finish().await;
+    }
+    let summary = match sampler {
+        Some(sampler) => sampler.finish().await,
+        None => None,
+    };
+    if let Some(summary) = summary {
+        println!("\nTxpool:\n{}", summary);
+    }
+    result
+}
+
+// Run a send command filed under --runs-dir: a manifest of its parameters and nodes, the
+// transaction records it writes, and copies of the `files` it leaves behind
+async fn filed<F, Fut>(
+    runs: &RunArgs,
+    topology: &TopologyArgs,
+    command: &Commands,
+    files: Vec<String>,
+    send: F,
+) -> eyre::Result<()>
+where
+    F: FnOnce(Recorder) -> Fut,
+    Fut: Future<Output = eyre::Result<()>>,
+{
+    let (name, _) = command.labels();
+    let (parameters, selectors) = command.run_spec();
+    let mut nodes = Vec::with_capacity(selectors.len());
+    for selector in &selectors {
+        let node = topology.node(selector)?;
+        nodes.push((node.name, node.rpc_url));
+    }
+    let mut run = runs.start("seed", name, parameters, topology.topology.as_deref(), &nodes, &Cli::command()).await?;
+    let result = send(run.recorder()).await;
+    for file in files {
+        run.attach(file);
+    }
+    run.finish(result.as_ref().err().map(ToString::to_string))?;
+    result
+}
+
+// Fill the nonce gaps left by rejected sends so the transactions behind them can be mined
+async fn repair_nonce_gaps(nonces: &NonceManager<RpcProvider>, wallets: &[LocalWallet], fees: &Fees) {
+    match nonces.fill_gaps(wallets, fees).await {
+        Ok(0) => {}
+        Ok(filled) => println!("Filled {} nonce gaps left by rejected transactions", filled),
+        Err(e) => println!("Failed to fill nonce gaps: {}", e),
+    }
+}
+
+// List senders whose mined nonce is still behind the nonces they were given
+async fn report_stuck_senders(nonces: &NonceManager<RpcProvider>) {
+    match nonces.stuck(Duration::ZERO).await {
+        Ok(stuck) if stuck.is_empty() => {}
+        Ok(stuck) => {
+            println!("\n{} senders have unmined nonces:", stuck.len());
+            for sender in stuck {
+                println!("  {:?}: mined up to nonce {}, issued up to {}, open gaps {:?}",
+                    sender.address, sender.mined, sender.issued, sender.gaps);
+            }
+        }
+        Err(e) => println!("Failed to check sender nonces: {}", e),
+    }
+}
+
 // Helper function to format Wei to ETH
 fn format_eth(wei: U256) -> String {
     let eth = wei.as_u128() as f64 / 1e18;
     format!("{:.6}", eth)
 }
 
-fn prepare_node_accounts(accounts_per_node: usize, num_nodes: usize) {
+fn prepare_node_accounts(topology: &TopologyArgs, accounts_per_node: usize, num_nodes: usize) -> eyre::Result<()> {
     // Read the accounts.json file
     let accounts_file = fs::read_to_string("../accounts.json")
-        .expect("Failed to read accounts.json");
-    let accounts: Value = serde_json::from_str(&accounts_file)
-        .expect("Failed to parse accounts.json");
-
-    let accounts_array = accounts.as_array()
-        .expect("Accounts should be an array");
+        .map_err(|e| eyre::eyre!("Failed to read accounts.json: {}", e))?;
+    let accounts: Vec<Account> = serde_json::from_str(&accounts_file)
+        .map_err(|e| eyre::eyre!("Failed to parse accounts.json: {}", e))?;
 
     // Calculate total accounts needed
     let total_accounts_needed = accounts_per_node * 2 * num_nodes;
-    if accounts_array.len() < total_accounts_needed {
-        panic!("Not enough accounts in accounts.json. Need {} accounts but found {}", 
-            total_accounts_needed, accounts_array.len());
+    if accounts.len() < total_accounts_needed {
+        return Err(eyre::eyre!("Not enough accounts in accounts.json. Need {} accounts but found {}",
+            total_accounts_needed, accounts.len()));
     }
 
     // Create node files
@@ -298,48 +505,37 @@ fn prepare_node_accounts(accounts_per_node: usize, num_nodes: usize) {
         let start_idx = node_idx * accounts_per_node * 2;
         
         // Get sender accounts
-        let senders: Vec<Value> = accounts_array[start_idx..start_idx + accounts_per_node]
+        let senders = accounts[start_idx..start_idx + accounts_per_node]
             .to_vec();
         
         // Get receiver accounts
-        let receivers: Vec<Value> = accounts_array[start_idx + accounts_per_node..start_idx + accounts_per_node * 2]
+        let receivers = accounts[start_idx + accounts_per_node..start_idx + accounts_per_node * 2]
             .to_vec();
 
-        // Create node configuration
-        let node_config = json!({
-            "senders": senders,
-            "receivers": receivers
-        });
-
-        // Write to file
-        let filename = format!("node-{}.json", node_idx + 1);
-        fs::write(
-            &filename,
-            serde_json::to_string_pretty(&node_config).unwrap()
-        ).expect(&format!("Failed to write {}", filename));
+        // Write node configuration
+        let filename = node_account_file(topology, &(node_idx + 1).to_string())?;
+        AccountStore::new(senders, receivers).save(&filename)?;
 
         println!("Created {}", filename);
     }
+    Ok(())
 }
 
-async fn defund_node(node: usize) -> eyre::Result<()> {
+async fn defund_node(topology: &TopologyArgs, fees: &FeeArgs, node: &str) -> eyre::Result<()> {
     // Get master wallet address from .env
     let master_address = env::var("MASTER_WALLET_ADDRESS")
         .expect("MASTER_WALLET_ADDRESS must be set in .env file");
     let master_address: Address = master_address.parse()?;
 
-    // Get node-specific RPC URL
-    let rpc_url = env::var(format!("NODE{}_RPC", node))
-        .map_err(|_| eyre::eyre!("NODE{}_RPC not set in .env", node))?;
+    let node_config = topology.node(node)?;
+    let rpc_url = &node_config.rpc_url;
     
     // Connect to network
-    let provider = Provider::<Http>::try_from(rpc_url.clone())?;
-    let client = Arc::new(provider);
+    let client = Arc::new(node_config.provider().await?);
+    let fees = fees.resolve(client.as_ref(), Some(U256::zero())).await?;
 
     // Read node file
-    let filename = format!("node-{}.json", node);
-    let file_content = fs::read_to_string(&filename)?;
-    let node_data: Value = serde_json::from_str(&file_content)?;
+    let node_accounts = node_config.accounts()?;
     
     println!("Starting to defund Node {} accounts...", node);
     println!("Using RPC URL: {}", rpc_url);
@@ -350,17 +546,11 @@ async fn defund_node(node: usize) -> eyre::Result<()> {
     let mut total_failed = 0;
 
     // Process both senders and receivers
-    for account_type in ["senders", "receivers"] {
-        let accounts = node_data[account_type].as_array()
-            .ok_or_else(|| eyre::eyre!("{} not found in {}", account_type, filename))?;
-
+    for (account_type, accounts) in [("senders", &node_accounts.senders), ("receivers", &node_accounts.receivers)] {
         println!("\nProcessing {} accounts...", account_type);
         
         for (idx, account) in accounts.iter().enumerate() {
-            let private_key = account["private_key"].as_str()
-                .ok_or_else(|| eyre::eyre!("Invalid private key format"))?;
-            let wallet = private_key.parse::<LocalWallet>()?;
-            let wallet = wallet.with_chain_id(client.get_chainid().await?.as_u64());
+            let wallet = account.wallet(client.get_chainid().await?.as_u64())?;
             
             let address = wallet.address();
             let balance = client.get_balance(address, None).await?;
@@ -369,13 +559,12 @@ async fn defund_node(node: usize) -> eyre::Result<()> {
                 println!("\nDefunding {} account {} ({})...", account_type, idx + 1, address);
                 println!("  Current balance: {} wei ({} ETH)", balance, format_eth(balance));
 
-                // Calculate gas cost for transfer
-                let gas_price = U256::zero();  // Using zero gas price
+                // Calculate the most the transfer can pay for gas
                 let gas_limit = U256::from(21_000);
-                let gas_cost = gas_price * gas_limit;
+                let gas_cost = fees.max_price_per_gas() * gas_limit;
                 
                 // Send entire balance minus gas cost
-                let transfer_amount = balance - gas_cost;
+                let transfer_amount = balance.saturating_sub(gas_cost);
                 
                 if transfer_amount > U256::zero() {
                     // Get the current nonce for this account
@@ -386,10 +575,9 @@ async fn defund_node(node: usize) -> eyre::Result<()> {
                         .value(transfer_amount)
                         .from(address)
                         .gas(gas_limit)
-                        .gas_price(gas_price)
                         .nonce(nonce);  // Add the current nonce
 
-                    let typed_tx = TypedTransaction::Legacy(tx);
+                    let typed_tx = fees.typed(tx);
                     match wallet.sign_transaction(&typed_tx).await {
                         Ok(signature) => {
                             let signed_tx = typed_tx.rlp_signed(&signature);
@@ -436,32 +624,27 @@ async fn defund_node(node: usize) -> eyre::Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn send_eth_crosschain(
-    from_node: usize,
-    to_node: usize,
+    topology: &TopologyArgs,
+    fees: &FeeArgs,
+    from_node: &str,
+    to_node: &str,
     num_accounts: usize,
     amount_wei: U256,
     rounds: usize,
+    records: &Recorder,
 ) -> eyre::Result<()> {
     info!("Starting cross-chain ETH transfer");
     debug!("Parameters: from_node={}, to_node={}, num_accounts={}, amount={}, rounds={}", 
         from_node, to_node, num_accounts, amount_wei, rounds);
 
-    // Get source node's details from .env
-    let src_chain_id: u32 = env::var(format!("NODE{}_CHAINID", from_node))?
-        .parse()
-        .map_err(|_| eyre::eyre!("Invalid chain ID format for NODE{}_CHAINID", from_node))?;
-    
-    let contract_addr = env::var(format!("NODE{}_CONTRACT", from_node))?
-        .parse::<Address>()
-        .map_err(|_| eyre::eyre!("Invalid contract address for NODE{}_CONTRACT", from_node))?;
-    
-    let rpc_url = env::var(format!("NODE{}_RPC", from_node))?;
-
-    // Get destination chain ID
-    let dst_chain_id: u32 = env::var(format!("NODE{}_CHAINID", to_node))?
-        .parse()
-        .map_err(|_| eyre::eyre!("Invalid chain ID format for NODE{}_CHAINID", to_node))?;
+    // Get source and destination node details from .env
+    let src_node = topology.node(from_node)?;
+    let dst_node = topology.node(to_node)?;
+    let src_chain_id = src_node.chain_id;
+    let contract_addr = src_node.contract;
+    let dst_chain_id = dst_node.chain_id;
 
     // Store transaction info for later verification
 
@@ -475,77 +658,47 @@ async fn send_eth_crosschain(
     println!("Preparing {} transactions...", expected_total);
     let prep_start = Instant::now();
 
-    // Track nonces for each sender
-    let mut sender_nonces: HashMap<Address, U256> = HashMap::new();
-
     // Read source and destination node files
-    let src_filename = format!("node-{}.json", from_node);
-    let src_content = fs::read_to_string(&src_filename)?;
-    let src_data: Value = serde_json::from_str(&src_content)?;
-
-    let dst_filename = format!("node-{}.json", to_node);
-    let dst_content = fs::read_to_string(&dst_filename)?;
-    let dst_data: Value = serde_json::from_str(&dst_content)?;
+    let src_accounts = src_node.accounts()?;
+    let dst_accounts = dst_node.accounts()?;
 
     // Connect to source node's network
-    let provider = Provider::<Http>::try_from(rpc_url.clone())?;
-    let client = Arc::new(provider);
+    let client = Arc::new(src_node.provider().await?);
     
     // Get chain ID early
     let chain_id = client.get_chainid().await?;
     println!("Connected to network. Chain ID: {}", chain_id);
+    let fees = fees.resolve(client.as_ref(), Some(U256::zero())).await?;
+    println!("Using fees: {}", fees);
 
-    // Create contract instance
-    let contract_json: Value = serde_json::from_slice(
-        include_bytes!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json")
-    )?;
-    let abi: ethers::abi::Abi = serde_json::from_value(contract_json["abi"].clone())?;
-
-    // Create base contract instance for encoding
-    let contract = Contract::new(
-        contract_addr,
-        abi.clone(),
-        client.clone()
-    );
+    // Track nonces for each sender
+    let nonces = Arc::new(NonceManager::new(client.clone()));
+    let mut wallets: Vec<LocalWallet> = Vec::new();
 
     // Prepare all transactions first
     for round in 1..=rounds {
         for acc_idx in 0..num_accounts {
-            let sender = &src_data["senders"][acc_idx];
-            let sender_wallet = sender["private_key"].as_str()
-                .ok_or_else(|| eyre::eyre!("Invalid private key format"))?
-                .parse::<LocalWallet>()?
-                .with_chain_id(chain_id.as_u64());
-
-            let receiver = &dst_data["receivers"][acc_idx];
-            let receiver_addr: Address = receiver["address"].as_str()
-                .ok_or_else(|| eyre::eyre!("Invalid receiver address"))?
-                .parse()
-                .map_err(|e| eyre::eyre!("Failed to parse receiver address: {}", e))?;
+            let sender_wallet = src_accounts.sender(acc_idx)?.wallet(chain_id.as_u64())?;
+            let receiver_addr = dst_accounts.receiver(acc_idx)?.address()?;
 
             // Get or initialize nonce
             let sender_address = sender_wallet.address();
-            
-            let nonce = if let Some(current_nonce) = sender_nonces.get(&sender_address) {
-                debug!("Using cached nonce {} for sender {:?}", current_nonce, sender_address);
-                *current_nonce
-            } else {
-                let current_nonce = client.get_transaction_count(sender_address, None).await?;
-                debug!("Got initial nonce {} for sender {:?}", current_nonce, sender_address);
-                sender_nonces.insert(sender_address, current_nonce);
-                current_nonce
-            };
+            if !wallets.iter().any(|wallet| wallet.address() == sender_address) {
+                let start_nonce = nonces.track(sender_address).await?;
+                debug!("Got initial nonce {} for sender {:?}", start_nonce, sender_address);
+                wallets.push(sender_wallet.clone());
+            }
+            let nonce = nonces.next(sender_address)?;
 
             // Create transaction with tracked nonce
             let tx = TransactionRequest::new()
                 .to(contract_addr)
                 .value(amount_wei)
                 .gas(70_000)
-                .gas_price(U256::zero())
                 .nonce(nonce)  // Use tracked nonce
-                .data(contract.encode("sendETHToDestinationChain", (dst_chain_id, receiver_addr))?);
+                .data(SendETHToDestinationChainCall { chain_id: dst_chain_id, recipient: receiver_addr }.encode());
 
-            let typed_tx = TypedTransaction::Legacy(tx);
+            let typed_tx = fees.typed(tx);
 
             prepared_txs.push(PreparedTx {
                 tx: typed_tx,
@@ -558,14 +711,11 @@ async fn send_eth_crosschain(
                     from_addr: sender_wallet.address(),
                     to_addr: receiver_addr,
                     amount: amount_wei,
+                    contract: contract_addr,
+                    nonce,
+                    sent_at: Instant::now(),
                 },
             });
-
-            // Increment nonce for next use
-            if let Some(current_nonce) = sender_nonces.get_mut(&sender_address) {
-                *current_nonce = nonce + U256::from(1);
-                debug!("Incremented nonce to {} for sender {:?}", current_nonce, sender_address);
-            }
         }
     }
 
@@ -583,26 +733,43 @@ async fn send_eth_crosschain(
     for prepared in prepared_txs.into_iter() {
         let client = client.clone();
         let semaphore = semaphore.clone();
+        let nonces = nonces.clone();
+        let records = records.clone();
         
         handles.push(tokio::spawn(async move {
             let _permit = semaphore.acquire().await.unwrap();
             match prepared.wallet.sign_transaction(&prepared.tx).await {
                 Ok(signature) => {
                     let signed_tx = prepared.tx.rlp_signed(&signature);
+                    let sent_at = Instant::now();
+                    metrics::submitted(1);
                     match client.send_raw_transaction(signed_tx).await {
                         Ok(tx) => {
+                            metrics::accepted(1);
                             let mut info = prepared.info;
+                            metrics::crosschain_message(info.from_chain as u64, info.to_chain as u64, "sent");
                             info.hash = tx.tx_hash();
+                            info.sent_at = sent_at;
                             Ok(info)
                         }
                         Err(e) => {
+                            metrics::rejected(1);
+                            metrics::crosschain_message(prepared.info.from_chain as u64, prepared.info.to_chain as u64, "rejected");
                             println!("Failed to send transaction: {}", e);
+                            let nonce = prepared.tx.nonce().copied().unwrap_or_default().as_u64();
+                            nonces.rejected(prepared.info.from_addr, nonce, &e.to_string());
+                            let mut record = TxInfo { sent_at, ..prepared.info }.record(TxStatus::Rejected);
+                            record.error = Some(e.to_string());
+                            records.record(record);
                             Err(eyre::eyre!("Transaction failed: {}", e))
                         }
                     }
                 }
                 Err(e) => {
                     println!("Failed to sign transaction: {}", e);
+                    let mut record = prepared.info.record(TxStatus::Rejected);
+                    record.error = Some(format!("Signing failed: {}", e));
+                    records.record(record);
                     Err(eyre::eyre!("Signing failed: {}", e))
                 }
             }
@@ -630,27 +797,21 @@ async fn send_eth_crosschain(
     } else {
         println!("No transactions were sent successfully");
     }
+    repair_nonce_gaps(&nonces, &wallets, &fees).await;
 
     // After sending transactions, before receipt checking
     println!("\nAll transactions sent. Checking receipts...");
     println!("Number of transactions to check: {}", transactions.len());
 
-    // Create log file
-    let log_file = OpenOptions::new()
-        .create(true)
-        .read(true)  // Add read permission to read back the file
-        .append(true)
-        .open("eth-transfer-1way.log")?;
-    let mut log = BufWriter::new(log_file);
-
-    // After creating log file, add block stats tracking
+    // Track block stats while waiting
     let mut block_stats: HashMap<U64, BlockStats> = HashMap::new();
     let mut successful = 0;
     let mut failed = 0;
 
-    // Wait for all transaction receipts with timeout
+    // Wait for all transaction receipts with timeout, checking again on every new block
     let max_wait = Duration::from_secs(60); // Maximum wait time of 60 seconds
     let start_wait = Instant::now();
+    let mut heads = src_node.watch_heads(&client, Duration::from_secs(1)).await?;
 
     while !transactions.is_empty() && start_wait.elapsed() < max_wait {
         debug!("Checking {} pending transactions...", transactions.len());
@@ -662,86 +823,40 @@ async fn send_eth_crosschain(
                     debug!("Got receipt for tx: {:#x}", tx_info.hash);
                     let block_num = receipt.block_number.unwrap_or_default();
                     
-                    // Update block statistics
-                    let stats = block_stats.entry(block_num).or_insert(BlockStats {
-                        tx_count: 0,
-                        total_gas_used: U256::zero(),
-                        our_gas_used: U256::zero(),
-                    });
+                    // Update block statistics, reading the block's total gas once, with its first
+                    // receipt; block_observer records every block, ours or not
+                    let stats = match block_stats.entry(block_num) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            let total_gas_used = match client.get_block(block_num).await {
+                                Ok(Some(block)) => block.gas_used,
+                                _ => U256::zero(),
+                            };
+                            entry.insert(BlockStats {
+                                tx_count: 0,
+                                total_gas_used,
+                                our_gas_used: U256::zero(),
+                            })
+                        }
+                    };
                     stats.tx_count += 1;
                     stats.our_gas_used += receipt.gas_used.unwrap_or_default();
-
-                    // Get block info for total gas used
-                    if let Ok(block) = client.get_block(block_num).await {
-                        if let Some(block) = block {
-                            stats.total_gas_used = block.gas_used;
-                        }
-                    }
-
-                    if receipt.status.unwrap().as_u64() == 1 {
-                        debug!("Transaction successful, writing to log...");
-                        // Get message ID from logs more safely
-                        let message_id = if let Some(log) = receipt.logs.first() {
-                            // Create raw log for parsing
-                            let raw_log = RawLog {
-                                topics: log.topics.clone(),
-                                data: log.data.to_vec(),
-                            };
-
-                            // Create contract instance for event parsing
-                            let contract_json: Value = serde_json::from_slice(
-                                include_bytes!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json")
-                            )?;
-                            let abi: ethers::abi::Abi = serde_json::from_value(contract_json["abi"].clone())?;
-
-                            // Find and parse the event
-                            if let Some(event) = abi.events().find(|e| e.name == "ETHSentToDestinationChain") {
-                                if let Ok(parsed) = event.parse_log(raw_log) {
-                                    // Find messageId parameter
-                                    if let Some(param) = parsed.params.iter().find(|p| p.name == "messageId") {
-                                        if let Token::Uint(val) = &param.value {
-                                            val.as_u64()
-                                        } else {
-                                            0
-                                        }
-                                    } else {
-                                        0
-                                    }
-                                } else {
-                                    0
-                                }
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        };
-                        writeln!(log, "success,{},{},{},{},{},{},{},{},{},{}",
-                            block_num,
-                            message_id,
-                            format!("{:#x}", tx_info.hash),
-                            1,
-                            tx_info.from_chain,
-                            tx_info.to_chain,
-                            format!("{:x}", tx_info.from_addr),
-                            format!("{:x}", tx_info.to_addr),
-                            tx_info.amount,
-                            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
-                        )?;
+                    metrics::included(tx_info.sent_at.elapsed());
+
+                    let succeeded = receipt.status.unwrap().as_u64() == 1;
+                    let mut record = tx_info.record(if succeeded { TxStatus::Included } else { TxStatus::Reverted })
+                        .timed(tx_info.sent_at.into_std(), Some(std::time::Instant::now()));
+                    record.block_number = Some(block_num.as_u64());
+                    record.gas_used = receipt.gas_used.map(|gas| gas.as_u64());
+                    record.message_id = message_id(&receipt);
+                    records.record(record);
+                    if succeeded {
+                        debug!("Transaction successful: {:#x}", tx_info.hash);
+                        metrics::crosschain_message(tx_info.from_chain as u64, tx_info.to_chain as u64, "succeeded");
                         successful += 1;
                     } else {
                         warn!("Transaction failed: {:#x}", tx_info.hash);
-                        writeln!(log, "failed,{},{},{},{},{},0x{:?},0x{:?},{},{}",
-                            block_num,
-                            format!("{:#x}", tx_info.hash),
-                            1,
-                            tx_info.from_chain,
-                            tx_info.to_chain,
-                            tx_info.from_addr,
-                            tx_info.to_addr,
-                            tx_info.amount,
-                            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
-                        )?;
+                        metrics::crosschain_message(tx_info.from_chain as u64, tx_info.to_chain as u64, "reverted");
                         failed += 1;
                     }
                     completed.push(idx);
@@ -760,11 +875,15 @@ async fn send_eth_crosschain(
 
         if !transactions.is_empty() {
             debug!("Waiting for {} more receipts...", transactions.len());
-            sleep(Duration::from_secs(1)).await;
+            let _ = timeout(max_wait.saturating_sub(start_wait.elapsed()), heads.next()).await;
         }
     }
 
-    log.flush()?;
+    // Whatever is left was accepted but not mined within the wait
+    for tx_info in &transactions {
+        records.record(tx_info.record(TxStatus::Pending));
+    }
+    report_stuck_senders(&nonces).await;
 
     // After the loop, print block statistics
     println!("\nBlock-wise Distribution:");
@@ -794,7 +913,7 @@ async fn send_eth_crosschain(
     Ok(())
 }
 
-async fn fund_node(node: usize, amount_eth: f64) -> eyre::Result<()> {
+async fn fund_node(topology: &TopologyArgs, fees: &FeeArgs, node: &str, amount_eth: f64) -> eyre::Result<()> {
     // Convert ETH to wei
     let amount_wei = U256::from((amount_eth * 1e18) as u64);
     
@@ -804,21 +923,16 @@ async fn fund_node(node: usize, amount_eth: f64) -> eyre::Result<()> {
     let master_wallet = master_key.parse::<LocalWallet>()
         .expect("Invalid master wallet private key");
 
-    // Get node-specific RPC URL
-    let rpc_url = env::var(format!("NODE{}_RPC", node))
-        .map_err(|_| eyre::eyre!("NODE{}_RPC not set in .env", node))?;
+    let node_config = topology.node(node)?;
+    let rpc_url = &node_config.rpc_url;
     
     // Connect to network
-    let provider = Provider::<Http>::try_from(rpc_url.clone())?;
-    let client = Arc::new(provider);
+    let client = Arc::new(node_config.provider().await?);
     let master_wallet = master_wallet.with_chain_id(client.get_chainid().await?.as_u64());
+    let fees = fees.resolve(client.as_ref(), Some(U256::zero())).await?;
 
     // Read node file
-    let filename = format!("node-{}.json", node);
-    let file_content = fs::read_to_string(&filename)?;
-    let node_data: Value = serde_json::from_str(&file_content)?;
-    let senders = node_data["senders"].as_array()
-        .ok_or_else(|| eyre::eyre!("No senders found in {}", filename))?;
+    let senders = node_config.accounts()?.senders;
 
     println!("Starting to fund Node {} sender accounts...", node);
     println!("Using RPC URL: {}", rpc_url);
@@ -834,11 +948,9 @@ async fn fund_node(node: usize, amount_eth: f64) -> eyre::Result<()> {
 
     // Fund each sender account
     for (idx, sender) in senders.iter().enumerate() {
-        let address = sender["address"].as_str()
-            .ok_or_else(|| eyre::eyre!("Invalid address format"))?;
-        let to_address: Address = address.parse()?;
+        let to_address = sender.address()?;
 
-        println!("\nFunding sender account {} ({})...", idx + 1, address);
+        println!("\nFunding sender account {} ({})...", idx + 1, sender.address);
 
         let tx = TransactionRequest::new()
             .to(to_address)
@@ -847,7 +959,7 @@ async fn fund_node(node: usize, amount_eth: f64) -> eyre::Result<()> {
             .gas(21_000)
             .nonce(current_nonce);
 
-        let typed_tx = TypedTransaction::Legacy(tx);
+        let typed_tx = fees.typed(tx);
         let signature = master_wallet.sign_transaction(&typed_tx).await?;
         let signed_tx = typed_tx.rlp_signed(&signature);
         
@@ -877,19 +989,15 @@ async fn fund_node(node: usize, amount_eth: f64) -> eyre::Result<()> {
     Ok(())
 }
 
-async fn check_node_balances(node: usize) -> eyre::Result<()> {
-    // Get node-specific RPC URL
-    let rpc_url = env::var(format!("NODE{}_RPC", node))
-        .map_err(|_| eyre::eyre!("NODE{}_RPC not set in .env", node))?;
+async fn check_node_balances(topology: &TopologyArgs, node: &str) -> eyre::Result<()> {
+    let node_config = topology.node(node)?;
+    let rpc_url = &node_config.rpc_url;
     
     // Connect to network
-    let provider = Provider::<Http>::try_from(rpc_url.clone())?;
-    let client = Arc::new(provider);
+    let client = Arc::new(node_config.provider().await?);
 
     // Read node file
-    let filename = format!("node-{}.json", node);
-    let file_content = fs::read_to_string(&filename)?;
-    let node_data: Value = serde_json::from_str(&file_content)?;
+    let node_accounts = node_config.accounts()?;
 
     println!("\nChecking balances for Node {} accounts...", node);
     println!("Using RPC URL: {}", rpc_url);
@@ -898,16 +1006,11 @@ async fn check_node_balances(node: usize) -> eyre::Result<()> {
     println!("Chain ID: {}", chain_id);
 
     // Process both senders and receivers
-    for account_type in ["senders", "receivers"] {
-        let accounts = node_data[account_type].as_array()
-            .ok_or_else(|| eyre::eyre!("{} not found in {}", account_type, filename))?;
-
+    for (account_type, accounts) in [("senders", &node_accounts.senders), ("receivers", &node_accounts.receivers)] {
         println!("\n{} Accounts:", if account_type == "senders" { "Sender" } else { "Receiver" });
         
         for (idx, account) in accounts.iter().enumerate() {
-            let address = account["address"].as_str()
-                .ok_or_else(|| eyre::eyre!("Invalid address format"))?;
-            let address: Address = address.parse()?;
+            let address = account.address()?;
 
             let balance = client.get_balance(address, None).await?;
             
@@ -924,21 +1027,24 @@ async fn check_node_balances(node: usize) -> eyre::Result<()> {
 }
 
 async fn send_eth_crosschain_loop(
+    topology: &TopologyArgs,
+    fees: &FeeArgs,
     num_nodes: usize,
     num_accounts: usize,
     amount_wei: U256,
     rounds: &str,
+    records: &Recorder,
 ) -> eyre::Result<()> {
     let infinite = rounds == "#";
     let num_rounds = if infinite { 1 } else { rounds.parse::<usize>()? };
     
-    // Create or open log file with new name
-    let log_file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("eth_transfers-Nway.log")?;  // Changed from eth_transfers.log
-    let mut log = BufWriter::new(log_file);
-    
+    let registry = topology.registry()?;
+    if num_nodes > registry.nodes().len() {
+        return Err(eyre::eyre!("--num-nodes {} but only {} nodes are configured",
+            num_nodes, registry.nodes().len()));
+    }
+    let nodes = &registry.nodes()[..num_nodes];
+
     let start_time = Instant::now();
     let mut round = 1;
     let mut successful_transfers = 0;
@@ -948,65 +1054,36 @@ async fn send_eth_crosschain_loop(
         println!("\nStarting round {}", round);
         
         // Reuse existing logic but capture transactions
-        for src_node in 1..=num_nodes {
-            for dst_node in 1..=num_nodes {
-                if src_node != dst_node {
-                    // Change this line to provide both nodes
-                    let chain_ids = get_chain_ids(src_node, dst_node).await?;
-                    let contract_addresses = get_contract_addresses(src_node, dst_node).await?;
+        for src_config in nodes {
+            for dst_config in nodes {
+                if src_config.index != dst_config.index {
+                    let src_node = &src_config.name;
+                    let dst_node = &dst_config.name;
                     
-                    // Get source node details
-                    let src_file = format!("node-{}.json", src_node);
-                    let src_content = fs::read_to_string(&src_file)?;
-                    let src_data: Value = serde_json::from_str(&src_content)?;
+                    // Get source and destination accounts
+                    let src_accounts = src_config.accounts()?;
+                    let dst_accounts = dst_config.accounts()?;
                     
-                    // Get RPC URL for source node
-                    let rpc_url = env::var(format!("NODE{}_RPC", src_node))
-                        .map_err(|_| eyre::eyre!("NODE{}_RPC not set in .env", src_node))?;
-                    
-                    let provider = Provider::<Http>::try_from(rpc_url)?;
-                    let client = Arc::new(provider);
+                    let client = Arc::new(src_config.provider().await?);
+                    let src_fees = fees.resolve(client.as_ref(), Some(U256::zero())).await?;
                     
                     for acc_idx in 0..num_accounts {
-                        let sender = &src_data["senders"][acc_idx];
-                        let sender_key = sender["private_key"].as_str()
-                            .ok_or_else(|| eyre::eyre!("Invalid private key format in {} for sender {}", 
-                                src_file, acc_idx + 1))?;
-                        
-                        let sender_wallet = sender_key.parse::<LocalWallet>()
-                            .map_err(|e| eyre::eyre!("Failed to parse sender private key in {} for account {}: {}", 
-                                src_file, acc_idx + 1, e))?;
-                        
                         let chain_id = client.get_chainid().await
                             .map_err(|e| eyre::eyre!("Failed to get chain ID from Node {} RPC: {}", src_node, e))?;
-                        let sender_wallet = sender_wallet.with_chain_id(chain_id.as_u64());
+                        let sender_wallet = src_accounts.sender(acc_idx)?.wallet(chain_id.as_u64())?;
 
                         // Get receiver address
-                        let dst_file = format!("node-{}.json", dst_node);
-                        let dst_content = fs::read_to_string(&dst_file)?;
-                        let dst_data: Value = serde_json::from_str(&dst_content)?;
-                        let receiver = &dst_data["receivers"][acc_idx];
-                        let receiver_addr: Address = receiver["address"].as_str()
-                            .ok_or_else(|| eyre::eyre!("Invalid receiver address in {} for account {}", 
-                                dst_file, acc_idx + 1))?
-                            .parse()
-                            .map_err(|e| eyre::eyre!("Failed to parse receiver address: {}", e))?;
+                        let receiver_addr = dst_accounts.receiver(acc_idx)?.address()?;
 
                         // Create contract instance for source node
                         println!("\nSending {} wei from Node {} (Chain ID: {}) Account {} to Node {} (Chain ID: {}) Account {}", 
-                            amount_wei, src_node, chain_ids[src_node - 1], acc_idx + 1, 
-                            dst_node, chain_ids[dst_node - 1], acc_idx + 1);
+                            amount_wei, src_node, src_config.chain_id, acc_idx + 1, 
+                            dst_node, dst_config.chain_id, acc_idx + 1);
                         
-                        println!("Using contract {} on Node {}", contract_addresses[src_node - 1], src_node);
+                        println!("Using contract {} on Node {}", src_config.contract, src_node);
 
-                        let contract_json: Value = serde_json::from_slice(
-                            include_bytes!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json")
-                        )?;
-                        let abi: ethers::abi::Abi = serde_json::from_value(contract_json["abi"].clone())?;
-                        
-                        let contract = Contract::new(
-                            contract_addresses[src_node - 1],
-                            abi,
+                        let contract = monet_contract(
+                            src_config.contract,
                             Arc::new(SignerMiddleware::new(
                                 client.clone(),
                                 sender_wallet.clone()
@@ -1015,22 +1092,21 @@ async fn send_eth_crosschain_loop(
 
                         // Print detailed transfer information
                         println!("\nCross-chain Transfer Details:");
-                        println!("  From Node {} (Chain ID: {})", src_node, chain_ids[src_node - 1]);
-                        println!("  To Node {} (Chain ID: {})", dst_node, chain_ids[dst_node - 1]);
+                        println!("  From Node {} (Chain ID: {})", src_node, src_config.chain_id);
+                        println!("  To Node {} (Chain ID: {})", dst_node, dst_config.chain_id);
                         println!("  Amount: {} wei", amount_wei);
                         println!("  Source Account: {}", sender_wallet.address());
                         println!("  Destination Account: {}", receiver_addr);
-                        println!("  Using Contract: {}", contract_addresses[src_node - 1]);
+                        println!("  Using Contract: {}", src_config.contract);
 
                         // Check balances before transfer
                         let sender_balance = client.get_balance(sender_wallet.address(), None).await?;
-                        let gas_price = U256::zero();  // Since we're using zero gas price
                         let gas_limit = U256::from(200_000);  // Increased to 200k to ensure enough gas
-                        let total_needed = amount_wei;  // Only need to check against transfer amount since gas is free
+                        let total_needed = amount_wei + src_fees.max_price_per_gas() * gas_limit;
                         
                         if sender_balance < total_needed {
                             println!("✗ Insufficient funds for cross-chain transfer!");
-                            println!("  Source Chain ID: {}", chain_ids[src_node - 1]);
+                            println!("  Source Chain ID: {}", src_config.chain_id);
                             println!("  Source Address: {}", sender_wallet.address());
                             println!("  Current balance: {} wei ({} ETH)", 
                                 sender_balance, format_eth(sender_balance));
@@ -1042,46 +1118,58 @@ async fn send_eth_crosschain_loop(
                         }
 
                         // Send transaction and log result
-                        match contract.method::<(u32, Address), H256>(
-                            "sendETHToDestinationChain",
-                            (chain_ids[dst_node - 1], receiver_addr)
-                        )?.gas(gas_limit)
-                          .gas_price(gas_price)
-                          .value(amount_wei)
-                          .send()
-                          .await {
+                        let mut call = contract.send_eth_to_destination_chain(dst_config.chain_id, receiver_addr)
+                          .gas(gas_limit)
+                          .value(amount_wei);
+                        call.tx = src_fees.apply(&call.tx);
+                        let (src_chain_id, dst_chain_id) = (src_config.chain_id as u64, dst_config.chain_id as u64);
+                        let sent_at = Instant::now();
+                        let record = |status| {
+                            let mut record = transfer_record(src_config.chain_id, dst_config.chain_id,
+                                sender_wallet.address(), receiver_addr, amount_wei, status);
+                            record.round = Some(round);
+                            record.to = Some(src_config.contract);
+                            record.timed(sent_at.into_std(), None)
+                        };
+                        metrics::submitted(1);
+                        match call.send().await {
                             Ok(tx) => {
+                                metrics::accepted(1);
+                                metrics::crosschain_message(src_chain_id, dst_chain_id, "sent");
                                 // Store tx_hash before await since tx will be moved
                                 let tx_hash = tx.tx_hash();
                                 
                                 match tx.await {
                                     Ok(receipt) => {
-                                        if receipt.unwrap().status.unwrap().as_u64() == 1 {
+                                        metrics::included(sent_at.elapsed());
+                                        let receipt = receipt.unwrap();
+                                        let succeeded = receipt.status.unwrap().as_u64() == 1;
+                                        let mut mined = record(if succeeded { TxStatus::Included } else { TxStatus::Reverted })
+                                            .timed(sent_at.into_std(), Some(std::time::Instant::now()));
+                                        mined.hash = Some(tx_hash);
+                                        mined.block_number = receipt.block_number.map(|block| block.as_u64());
+                                        mined.gas_used = receipt.gas_used.map(|gas| gas.as_u64());
+                                        mined.message_id = message_id(&receipt);
+                                        records.record(mined);
+                                        if succeeded {
+                                            metrics::crosschain_message(src_chain_id, dst_chain_id, "succeeded");
                                             let tx_hash_str = format!("{:#x}", tx_hash);
                                             
-                                            // Log format: tx_hash,round,timestamp,src_chain,dst_chain,from,to,amount
-                                            writeln!(log, "{},{},{},{:#x},{},{},{},{}",
-                                                tx_hash_str,
-                                                round,
-                                                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
-                                                chain_ids[src_node - 1],
-                                                chain_ids[dst_node - 1],
-                                                sender_wallet.address(),
-                                                receiver_addr,
-                                                amount_wei
-                                            )?;
-                                            log.flush()?;
-                                            
                                             println!("✓ Round {} - Transaction successful!", round);
                                             println!("  Hash: {}", tx_hash_str);
                                             successful_transfers += 1;
                                         } else {
+                                            metrics::crosschain_message(src_chain_id, dst_chain_id, "reverted");
                                             println!("✗ Round {} - Transaction failed (reverted)!", round);
                                             println!("  Hash: {:#x}", tx_hash);
                                             failed_transfers += 1;
                                         }
                                     }
                                     Err(e) => {
+                                        let mut pending = record(TxStatus::Pending);
+                                        pending.hash = Some(tx_hash);
+                                        pending.error = Some(e.to_string());
+                                        records.record(pending);
                                         println!("✗ Round {} - Transaction failed while waiting for receipt!", round);
                                         println!("  Hash: {:#x}", tx_hash);
                                         println!("  Error: {}", e);
@@ -1090,10 +1178,15 @@ async fn send_eth_crosschain_loop(
                                 }
                             }
                             Err(e) => {
+                                metrics::rejected(1);
+                                metrics::crosschain_message(src_chain_id, dst_chain_id, "rejected");
+                                let mut rejected = record(TxStatus::Rejected);
+                                rejected.error = Some(e.to_string());
+                                records.record(rejected);
                                 println!("✗ Transaction failed: {}", e);
                                 failed_transfers += 1;
                             }
-                        }
+                        };
                     }
                 }
             }
@@ -1115,58 +1208,23 @@ async fn send_eth_crosschain_loop(
     Ok(())
 }
 
-async fn get_chain_ids(from_node: usize, to_node: usize) -> eyre::Result<Vec<u32>> {
-    let mut chain_ids = vec![0; std::cmp::max(from_node, to_node)];  // Pre-fill with zeros
-    
-    // Only get chain IDs for the nodes we need
-    for node_idx in [from_node, to_node] {
-        let chain_id = env::var(format!("NODE{}_CHAINID", node_idx))
-            .map_err(|_| eyre::eyre!("NODE{}_CHAINID not set in .env", node_idx))?
-            .parse()
-            .map_err(|_| eyre::eyre!("Invalid chain ID format for NODE{}_CHAINID", node_idx))?;
-        chain_ids[node_idx - 1] = chain_id;
-    }
-    Ok(chain_ids)
-}
-
-async fn get_contract_addresses(from_node: usize, to_node: usize) -> eyre::Result<Vec<Address>> {
-    let mut addresses = vec![Address::zero(); std::cmp::max(from_node, to_node)];  // Pre-fill with zero addresses
-    
-    // Only get contract addresses for the nodes we need
-    for node_idx in [from_node, to_node] {
-        let addr = env::var(format!("NODE{}_CONTRACT", node_idx))
-            .map_err(|_| eyre::eyre!("NODE{}_CONTRACT not set in .env", node_idx))?
-            .parse()
-            .map_err(|_| eyre::eyre!("Invalid contract address for NODE{}_CONTRACT", node_idx))?;
-        addresses[node_idx - 1] = addr;
-    }
-    Ok(addresses)
-}
-
 async fn send_eth_burst(
-    from_node: usize,
-    to_node: usize,
-    num_txs: usize,
-    amount_wei: U256,
-    zero_gas_price: bool,
+    topology: &TopologyArgs,
+    fees: &FeeArgs,
+    burst: &BurstArgs,
+    board: DashboardHandle,
+    records: &Recorder,
 ) -> eyre::Result<()> {
+    let BurstArgs { from_node, to_node, num_txs, amount_wei, zero_gas_price, .. } = burst;
+    let (num_txs, amount_wei, zero_gas_price) = (*num_txs, *amount_wei, *zero_gas_price);
     info!("Starting burst ETH transfer");
 
-    // Get source node's details from .env
-    let src_chain_id: u32 = env::var(format!("NODE{}_CHAINID", from_node))?
-        .parse()
-        .map_err(|_| eyre::eyre!("Invalid chain ID format for NODE{}_CHAINID", from_node))?;
-    
-    let contract_addr = env::var(format!("NODE{}_CONTRACT", from_node))?
-        .parse::<Address>()
-        .map_err(|_| eyre::eyre!("Invalid contract address for NODE{}_CONTRACT", from_node))?;
-    
-    let rpc_url = env::var(format!("NODE{}_RPC", from_node))?;
-
-    // Get destination chain ID
-    let dst_chain_id: u32 = env::var(format!("NODE{}_CHAINID", to_node))?
-        .parse()
-        .map_err(|_| eyre::eyre!("Invalid chain ID format for NODE{}_CHAINID", to_node))?;
+    // Get source and destination node details from .env
+    let src_node = topology.node(from_node)?;
+    let dst_node = topology.node(to_node)?;
+    let src_chain_id = src_node.chain_id;
+    let contract_addr = src_node.contract;
+    let dst_chain_id = dst_node.chain_id;
 
     println!("Starting burst ETH transfers...");
     let start_time = Instant::now();
@@ -1178,58 +1236,33 @@ async fn send_eth_burst(
     let prep_start = Instant::now();
 
     // Read source and destination node files - only get first sender/receiver
-    let src_filename = format!("node-{}.json", from_node);
-    let src_content = fs::read_to_string(&src_filename)?;
-    let src_data: Value = serde_json::from_str(&src_content)?;
-
-    let dst_filename = format!("node-{}.json", to_node);
-    let dst_content = fs::read_to_string(&dst_filename)?;
-    let dst_data: Value = serde_json::from_str(&dst_content)?;
+    let src_accounts = src_node.accounts()?;
+    let dst_accounts = dst_node.accounts()?;
 
     // Connect to source node's network
-    let provider = Provider::<Http>::try_from(rpc_url.clone())?;
-    let client = Arc::new(provider);
+    let client = Arc::new(src_node.provider().await?);
     
     // Get chain ID early
     let chain_id = client.get_chainid().await?;
     println!("Connected to network. Chain ID: {}", chain_id);
 
-    // Create contract instance
-    let contract_json: Value = serde_json::from_slice(
-        include_bytes!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json")
-    )?;
-    let abi: ethers::abi::Abi = serde_json::from_value(contract_json["abi"].clone())?;
-
-    // Create base contract instance for encoding
-    let contract = Contract::new(
-        contract_addr,
-        abi,
-        client.clone()
-    );
 
     // Get first sender and receiver
-    let sender = &src_data["senders"][0];
-    let sender_wallet = sender["private_key"].as_str()
-        .ok_or_else(|| eyre::eyre!("Invalid private key format"))?
-        .parse::<LocalWallet>()?
-        .with_chain_id(chain_id.as_u64());
-
-    let receiver = &dst_data["receivers"][0];
-    let receiver_addr: Address = receiver["address"].as_str()
-        .ok_or_else(|| eyre::eyre!("Invalid receiver address"))?
-        .parse()?;
+    let sender_wallet = src_accounts.sender(0)?.wallet(chain_id.as_u64())?;
+    let receiver_addr = dst_accounts.receiver(0)?.address()?;
 
     // After setting gas price and before preparing transactions
-    let gas_price = if zero_gas_price {
+    let default_gas_price = if zero_gas_price {
         U256::zero()
     } else {
         U256::from(1_000_000_000)  // 1 gwei
     };
+    let fees = fees.resolve(client.as_ref(), Some(default_gas_price)).await?;
     
-    println!("Using gas price: {} wei", gas_price);
+    println!("Using fees: {}", fees);
 
     // Check sender balance and required funds
-    let gas_cost = gas_price * U256::from(70_000);  // gas_price * gas_limit
+    let gas_cost = fees.max_price_per_gas() * U256::from(70_000);  // max price per gas * gas_limit
     let cost_per_tx = gas_cost + amount_wei;
     let total_needed = cost_per_tx * U256::from(num_txs);
     let sender_balance = client.get_balance(sender_wallet.address(), None).await?;
@@ -1248,21 +1281,22 @@ async fn send_eth_burst(
     }
 
     // Get initial nonce once before the loop
-    let initial_nonce = client.get_transaction_count(sender_wallet.address(), None).await?;
-    println!("Starting with nonce: {}", initial_nonce);
+    let nonces = Arc::new(NonceManager::new(client.clone()));
+    let wallets = vec![sender_wallet.clone()];
+    println!("Starting with nonce: {}", nonces.track(sender_wallet.address()).await?);
     
     // Prepare all transactions
-    for i in 0..num_txs {
+    for _ in 0..num_txs {
+        let nonce = nonces.next(sender_wallet.address())?;
         let tx = TransactionRequest::new()
             .to(contract_addr)
             .value(amount_wei)
             .gas(70_000)
-            .gas_price(gas_price)
-            .nonce(initial_nonce + U256::from(i))  // Increment nonce for each tx
-            .data(contract.encode("sendETHToDestinationChain", (dst_chain_id, receiver_addr))?);
+            .nonce(nonce)  // Increment nonce for each tx
+            .data(SendETHToDestinationChainCall { chain_id: dst_chain_id, recipient: receiver_addr }.encode());
 
         prepared_txs.push(PreparedTx {
-            tx: TypedTransaction::Legacy(tx),
+            tx: fees.typed(tx),
             wallet: sender_wallet.clone(),
             info: TxInfo {
                 round: 1,
@@ -1272,6 +1306,9 @@ async fn send_eth_burst(
                 from_addr: sender_wallet.address(),
                 to_addr: receiver_addr,
                 amount: amount_wei,
+                contract: contract_addr,
+                nonce,
+                sent_at: Instant::now(),
             },
         });
     }
@@ -1290,26 +1327,49 @@ async fn send_eth_burst(
     for prepared in prepared_txs.into_iter() {
         let client = client.clone();
         let semaphore = semaphore.clone();
+        let nonces = nonces.clone();
+        let board = board.clone();
+        let records = records.clone();
         
         handles.push(tokio::spawn(async move {
             let _permit = semaphore.acquire().await.unwrap();
             match prepared.wallet.sign_transaction(&prepared.tx).await {
                 Ok(signature) => {
                     let signed_tx = prepared.tx.rlp_signed(&signature);
+                    let sent_at = Instant::now();
+                    board.started(1);
+                    metrics::submitted(1);
                     match client.send_raw_transaction(signed_tx).await {
                         Ok(tx) => {
+                            board.accepted(1, sent_at.elapsed());
+                            metrics::accepted(1);
                             let mut info = prepared.info;
+                            metrics::crosschain_message(info.from_chain as u64, info.to_chain as u64, "sent");
                             info.hash = tx.tx_hash();
+                            info.sent_at = sent_at;
                             Ok(info)
                         }
                         Err(e) => {
-                            println!("Failed to send transaction: {}", e);
+                            board.rejected(&e);
+                            metrics::rejected(1);
+                            metrics::crosschain_message(prepared.info.from_chain as u64, prepared.info.to_chain as u64, "rejected");
+                            if !board.is_live() {
+                                println!("Failed to send transaction: {}", e);
+                            }
+                            let nonce = prepared.tx.nonce().copied().unwrap_or_default().as_u64();
+                            nonces.rejected(prepared.info.from_addr, nonce, &e.to_string());
+                            let mut record = TxInfo { sent_at, ..prepared.info }.record(TxStatus::Rejected);
+                            record.error = Some(e.to_string());
+                            records.record(record);
                             Err(eyre::eyre!("Transaction failed: {}", e))
                         }
                     }
                 }
                 Err(e) => {
                     println!("Failed to sign transaction: {}", e);
+                    let mut record = prepared.info.record(TxStatus::Rejected);
+                    record.error = Some(format!("Signing failed: {}", e));
+                    records.record(record);
                     Err(eyre::eyre!("Signing failed: {}", e))
                 }
             }
@@ -1324,7 +1384,9 @@ async fn send_eth_burst(
                 total_sent += 1;
             }
             Err(e) => {
-                println!("Transaction failed: {}", e);
+                if !board.is_live() {
+                    println!("Transaction failed: {}", e);
+                }
             }
         }
     }
@@ -1337,26 +1399,21 @@ async fn send_eth_burst(
     } else {
         println!("No transactions were sent successfully");
     }
+    repair_nonce_gaps(&nonces, &wallets, &fees).await;
 
     // After sending transactions
     info!("All transactions sent. Checking receipts...");
     debug!("Number of transactions to check: {}", transactions.len());
 
-    // Create log file
-    let log_file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("eth-transfer-burst.log")?;
-    let mut log = BufWriter::new(log_file);
-
-    // After creating log file, add block stats tracking
+    // Track block stats while waiting
     let mut block_stats: HashMap<U64, BlockStats> = HashMap::new();
     let mut successful = 0;
     let mut failed = 0;
 
-    // Wait for all transaction receipts with timeout
+    // Wait for all transaction receipts with timeout, checking again on every new block
     let max_wait = Duration::from_secs(60);
     let start_wait = Instant::now();
+    let mut heads = src_node.watch_heads(&client, Duration::from_secs(1)).await?;
 
     while !transactions.is_empty() && start_wait.elapsed() < max_wait {
         debug!("Checking {} pending transactions...", transactions.len());
@@ -1368,86 +1425,40 @@ async fn send_eth_burst(
                     debug!("Got receipt for tx: {:#x}", tx_info.hash);
                     let block_num = receipt.block_number.unwrap_or_default();
                     
-                    // Update block statistics
-                    let stats = block_stats.entry(block_num).or_insert(BlockStats {
-                        tx_count: 0,
-                        total_gas_used: U256::zero(),
-                        our_gas_used: U256::zero(),
-                    });
+                    // Update block statistics, reading the block's total gas once, with its first
+                    // receipt; block_observer records every block, ours or not
+                    let stats = match block_stats.entry(block_num) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            let total_gas_used = match client.get_block(block_num).await {
+                                Ok(Some(block)) => block.gas_used,
+                                _ => U256::zero(),
+                            };
+                            entry.insert(BlockStats {
+                                tx_count: 0,
+                                total_gas_used,
+                                our_gas_used: U256::zero(),
+                            })
+                        }
+                    };
                     stats.tx_count += 1;
                     stats.our_gas_used += receipt.gas_used.unwrap_or_default();
-
-                    // Get block info for total gas used
-                    if let Ok(block) = client.get_block(block_num).await {
-                        if let Some(block) = block {
-                            stats.total_gas_used = block.gas_used;
-                        }
-                    }
-
-                    if receipt.status.unwrap().as_u64() == 1 {
-                        debug!("Transaction successful, writing to log...");
-                        // Get message ID from logs more safely
-                        let message_id = if let Some(log) = receipt.logs.first() {
-                            // Create raw log for parsing
-                            let raw_log = RawLog {
-                                topics: log.topics.clone(),
-                                data: log.data.to_vec(),
-                            };
-
-                            // Create contract instance for event parsing
-                            let contract_json: Value = serde_json::from_slice(
-                                include_bytes!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json")
-                            )?;
-                            let abi: ethers::abi::Abi = serde_json::from_value(contract_json["abi"].clone())?;
-
-                            // Find and parse the event
-                            if let Some(event) = abi.events().find(|e| e.name == "ETHSentToDestinationChain") {
-                                if let Ok(parsed) = event.parse_log(raw_log) {
-                                    // Find messageId parameter
-                                    if let Some(param) = parsed.params.iter().find(|p| p.name == "messageId") {
-                                        if let Token::Uint(val) = &param.value {
-                                            val.as_u64()
-                                        } else {
-                                            0
-                                        }
-                                    } else {
-                                        0
-                                    }
-                                } else {
-                                    0
-                                }
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        };
-                        writeln!(log, "success,{},{},{},{},{},{},{},{},{},{}",
-                            block_num,
-                            message_id,
-                            format!("{:#x}", tx_info.hash),
-                            1,
-                            tx_info.from_chain,
-                            tx_info.to_chain,
-                            format!("{:x}", tx_info.from_addr),
-                            format!("{:x}", tx_info.to_addr),
-                            tx_info.amount,
-                            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
-                        )?;
+                    metrics::included(tx_info.sent_at.elapsed());
+
+                    let succeeded = receipt.status.unwrap().as_u64() == 1;
+                    let mut record = tx_info.record(if succeeded { TxStatus::Included } else { TxStatus::Reverted })
+                        .timed(tx_info.sent_at.into_std(), Some(std::time::Instant::now()));
+                    record.block_number = Some(block_num.as_u64());
+                    record.gas_used = receipt.gas_used.map(|gas| gas.as_u64());
+                    record.message_id = message_id(&receipt);
+                    records.record(record);
+                    if succeeded {
+                        debug!("Transaction successful: {:#x}", tx_info.hash);
+                        metrics::crosschain_message(tx_info.from_chain as u64, tx_info.to_chain as u64, "succeeded");
                         successful += 1;
                     } else {
                         warn!("Transaction failed: {:#x}", tx_info.hash);
-                        writeln!(log, "failed,{},{},{},{},{},0x{:?},0x{:?},{},{}",
-                            block_num,
-                            format!("{:#x}", tx_info.hash),
-                            1,
-                            tx_info.from_chain,
-                            tx_info.to_chain,
-                            tx_info.from_addr,
-                            tx_info.to_addr,
-                            tx_info.amount,
-                            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
-                        )?;
+                        metrics::crosschain_message(tx_info.from_chain as u64, tx_info.to_chain as u64, "reverted");
                         failed += 1;
                     }
                     completed.push(idx);
@@ -1466,11 +1477,15 @@ async fn send_eth_burst(
 
         if !transactions.is_empty() {
             debug!("Waiting for {} more receipts...", transactions.len());
-            sleep(Duration::from_secs(1)).await;
+            let _ = timeout(max_wait.saturating_sub(start_wait.elapsed()), heads.next()).await;
         }
     }
 
-    log.flush()?;
+    // Whatever is left was accepted but not mined within the wait
+    for tx_info in &transactions {
+        records.record(tx_info.record(TxStatus::Pending));
+    }
+    report_stuck_senders(&nonces).await;
 
     // After the loop, print block statistics
     println!("\nBlock-wise Distribution:");
@@ -1495,66 +1510,43 @@ async fn send_eth_burst(
 
 // New function that copies send_eth_burst but skips receipt checking
 async fn send_eth_burst_no_receipt(
-    from_node: usize,
-    to_node: usize,
-    num_txs: usize,
-    amount_wei: U256,
-    zero_gas_price: bool,
+    topology: &TopologyArgs,
+    fees: &FeeArgs,
+    burst: &BurstArgs,
+    board: DashboardHandle,
+    records: &Recorder,
+    round: usize,
 ) -> eyre::Result<()> {
+    let BurstArgs { from_node, to_node, num_txs, amount_wei, zero_gas_price, .. } = burst;
+    let (num_txs, amount_wei, zero_gas_price) = (*num_txs, *amount_wei, *zero_gas_price);
     let start_time = Instant::now();
     let mut total_sent = 0;
 
-    // Get chain IDs and contract addresses
-    let chain_ids = get_chain_ids(from_node, to_node).await?;
-    let contract_addresses = get_contract_addresses(from_node, to_node).await?;
+    // Get source and destination node details
+    let src_node = topology.node(from_node)?;
+    let dst_node = topology.node(to_node)?;
+    let (src_chain_id, dst_chain_id) = (src_node.chain_id as u64, dst_node.chain_id as u64);
 
-    // Get RPC URL for source node
-    let provider = Provider::<Http>::try_from(env::var(format!("NODE{}_RPC", from_node))?)?;
-    let client = Arc::new(provider);
-
-    // Create contract instance
-    let contract_json: Value = serde_json::from_slice(
-        include_bytes!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json")
-    )?;
-    let abi: ethers::abi::Abi = serde_json::from_value(contract_json["abi"].clone())?;
+    // Connect to source node's network
+    let client = Arc::new(src_node.provider().await?);
 
-    // Create base contract instance for encoding
-    let contract = Contract::new(
-        contract_addresses[from_node - 1],
-        abi,
-        client.clone()
-    );
 
     // Get first sender and receiver
-    let src_file = format!("node-{}.json", from_node);
-    let dst_file = format!("node-{}.json", to_node);
-    let src_content = fs::read_to_string(&src_file)?;
-    let dst_content = fs::read_to_string(&dst_file)?;
-    let src_data: Value = serde_json::from_str(&src_content)?;
-    let dst_data: Value = serde_json::from_str(&dst_content)?;
-
-    let sender = &src_data["senders"][0];
-    let sender_wallet = sender["private_key"].as_str()
-        .ok_or_else(|| eyre::eyre!("Invalid private key format"))?
-        .parse::<LocalWallet>()?
-        .with_chain_id(client.get_chainid().await?.as_u64());
-
-    let receiver = &dst_data["receivers"][0];
-    let receiver_addr: Address = receiver["address"].as_str()
-        .ok_or_else(|| eyre::eyre!("Invalid receiver address"))?
-        .parse()?;
+    let sender_wallet = src_node.accounts()?.sender(0)?.wallet(client.get_chainid().await?.as_u64())?;
+    let receiver_addr = dst_node.accounts()?.receiver(0)?.address()?;
 
     // Set gas price
-    let gas_price = if zero_gas_price {
+    let default_gas_price = if zero_gas_price {
         U256::zero()
     } else {
         U256::from(1_000_000_000)  // 1 gwei
     };
+    let fees = fees.resolve(client.as_ref(), Some(default_gas_price)).await?;
     
-    println!("Using gas price: {} wei", gas_price);
+    println!("Using fees: {}", fees);
 
     // Check sender balance
-    let gas_cost = gas_price * U256::from(70_000);
+    let gas_cost = fees.max_price_per_gas() * U256::from(70_000);
     let cost_per_tx = gas_cost + amount_wei;
     let total_needed = cost_per_tx * U256::from(num_txs);
     let sender_balance = client.get_balance(sender_wallet.address(), None).await?;
@@ -1573,8 +1565,8 @@ async fn send_eth_burst_no_receipt(
     }
 
     // Get latest nonce at start of this iteration
-    let initial_nonce = client.get_transaction_count(sender_wallet.address(), Some(BlockId::Number(BlockNumber::Pending))).await?;
-    println!("Starting with nonce: {}", initial_nonce);
+    let nonces = Arc::new(NonceManager::new(client.clone()));
+    println!("Starting with nonce: {}", nonces.track(sender_wallet.address()).await?);
     
     // Send transactions in parallel
     println!("\nSending {} transactions...", num_txs);
@@ -1583,32 +1575,68 @@ async fn send_eth_burst_no_receipt(
     let mut handles = Vec::new();
     let semaphore = Arc::new(Semaphore::new(100));  // Limit concurrent transactions
 
-    // Use sequential nonces starting from initial_nonce
-    for i in 0..num_txs {
+    // Use sequential nonces from the nonce manager
+    for _ in 0..num_txs {
+        let nonce = nonces.next(sender_wallet.address())?;
         let tx = TransactionRequest::new()
-            .to(contract_addresses[from_node - 1])
+            .to(src_node.contract)
             .value(amount_wei)
             .gas(70_000)
-            .gas_price(gas_price)
-            .nonce(initial_nonce + U256::from(i))  // Sequential nonces
-            .data(contract.encode("sendETHToDestinationChain", (chain_ids[to_node - 1], receiver_addr))?);
+            .nonce(nonce)  // Sequential nonces
+            .data(SendETHToDestinationChainCall { chain_id: dst_node.chain_id, recipient: receiver_addr }.encode());
+        let typed_tx = fees.typed(tx);
 
         let client = client.clone();
         let wallet = sender_wallet.clone();
         let semaphore = semaphore.clone();
+        let nonces = nonces.clone();
+        let board = board.clone();
+        let records = records.clone();
+        let (from_chain, to_chain, from, contract) = (src_node.chain_id, dst_node.chain_id, wallet.address(), src_node.contract);
+        let record = move |status, sent_at: Instant| {
+            let mut record = transfer_record(from_chain, to_chain, from, receiver_addr, amount_wei, status);
+            record.round = Some(round);
+            record.to = Some(contract);
+            record.nonce = Some(nonce);
+            record.timed(sent_at.into_std(), None)
+        };
         
         handles.push(tokio::spawn(async move {
             let _permit = semaphore.acquire().await.unwrap();
-            let typed_tx = TypedTransaction::Legacy(tx);  // Create TypedTransaction once
             match wallet.sign_transaction(&typed_tx).await {
                 Ok(signature) => {
                     let signed_tx = typed_tx.rlp_signed(&signature);  // Use the same TypedTransaction
+                    let sent_at = Instant::now();
+                    board.started(1);
+                    metrics::submitted(1);
                     match client.send_raw_transaction(signed_tx).await {
-                        Ok(_) => Ok(()),
-                        Err(e) => Err(eyre::eyre!("Transaction failed: {}", e))
+                        Ok(pending) => {
+                            board.accepted(1, sent_at.elapsed());
+                            metrics::accepted(1);
+                            metrics::crosschain_message(src_chain_id, dst_chain_id, "sent");
+                            let mut accepted = record(TxStatus::Accepted, sent_at);
+                            accepted.hash = Some(pending.tx_hash());
+                            records.record(accepted);
+                            Ok(())
+                        }
+                        Err(e) => {
+                            board.rejected(&e);
+                            metrics::rejected(1);
+                            metrics::crosschain_message(src_chain_id, dst_chain_id, "rejected");
+                            nonces.rejected(wallet.address(), nonce, &e.to_string());
+                            let mut rejected = record(TxStatus::Rejected, sent_at);
+                            rejected.error = Some(e.to_string());
+                            records.record(rejected);
+                            Err(eyre::eyre!("Transaction failed: {}", e))
+                        }
                     }
                 }
-                Err(e) => Err(eyre::eyre!("Signing failed: {}", e))
+                Err(e) => {
+                    let mut rejected = record(TxStatus::Rejected, Instant::now());
+                    rejected.error = Some(format!("Signing failed: {}", e));
+                    records.record(rejected);
+                    Err(eyre::eyre!("Signing failed: {}", e))
+                }
             }
         }));
     }
@@ -1617,7 +1645,8 @@ async fn send_eth_burst_no_receipt(
     for handle in handles {
         match handle.await? {
             Ok(_) => total_sent += 1,
-            Err(e) => println!("Transaction failed: {}", e),
+            Err(e) if !board.is_live() => println!("Transaction failed: {}", e),
+            Err(_) => {}
         }
     }
 
@@ -1627,6 +1656,7 @@ async fn send_eth_burst_no_receipt(
         println!("Average time per transaction: {:?}", send_time / total_sent as u32);
         println!("Transactions per second: {:.2}", total_sent as f64 / send_time.as_secs_f64());
     }
+    repair_nonce_gaps(&nonces, &[sender_wallet], &fees).await;
 
     println!("\nTransaction Summary:");
     println!("Total transactions sent: {}", total_sent);
@@ -1637,39 +1667,35 @@ async fn send_eth_burst_no_receipt(
 
 // New function that runs send_eth_burst_no_receipt in a loop
 async fn send_eth_coh_no_receipt(
-    from_node: usize,
-    to_node: usize,
-    num_txs: usize,
-    amount_wei: U256,
-    zero_gas_price: bool,
+    topology: &TopologyArgs,
+    fees: &FeeArgs,
+    burst: &BurstArgs,
     iterations: Option<usize>,
     delay_secs: u64,
+    board: DashboardHandle,
+    records: &Recorder,
 ) -> eyre::Result<()> {
+    let (from_node, num_txs) = (&burst.from_node, burst.num_txs);
     let total_start = Instant::now();
     let mut iteration = 0;
     let mut total_successful = 0;
     let mut total_failed = 0;
 
     // Get provider once for nonce checking
-    let provider = Provider::<Http>::try_from(env::var(format!("NODE{}_RPC", from_node))?)?;
-    let client = Arc::new(provider);
+    let src_node = topology.node(from_node)?;
+    let client = Arc::new(src_node.provider().await?);
 
     // Get sender wallet once for nonce checking
-    let src_file = format!("node-{}.json", from_node);
-    let src_content = fs::read_to_string(&src_file)?;
-    let src_data: Value = serde_json::from_str(&src_content)?;
-    let sender = &src_data["senders"][0];
-    let sender_wallet = sender["private_key"].as_str()
-        .ok_or_else(|| eyre::eyre!("Invalid private key format"))?
-        .parse::<LocalWallet>()?
-        .with_chain_id(client.get_chainid().await?.as_u64());
+    let sender_wallet = src_node.accounts()?.sender(0)?.wallet(client.get_chainid().await?.as_u64())?;
 
     loop {
         iteration += 1;
         println!("\n=== Starting iteration {} ===", iteration);
         let iter_start = Instant::now();
 
-        match send_eth_burst_no_receipt(from_node, to_node, num_txs, amount_wei, zero_gas_price).await {
+        let result = send_eth_burst_no_receipt(topology, fees, burst, board.clone(), records, iteration).await;
+        records.flush();
+        match result {
             Ok(()) => {
                 total_successful += num_txs;
             }
@@ -1713,7 +1739,7 @@ async fn send_eth_coh_no_receipt(
     Ok(())
 }
 
-fn prepare_new_accounts(node: usize, num_accounts: usize) {
+fn prepare_new_accounts(topology: &TopologyArgs, node: &str, num_accounts: usize) -> eyre::Result<()> {
     println!("Generating {} sender-receiver pairs for node {}", num_accounts, node);
     
     // Generate accounts
@@ -1725,37 +1751,18 @@ fn prepare_new_accounts(node: usize, num_accounts: usize) {
         let sender_wallet = LocalWallet::new(&mut rand::thread_rng());
         let receiver_wallet = LocalWallet::new(&mut rand::thread_rng());
         
-        // Create account objects
-        let sender = json!({
-            "private_key": format!("{:x}", sender_wallet.signer().to_bytes()),
-            "address": format!("{:?}", sender_wallet.address())
-        });
-        
-        let receiver = json!({
-            "private_key": format!("{:x}", receiver_wallet.signer().to_bytes()),
-            "address": format!("{:?}", receiver_wallet.address())
-        });
-        
-        senders.push(sender);
-        receivers.push(receiver);
+        senders.push(Account::from_wallet(&sender_wallet));
+        receivers.push(Account::from_wallet(&receiver_wallet));
         
         println!("Generated pair {}", i + 1);
         println!("  Sender: {}", sender_wallet.address());
         println!("  Receiver: {}", receiver_wallet.address());
     }
     
-    // Create node configuration
-    let node_config = json!({
-        "senders": senders,
-        "receivers": receivers
-    });
-    
-    // Write to file
-    let filename = format!("node-{}.json", node);
-    fs::write(
-        &filename,
-        serde_json::to_string_pretty(&node_config).unwrap()
-    ).expect(&format!("Failed to write {}", filename));
+    // Write node configuration
+    let filename = node_account_file(topology, node)?;
+    AccountStore::new(senders, receivers).save(&filename)?;
     
     println!("\nCreated {} with {} account pairs", filename, num_accounts);
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {ERC20} from "@openzeppelin/contracts/token/ERC20/ERC20.sol";
+
+// Standard OpenZeppelin ERC-20 for token load tests. Anyone may mint, so tx-generator
+// can seed its sender accounts in a few batches; never deploy it anywhere that matters.
+contract LoadToken is ERC20 {
+    constructor(string memory name, string memory symbol) ERC20(name, symbol) {}
+
+    // Mints `amount` to every recipient
+    function mintBatch(address[] calldata recipients, uint256 amount) external {
+        for (uint256 i = 0; i < recipients.length; ) {
+            _mint(recipients[i], amount);
+            unchecked {
+                i++;
+            }
+        }
+    }
+}
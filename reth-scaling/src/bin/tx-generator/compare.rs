@@ -0,0 +1,90 @@
+// Runs the same load once per endpoint of a node, e.g. its HTTP, WebSocket and IPC
+// endpoints, and reports throughput and latency side by side
+
+use bridge_core::{connect, transport_kind, Account, Recorder};
+use serde_json::json;
+use std::{fs::File, sync::Arc, time::Duration};
+use tokio::time;
+
+use crate::profile::Profile;
+use crate::source::TxSource;
+use crate::{send_transactions, Args, Result, TxStats};
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct CompareArgs {
+    /// Comma-separated endpoints (HTTP or WebSocket URLs, IPC socket paths) to run the
+    /// same test against one after another, reporting them side by side; replaces --rpc-url
+    #[clap(long, value_delimiter = ',', conflicts_with_all = ["replay_corpus", "generate_corpus", "find_max_tps", "endpoints"])]
+    pub compare_endpoints: Vec<String>,
+
+    /// Pause between runs so the node can settle
+    #[clap(long, default_value_t = 5)]
+    pub compare_cooldown_secs: u64,
+}
+
+struct Run {
+    endpoint: String,
+    stats: TxStats,
+}
+
+pub async fn run(
+    args: &Args,
+    profile: Option<Profile>,
+    senders: &[Account],
+    receivers: &[Account],
+    recorder: &Recorder,
+) -> Result<()> {
+    let endpoints = &args.compare.compare_endpoints;
+    let mut runs = Vec::with_capacity(endpoints.len());
+
+    for (i, endpoint) in endpoints.iter().enumerate() {
+        if i > 0 {
+            time::sleep(Duration::from_secs(args.compare.compare_cooldown_secs)).await;
+        }
+        let provider = Arc::new(connect(endpoint).await?);
+        println!("\n=== Run {}/{} over {} ({}) ===", i + 1, endpoints.len(), transport_kind(&provider), endpoint);
+
+        let mut run_args = args.clone();
+        run_args.rpc_url = endpoint.clone();
+
+        // Each run signs afresh from the senders' pending nonces
+        let fees = run_args.fees(&provider).await?;
+        let workload = run_args.workload(&provider, senders, receivers.len()).await?;
+        let source = TxSource::live(&provider, senders, receivers, fees, workload, args.chain_id, None).await?;
+        let stats = send_transactions(run_args, profile.clone(), source, recorder.trial(i as u32 + 1)).await?;
+        runs.push(Run { endpoint: endpoint.clone(), stats });
+    }
+
+    println!("\n=== Transport comparison ===");
+    println!("{:<9} {:>10} {:>12} {:>9} {:>9} {:>12} {:>12} {:>12}  endpoint",
+        "transport", "send TPS", "RPC latency", "failed", "included", "incl. TPS", "incl. p50", "incl. p99");
+    for run in &runs {
+        let stats = &run.stats;
+        let inclusion = &stats.confirmation;
+        println!("{:<9} {:>10.2} {:>12.2?} {:>9} {:>9} {:>12.2} {:>12.2?} {:>12.2?}  {}",
+            stats.transport.to_string(), stats.tps, stats.avg_latency, stats.failed,
+            format!("{}/{}", inclusion.included, inclusion.tracked), inclusion.inclusion_tps,
+            inclusion.latency.p50, inclusion.latency.p99, run.endpoint);
+    }
+
+    let report = json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "tx_count": args.tx_count,
+        "target_tps": args.target_tps,
+        "concurrency": args.concurrency,
+        "runs": runs.iter().map(|run| json!({
+            "endpoint": run.endpoint,
+            "transport": run.stats.transport,
+            "tps": run.stats.tps,
+            "avg_latency_ms": run.stats.avg_latency.as_millis(),
+            "accepted": run.stats.confirmed,
+            "failed": run.stats.failed,
+            "inclusion": run.stats.confirmation.to_json(),
+        })).collect::<Vec<_>>(),
+    });
+    let report_file = File::create("tx_transports.json")?;
+    serde_json::to_writer_pretty(report_file, &report)?;
+    println!("Comparison saved to tx_transports.json");
+
+    Ok(())
+}
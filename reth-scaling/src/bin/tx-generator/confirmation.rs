@@ -0,0 +1,540 @@
+// Follows new blocks and matches their transactions against what tx-generator submitted
+
+use bridge_core::{metrics, BatchClient, HeadWatcher, Recorder, RpcProvider, TxRecord, TxStatus};
+use ethers::{
+    providers::Middleware,
+    types::{Address, H256, U256},
+};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{task::JoinHandle, time};
+
+use crate::latency::Percentiles;
+use crate::source::SignedTx;
+use crate::traffic::Lane;
+use crate::workload::Kind;
+use crate::{AppError, Result};
+
+// Hashes checked per eth_getTransactionByHash batch when looking for dropped transactions
+const LOOKUP_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy)]
+struct Sent {
+    at: Instant,
+    phase: usize,
+    kind: Kind,
+    lane: Lane,
+    hash: H256,
+    from: Address,
+    nonce: u64,
+    to: Option<Address>,
+    value: Option<U256>,
+}
+
+impl Sent {
+    fn new(tx: &SignedTx, at: Instant, phase: usize) -> Self {
+        Sent {
+            at,
+            phase,
+            kind: tx.kind,
+            lane: tx.lane,
+            hash: tx.hash,
+            from: tx.from,
+            nonce: tx.nonce,
+            to: tx.to,
+            value: tx.value,
+        }
+    }
+
+    // Transaction record of the run, without the outcome details
+    fn record(&self, chain_id: u64, status: TxStatus) -> TxRecord {
+        let mut record = TxRecord::new(self.kind.to_string(), chain_id, self.from, status);
+        record.phase = Some(self.phase);
+        record.lane = Some(self.lane.to_string());
+        record.to = self.to;
+        record.nonce = Some(self.nonce);
+        record.value_wei = self.value;
+        record.hash = Some(self.hash);
+        record
+    }
+}
+
+#[derive(Debug, Default)]
+struct KindState {
+    tracked: usize,
+    receipts: usize,
+    reverted: usize,
+    gas_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct LaneState {
+    tracked: usize,
+    rejected: usize,
+    /// Included transactions per block number
+    per_block: BTreeMap<u64, usize>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    /// Submitted but not yet seen in a block
+    pending: HashMap<H256, Sent>,
+    tracked: usize,
+    /// Submit-to-inclusion latency of every included transaction
+    latencies: Vec<(Sent, Duration)>,
+    /// Block of every included transaction
+    mined: HashMap<H256, u64>,
+    /// Gas used and whether it reverted, for included transactions whose receipt was read
+    receipts: HashMap<H256, (u64, bool)>,
+    kinds: BTreeMap<Kind, KindState>,
+    lanes: BTreeMap<Lane, LaneState>,
+    blocks: u64,
+    last_inclusion: Option<Instant>,
+}
+
+/// Submit-to-inclusion results for one run.
+#[derive(Debug, Default)]
+pub struct ConfirmationStats {
+    /// Transactions accepted by the RPC and followed on chain
+    pub tracked: usize,
+    pub included: usize,
+    /// No longer known to the node when tracking stopped
+    pub dropped: usize,
+    /// Still in the pool (or not looked up) when tracking stopped
+    pub pending: usize,
+    pub blocks: u64,
+    /// New blocks arrived over a newHeads subscription rather than by polling
+    pub head_subscription: bool,
+    pub latency: Percentiles,
+    /// Included transactions per second, from the start of the run to the last inclusion
+    pub inclusion_tps: f64,
+    /// Inclusion results by load profile phase
+    pub phases: Vec<PhaseInclusion>,
+    /// Inclusion and gas results by workload kind
+    pub kinds: Vec<KindInclusion>,
+    /// Inclusion results of hot-sender, hot-receiver and spread traffic
+    pub lanes: Vec<LaneInclusion>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PhaseInclusion {
+    pub included: usize,
+    pub latency: Percentiles,
+}
+
+#[derive(Debug, Clone)]
+pub struct KindInclusion {
+    pub kind: Kind,
+    pub tracked: usize,
+    pub included: usize,
+    pub latency: Percentiles,
+    /// Receipts fetched, and of those the reverted ones and the gas they used; no
+    /// receipts are fetched for runs of plain transfers
+    pub receipts: usize,
+    pub reverted: usize,
+    pub gas_used: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LaneInclusion {
+    pub lane: Lane,
+    pub tracked: usize,
+    /// Rejected by the RPC, e.g. a sender over the pool's per-account limit
+    pub rejected: usize,
+    pub included: usize,
+    pub latency: Percentiles,
+    /// Blocks holding the lane's transactions, and the most in any one of them
+    pub blocks: usize,
+    pub max_per_block: usize,
+}
+
+impl LaneInclusion {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "lane": self.lane,
+            "tracked": self.tracked,
+            "rejected": self.rejected,
+            "included": self.included,
+            "blocks": self.blocks,
+            "max_per_block": self.max_per_block,
+            "latency_ms": self.latency.to_json(),
+        })
+    }
+}
+
+impl KindInclusion {
+    pub fn avg_gas(&self) -> Option<u64> {
+        (self.receipts > 0).then(|| self.gas_used / self.receipts as u64)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind,
+            "tracked": self.tracked,
+            "included": self.included,
+            "reverted": self.reverted,
+            "receipts": self.receipts,
+            "gas_used": self.gas_used,
+            "avg_gas": self.avg_gas(),
+            "latency_ms": self.latency.to_json(),
+        })
+    }
+}
+
+impl ConfirmationStats {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "tracked": self.tracked,
+            "included": self.included,
+            "dropped": self.dropped,
+            "pending": self.pending,
+            "blocks": self.blocks,
+            "head_subscription": self.head_subscription,
+            "inclusion_tps": self.inclusion_tps,
+            "latency_ms": self.latency.to_json(),
+            "kinds": self.kinds.iter().map(KindInclusion::to_json).collect::<Vec<_>>(),
+            "lanes": self.lanes.iter().map(LaneInclusion::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Cheap handle the sending tasks use to register their transactions.
+#[derive(Debug, Clone)]
+pub struct TrackerHandle {
+    state: Arc<Mutex<TrackerState>>,
+    recorder: Recorder,
+    chain_id: u64,
+}
+
+impl TrackerHandle {
+    /// Registers a transaction just before it is sent, so a fast inclusion cannot be missed.
+    /// `phase` is the load profile phase it belongs to, 0 without a profile.
+    pub fn submitted(&self, tx: &SignedTx, sent_at: Instant, phase: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.pending.insert(tx.hash, Sent::new(tx, sent_at, phase));
+        state.tracked += 1;
+        state.kinds.entry(tx.kind).or_default().tracked += 1;
+        state.lanes.entry(tx.lane).or_default().tracked += 1;
+    }
+
+    /// Forgets a transaction the RPC rejected, or records one that could not be sent at all.
+    pub fn rejected(&self, tx: &SignedTx, sent_at: Instant, phase: usize, error: &str) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(sent) = state.pending.remove(&tx.hash) {
+                state.tracked -= 1;
+                state.kinds.entry(sent.kind).or_default().tracked -= 1;
+                state.lanes.entry(sent.lane).or_default().tracked -= 1;
+            }
+            // Counted even when it was never tracked, e.g. no endpoint was available to send it
+            state.lanes.entry(tx.lane).or_default().rejected += 1;
+        }
+        let mut record = Sent::new(tx, sent_at, phase).record(self.chain_id, TxStatus::Rejected)
+            .timed(sent_at, None);
+        record.error = Some(error.to_string());
+        self.recorder.record(record);
+    }
+}
+
+pub struct ConfirmationTracker {
+    provider: Arc<RpcProvider>,
+    state: Arc<Mutex<TrackerState>>,
+    recorder: Recorder,
+    chain_id: u64,
+    stop: Arc<AtomicBool>,
+    follower: JoinHandle<()>,
+    poll_interval: Duration,
+    head_subscription: bool,
+    started: Instant,
+}
+
+impl ConfirmationTracker {
+    /// Starts following blocks after the current head, over a newHeads subscription
+    /// when the endpoint has one and by polling every `poll_interval` otherwise.
+    /// With `receipts`, blocks holding tracked transactions also have their receipts
+    /// fetched for gas used and reverts. Every transaction ends up as a record in `recorder`.
+    pub async fn start(
+        provider: Arc<RpcProvider>,
+        poll_interval: Duration,
+        receipts: bool,
+        recorder: Recorder,
+        chain_id: u64,
+    ) -> Result<Self> {
+        let heads = HeadWatcher::start(provider.clone(), poll_interval).await
+            .map_err(|e| AppError::Provider(format!("Failed to get block number: {}", e)))?;
+        let head_subscription = heads.subscribed();
+
+        let state = Arc::new(Mutex::new(TrackerState::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let follower = tokio::spawn(follow_blocks(
+            provider.clone(),
+            heads,
+            state.clone(),
+            stop.clone(),
+            poll_interval,
+            receipts,
+        ));
+
+        Ok(ConfirmationTracker {
+            provider,
+            state,
+            recorder,
+            chain_id,
+            stop,
+            follower,
+            poll_interval,
+            head_subscription,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn handle(&self) -> TrackerHandle {
+        TrackerHandle { state: self.state.clone(), recorder: self.recorder.clone(), chain_id: self.chain_id }
+    }
+
+    /// Waits up to `timeout` for every tracked transaction to be included, then
+    /// looks up the rest to tell dropped transactions from ones still pending, in
+    /// JSON-RPC batches when `lookup` is given and one by one otherwise.
+    pub async fn finish(self, timeout: Duration, lookup: Option<&BatchClient>) -> ConfirmationStats {
+        let waiting = self.state.lock().unwrap().pending.len();
+        if waiting > 0 {
+            println!("Waiting up to {:?} for {} transactions to be included...", timeout, waiting);
+        }
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline && !self.state.lock().unwrap().pending.is_empty() {
+            time::sleep(self.poll_interval).await;
+        }
+
+        // The follower scans once more before it exits
+        self.stop.store(true, Ordering::SeqCst);
+        if let Err(e) = self.follower.await {
+            eprintln!("Block follower failed: {}", e);
+        }
+
+        let state = std::mem::take(&mut *self.state.lock().unwrap());
+        let unmatched: Vec<H256> = state.pending.keys().copied().collect();
+        let dropped = match lookup {
+            Some(lookup) => find_dropped(lookup, &unmatched).await,
+            None => find_dropped_each(&self.provider, &unmatched).await,
+        };
+        write_records(&self.recorder, self.chain_id, &state, &dropped);
+        let dropped = dropped.len();
+
+        let included = state.latencies.len();
+        let phase_count = state.latencies.iter().map(|(sent, _)| sent.phase + 1).max().unwrap_or(0);
+        let mut by_phase = vec![Vec::new(); phase_count];
+        let mut by_kind: BTreeMap<Kind, Vec<Duration>> = BTreeMap::new();
+        let mut by_lane: BTreeMap<Lane, Vec<Duration>> = BTreeMap::new();
+        for (sent, latency) in &state.latencies {
+            by_phase[sent.phase].push(*latency);
+            by_kind.entry(sent.kind).or_default().push(*latency);
+            by_lane.entry(sent.lane).or_default().push(*latency);
+        }
+        let phases = by_phase.into_iter()
+            .map(|latencies| PhaseInclusion {
+                included: latencies.len(),
+                latency: Percentiles::from_samples(latencies),
+            })
+            .collect();
+        let kinds = state.kinds.iter()
+            .map(|(kind, counts)| {
+                let latencies = by_kind.remove(kind).unwrap_or_default();
+                KindInclusion {
+                    kind: *kind,
+                    tracked: counts.tracked,
+                    included: latencies.len(),
+                    latency: Percentiles::from_samples(latencies),
+                    receipts: counts.receipts,
+                    reverted: counts.reverted,
+                    gas_used: counts.gas_used,
+                }
+            })
+            .collect();
+        let lanes = state.lanes.iter()
+            .map(|(lane, counts)| {
+                let latencies = by_lane.remove(lane).unwrap_or_default();
+                LaneInclusion {
+                    lane: *lane,
+                    tracked: counts.tracked,
+                    rejected: counts.rejected,
+                    included: latencies.len(),
+                    latency: Percentiles::from_samples(latencies),
+                    blocks: counts.per_block.len(),
+                    max_per_block: counts.per_block.values().copied().max().unwrap_or(0),
+                }
+            })
+            .collect();
+        let inclusion_tps = match state.last_inclusion {
+            Some(last) if last > self.started => {
+                included as f64 / (last - self.started).as_secs_f64()
+            }
+            _ => 0.0,
+        };
+
+        ConfirmationStats {
+            tracked: state.tracked,
+            included,
+            dropped,
+            pending: unmatched.len() - dropped,
+            blocks: state.blocks,
+            head_subscription: self.head_subscription,
+            latency: Percentiles::from_samples(
+                state.latencies.into_iter().map(|(_, latency)| latency).collect(),
+            ),
+            inclusion_tps,
+            phases,
+            kinds,
+            lanes,
+        }
+    }
+}
+
+// One record per tracked transaction; rejected ones were recorded as they happened
+fn write_records(recorder: &Recorder, chain_id: u64, state: &TrackerState, dropped: &HashSet<H256>) {
+    for (sent, latency) in &state.latencies {
+        let receipt = state.receipts.get(&sent.hash);
+        let status = match receipt {
+            Some((_, true)) => TxStatus::Reverted,
+            _ => TxStatus::Included,
+        };
+        let mut record = sent.record(chain_id, status).timed(sent.at, Some(sent.at + *latency));
+        record.block_number = state.mined.get(&sent.hash).copied();
+        record.gas_used = receipt.map(|(gas, _)| *gas);
+        recorder.record(record);
+    }
+    for (hash, sent) in &state.pending {
+        let status = if dropped.contains(hash) { TxStatus::Dropped } else { TxStatus::Pending };
+        recorder.record(sent.record(chain_id, status).timed(sent.at, None));
+    }
+}
+
+async fn follow_blocks(
+    provider: Arc<RpcProvider>,
+    mut heads: HeadWatcher,
+    state: Arc<Mutex<TrackerState>>,
+    stop: Arc<AtomicBool>,
+    poll_interval: Duration,
+    receipts: bool,
+) {
+    let mut next_block = heads.head() + 1;
+    loop {
+        let stopping = stop.load(Ordering::SeqCst);
+
+        // The last scan asks the node directly so a polled head cannot lag behind
+        let head = if stopping {
+            provider.get_block_number().await.map(|head| head.as_u64())
+        } else {
+            Ok(heads.head())
+        };
+        match head {
+            Ok(head) => {
+                while next_block <= head {
+                    match provider.get_block(next_block).await {
+                        Ok(Some(block)) => {
+                            let seen_at = Instant::now();
+                            let mut matched = HashMap::new();
+                            {
+                                let mut state = state.lock().unwrap();
+                                state.blocks += 1;
+                                for hash in block.transactions {
+                                    if let Some(sent) = state.pending.remove(&hash) {
+                                        state.latencies.push((sent, seen_at - sent.at));
+                                        state.mined.insert(hash, next_block);
+                                        metrics::included(seen_at - sent.at);
+                                        state.last_inclusion = Some(seen_at);
+                                        *state.lanes.entry(sent.lane).or_default()
+                                            .per_block.entry(next_block).or_default() += 1;
+                                        matched.insert(hash, sent.kind);
+                                    }
+                                }
+                            }
+                            if receipts && !matched.is_empty() {
+                                count_gas(&provider, next_block, &matched, &state).await;
+                            }
+                            next_block += 1;
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            eprintln!("Failed to fetch block {}: {}", next_block, e);
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to get block number: {}", e),
+        }
+
+        if stopping {
+            break;
+        }
+        // Wake up for the next head, or after a poll interval to notice `stop`
+        let _ = time::timeout(poll_interval, heads.next()).await;
+    }
+}
+
+// Adds the gas used and reverts of the tracked transactions in `block` to their kinds
+async fn count_gas(provider: &RpcProvider, block: u64, matched: &HashMap<H256, Kind>, state: &Mutex<TrackerState>) {
+    let receipts = match provider.get_block_receipts(block).await {
+        Ok(receipts) => receipts,
+        Err(e) => {
+            eprintln!("Failed to fetch receipts of block {}: {}", block, e);
+            return;
+        }
+    };
+    let mut state = state.lock().unwrap();
+    for receipt in receipts {
+        if let Some(kind) = matched.get(&receipt.transaction_hash) {
+            let gas_used = receipt.gas_used.map_or(0, |gas| gas.as_u64());
+            let reverted = receipt.status.is_some_and(|status| status.is_zero());
+            let counts = state.kinds.entry(*kind).or_default();
+            counts.receipts += 1;
+            counts.gas_used += gas_used;
+            if reverted {
+                counts.reverted += 1;
+            }
+            state.receipts.insert(receipt.transaction_hash, (gas_used, reverted));
+        }
+    }
+}
+
+// Transactions the node no longer knows about; lookup failures count as still pending
+async fn find_dropped_each(provider: &RpcProvider, hashes: &[H256]) -> HashSet<H256> {
+    let mut dropped = HashSet::new();
+    for hash in hashes {
+        match provider.get_transaction(*hash).await {
+            Ok(None) => {
+                dropped.insert(*hash);
+            }
+            Ok(Some(_)) => {}
+            Err(e) => eprintln!("Failed to look up pending transaction {:?}: {}", hash, e),
+        }
+    }
+    dropped
+}
+
+// Same as `find_dropped_each`, in JSON-RPC batches over HTTP
+async fn find_dropped(lookup: &BatchClient, hashes: &[H256]) -> HashSet<H256> {
+    let mut dropped = HashSet::new();
+    for chunk in hashes.chunks(LOOKUP_BATCH_SIZE) {
+        let calls: Vec<(&str, Value)> = chunk.iter()
+            .map(|hash| ("eth_getTransactionByHash", json!([hash])))
+            .collect();
+        match lookup.call(&calls).await {
+            Ok(response) => {
+                dropped.extend(chunk.iter().zip(&response.results)
+                    .filter(|(_, result)| matches!(result, Ok(Value::Null)))
+                    .map(|(hash, _)| *hash));
+            }
+            Err(e) => eprintln!("Failed to look up pending transactions: {}", e),
+        }
+    }
+    dropped
+}
@@ -0,0 +1,48 @@
+// Latency percentiles shared by the tx-generator reports
+
+use serde_json::{json, Value};
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Percentiles {
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl Percentiles {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        Percentiles {
+            p50: percentile(&samples, 50),
+            p90: percentile(&samples, 90),
+            p99: percentile(&samples, 99),
+            max: samples.last().copied().unwrap_or_default(),
+        }
+    }
+
+    pub fn to_json(self) -> Value {
+        json!({
+            "p50": self.p50.as_millis(),
+            "p90": self.p90.as_millis(),
+            "p99": self.p99.as_millis(),
+            "max": self.max.as_millis(),
+        })
+    }
+}
+
+impl std::fmt::Display for Percentiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "p50 {:.2?}, p90 {:.2?}, p99 {:.2?}, max {:.2?}", self.p50, self.p90, self.p99, self.max)
+    }
+}
+
+// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -0,0 +1,1072 @@
+// src/main.rs for tx-generator
+
+mod compare;
+mod confirmation;
+mod latency;
+mod profile;
+mod scheduler;
+mod search;
+mod source;
+mod token;
+mod traffic;
+mod workload;
+
+use bridge_core::{
+    connect, metrics, Account, AccountStore, BridgeError, Dashboard, DashboardHandle, EndpointArgs, EndpointStats, FeeArgs,
+    Fees, Lease, MetricsArgs, NodeArgs, Recorder, RpcProvider, Run, RunArgs, TransportKind, TxpoolArgs, TxpoolSummary,
+};
+use clap::{CommandFactory, Parser};
+use ethers::{core::types::U256, providers::Middleware};
+use futures::future::join_all;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::{
+    fs::File,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+use tokio::{sync::Semaphore, time};
+
+use compare::CompareArgs;
+use confirmation::{ConfirmationStats, ConfirmationTracker, TrackerHandle};
+use profile::Profile;
+use scheduler::{Schedule, ScheduleStats, LATE_AFTER};
+use search::SearchArgs;
+use source::{NonceReport, SignedTx, TxSource};
+use traffic::{Jitter, Lane, Traffic, TrafficArgs};
+use workload::{Kind, Workload, WorkloadArgs};
+
+// CLI argument parsing
+#[derive(Parser, Debug, Clone)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// RPC endpoint: HTTP or WebSocket URL, or IPC socket path
+    #[clap(short = 'u', long, default_value = "http://localhost:8545")]
+    rpc_url: String,
+
+    /// Take the RPC endpoint from a topology or .env node instead of --rpc-url
+    #[clap(flatten)]
+    nodes: NodeArgs,
+
+    /// Number of sender accounts to generate
+    #[clap(short = 's', long, default_value_t = 3000)]
+    sender_count: usize,
+
+    /// Number of receiver accounts to generate
+    #[clap(short = 'r', long, default_value_t = 3000)]
+    receiver_count: usize,
+
+    /// Generate accounts only (no transactions)
+    #[clap(long)]
+    gen_accounts: bool,
+
+    /// Total number of transactions to send
+    #[clap(short = 't', long, default_value_t = 10000)]
+    tx_count: usize,
+
+    /// Maximum concurrent transactions
+    #[clap(short = 'c', long, default_value_t = 100)]
+    concurrency: usize,
+
+    /// Batch size for transaction submission
+    #[clap(short = 'b', long, default_value_t = 100)]
+    batch_size: usize,
+
+    /// Send each batch as one JSON-RPC 2.0 batch of eth_sendRawTransaction calls (HTTP only)
+    #[clap(long)]
+    use_batching: bool,
+
+    /// Target TPS (0 = max speed)
+    #[clap(long, default_value_t = 0)]
+    target_tps: usize,
+
+    /// Release transactions (or batches) on a fixed --target-tps timetable instead of
+    /// pacing each task after its send returns; --concurrency still caps in-flight sends
+    #[clap(long, requires = "target_tps")]
+    open_loop: bool,
+
+    /// Open-loop load profile instead of --tx-count at --target-tps, as comma-separated
+    /// phases: ramp:FROM:TO:SECS, hold:TPS:SECS, steps:FROM:TO:STEP:HOLD_SECS,
+    /// spike:BASE:PEAK:SECS:EVERY_SECS:BURST_SECS
+    #[clap(long, conflicts_with_all = ["profile_file", "open_loop"])]
+    profile: Option<String>,
+
+    /// TOML load profile with one [[phase]] table per phase (see --profile)
+    #[clap(long, conflicts_with = "open_loop")]
+    profile_file: Option<String>,
+
+    /// Path to accounts file (if already generated)
+    #[clap(long)]
+    accounts_file: Option<String>,
+
+    /// Generate genesis file with pre-funded accounts
+    #[clap(long)]
+    gen_genesis: bool,
+
+    /// Seconds to wait for submitted transactions to be included after sending
+    #[clap(long, default_value_t = 60)]
+    confirm_timeout: u64,
+
+    /// Interval for polling new blocks while tracking inclusion; WebSocket and IPC
+    /// endpoints subscribe to newHeads instead
+    #[clap(long, default_value_t = 250)]
+    block_poll_ms: u64,
+
+    #[clap(flatten)]
+    search: SearchArgs,
+
+    #[clap(flatten)]
+    compare: CompareArgs,
+
+    /// What the transactions do: transfers, contract calls, storage writes or a mix
+    #[clap(flatten)]
+    workload: WorkloadArgs,
+
+    /// Seed and account choice, so a run can be repeated with exactly the same traffic
+    #[clap(flatten)]
+    traffic: TrafficArgs,
+
+    /// Transaction type and fees for signed transfers; legacy at 1 gwei by default
+    #[clap(flatten)]
+    fees: FeeArgs,
+
+    /// Sign for this chain ID instead of asking the node
+    #[clap(long)]
+    chain_id: Option<u64>,
+
+    /// Start every sender at this nonce instead of its pending nonce on the node;
+    /// with --chain-id a corpus can be generated offline
+    #[clap(long)]
+    start_nonce: Option<u64>,
+
+    /// Pre-sign --tx-count transactions (or a profile's worth) into this JSONL file and exit
+    #[clap(long, conflicts_with = "replay_corpus")]
+    generate_corpus: Option<String>,
+
+    /// Send the pre-signed transactions from this corpus instead of signing during the run
+    #[clap(long)]
+    replay_corpus: Option<String>,
+
+    /// Several RPC endpoints of the same chain to send through, with failover
+    #[clap(flatten)]
+    endpoints: EndpointArgs,
+
+    /// Show a live dashboard of send and inclusion rates, errors, txpool and blocks,
+    /// refreshed every second; a log line a second when stdout is not a terminal
+    #[clap(long)]
+    dashboard: bool,
+
+    #[clap(flatten)]
+    metrics: MetricsArgs,
+
+    #[clap(flatten)]
+    txpool: TxpoolArgs,
+
+    #[clap(flatten)]
+    runs: RunArgs,
+}
+
+impl Args {
+    async fn fees(&self, provider: &RpcProvider) -> Result<Fees> {
+        let fees = self.fees.resolve(provider, Some(U256::exp10(9))).await?;
+        println!("Transaction fees: {}", fees);
+        Ok(fees)
+    }
+
+    // Set in main before any account or transaction is generated
+    fn seed(&self) -> u64 {
+        self.traffic.seed.expect("seed is resolved at startup")
+    }
+
+    async fn workload(&self, provider: &Arc<RpcProvider>, senders: &[Account], receivers: usize) -> Result<Workload> {
+        let traffic = Traffic::new(&self.traffic, self.seed(), senders.len(), receivers)?;
+        let senders = senders.iter()
+            .map(|sender| sender.address())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let workload = Workload::resolve(&self.workload, traffic, provider, &senders).await?;
+        println!("Workload: {}", workload);
+        Ok(workload)
+    }
+
+    // Run parameters, as saved under `config` in tx_stats.json and in the run manifest
+    fn config(&self) -> serde_json::Value {
+        serde_json::json!({
+            "rpc_url": self.rpc_url,
+            "endpoints": self.endpoints.endpoints,
+            "balance": self.endpoints.balance,
+            "tx_count": self.tx_count,
+            "target_tps": self.target_tps,
+            "concurrency": self.concurrency,
+            "batch_size": self.batch_size,
+            "use_batching": self.use_batching,
+            "open_loop": self.open_loop,
+            "replay_corpus": self.replay_corpus,
+            "workload": self.workload.workload,
+            "seed": self.traffic.seed,
+            "sender_dist": self.traffic.sender_dist.to_string(),
+            "receiver_dist": self.traffic.receiver_dist.to_string(),
+            "jitter": self.traffic.jitter,
+            "hot_sender_share": self.traffic.hot_sender_share,
+            "hot_receiver_share": self.traffic.hot_receiver_share,
+            "hot_receivers": self.traffic.hot_receivers,
+            "amount_gwei": self.workload.amount_gwei.to_string(),
+            "profile": self.profile.as_ref().or(self.profile_file.as_ref()),
+            "confirm_timeout_s": self.confirm_timeout,
+        })
+    }
+
+    // Files the run under --runs-dir, with the version of every endpoint it sends through
+    async fn start_run(&self, command: &str) -> Result<Run> {
+        let urls = if self.compare.compare_endpoints.is_empty() {
+            self.endpoints.urls(&self.rpc_url)
+        } else {
+            self.compare.compare_endpoints.clone()
+        };
+        let nodes: Vec<(String, String)> = urls.into_iter().enumerate()
+            .map(|(i, url)| match &self.nodes.node {
+                Some(node) if i == 0 => (node.clone(), url),
+                _ => (format!("endpoint{}", i + 1), url),
+            })
+            .collect();
+        let topology = self.nodes.topology.topology.as_deref();
+        Ok(self.runs.start("tx-generator", command, self.config(), topology, &nodes, &Args::command()).await?)
+    }
+}
+
+// Files the run's other outputs and closes it with the outcome of `result`
+fn finish_run<T>(mut run: Run, files: &[&str], txpool: &TxpoolArgs, result: Result<T>) -> Result<T> {
+    for file in files {
+        run.attach(file);
+    }
+    for file in txpool.files() {
+        run.attach(file);
+    }
+    run.finish(result.as_ref().err().map(ToString::to_string))?;
+    result
+}
+
+// Statistics for transaction processing
+#[derive(Debug)]
+struct TxStats {
+    /// Transport of the endpoint that follows blocks (the first send endpoint)
+    transport: TransportKind,
+    /// Requests, errors and latency per send endpoint
+    endpoints: Vec<EndpointStats>,
+    submitted: usize,
+    /// Accepted by the RPC; see `confirmation` for what was actually included
+    confirmed: usize,
+    failed: usize,
+    avg_latency: Duration,
+    total_time: Duration,
+    tps: f64,
+    /// JSON-RPC batch requests sent and their total body size (batching mode only)
+    rpc_batches: usize,
+    rpc_batch_bytes: usize,
+    confirmation: ConfirmationStats,
+    /// Intended versus actual send times (open-loop mode only)
+    schedule: Option<ScheduleStats>,
+    /// Nonce repairs and stuck senders (signing during the run only)
+    nonces: Option<NonceReport>,
+    /// Pool peaks and nonce gaps (with --txpool-sample-ms only)
+    txpool: Option<TxpoolSummary>,
+}
+
+// Error handling
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    
+    #[error("Provider error: {0}")]
+    Provider(String),
+    
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    
+    #[error("Invalid load profile: {0}")]
+    Profile(String),
+    
+    #[error(transparent)]
+    Bridge(#[from] BridgeError),
+    
+    #[error("Other error: {0}")]
+    Other(String),
+}
+
+impl From<Box<dyn std::error::Error>> for AppError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        AppError::Other(err.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, AppError>;
+
+// Generate random accounts; keys come from the seed only when --seed was given, since
+// a logged seed would otherwise let anyone with tx_stats.json or the manifest rebuild them
+async fn generate_accounts(sender_count: usize, receiver_count: usize, seed: Option<u64>) -> Result<(Vec<Account>, Vec<Account>)> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    
+    println!("Generating {} sender and {} receiver accounts...", sender_count, receiver_count);
+    let store = AccountStore::generate(sender_count, receiver_count, &mut rng);
+    
+    Ok((store.senders, store.receivers))
+}
+// Save accounts to file
+fn save_accounts(senders: &[Account], receivers: &[Account], filename: &str) -> Result<()> {
+    AccountStore::new(senders.to_vec(), receivers.to_vec()).save(filename)?;
+    
+    println!("Accounts saved to {}", filename);
+    Ok(())
+}
+
+// Load accounts from file
+fn load_accounts(filename: &str) -> Result<(Vec<Account>, Vec<Account>)> {
+    let AccountStore { senders, receivers, .. } = AccountStore::load(filename)?;
+    
+    println!("Loaded {} senders and {} receivers from {}", senders.len(), receivers.len(), filename);
+    Ok((senders, receivers))
+}
+
+// Generate genesis file with pre-funded accounts
+fn generate_genesis(senders: &[Account], base_genesis: &str, output: &str) -> Result<()> {
+    // Read base genesis file
+    let file = File::open(base_genesis)?;
+    let mut genesis: serde_json::Value = serde_json::from_reader(file)?;
+    
+    // Add sender accounts with balance
+    let alloc = genesis["alloc"].as_object_mut().unwrap();
+    for sender in senders {
+        // Process address to get the correct format for the genesis file
+        let address = if sender.address.starts_with("0x") {
+            sender.address[2..].to_string()
+        } else {
+            sender.address.clone()
+        };
+        
+        // Clean up any extra formatting
+        let address = address.trim_start_matches("0x").trim();
+        
+        let balance = "0x152d02c7e14af6800000"; // 100K ETH in hex
+        
+        alloc.insert(
+            address.to_string(),
+            serde_json::json!({
+                "balance": balance
+            }),
+        );
+    }
+    
+    // Write updated genesis file
+    let file = File::create(output)?;
+    serde_json::to_writer_pretty(file, &genesis)?;
+    
+    println!("Genesis file with {} funded accounts saved to {}", senders.len(), output);
+    Ok(())
+}
+
+// Send a single signed transaction through the endpoint picked for its sender
+async fn send_single_transaction(
+    lease: Result<Lease>,
+    tracker: &TrackerHandle,
+    dashboard: &DashboardHandle,
+    source: &TxSource,
+    tx: SignedTx,
+    sent_at: Instant,
+    phase: usize,
+) -> Result<ethers::types::H256> {
+    dashboard.started(1);
+    metrics::submitted(1);
+    let lease = match lease {
+        Ok(lease) => lease,
+        Err(e) => {
+            dashboard.failed(&e.to_string());
+            metrics::rejected(1);
+            tracker.rejected(&tx, sent_at, phase, &e.to_string());
+            source.rejected(&tx, &e.to_string());
+            return Err(e);
+        }
+    };
+    
+    // Track the hash before sending so a fast inclusion cannot be missed
+    tracker.submitted(&tx, sent_at, phase);
+    
+    let result = lease.provider().send_raw_transaction(tx.raw.clone()).await
+        .map(|pending_tx| pending_tx.tx_hash());
+    lease.record(&result);
+    match result {
+        Ok(tx_hash) => {
+            dashboard.accepted(1, sent_at.elapsed());
+            metrics::accepted(1);
+            Ok(tx_hash)
+        }
+        Err(e) => {
+            dashboard.rejected(&e);
+            metrics::rejected(1);
+            tracker.rejected(&tx, sent_at, phase, &e.to_string());
+            source.rejected(&tx, &e.to_string());
+            Err(AppError::Provider(format!("Failed to send transaction: {}", e)))
+        }
+    }
+}
+
+// Main transaction sending function
+async fn send_transactions(
+    args: Args,
+    profile: Option<Profile>,
+    source: TxSource,
+    recorder: Recorder,
+) -> Result<TxStats> {
+    // Create providers
+    let pool = Arc::new(args.endpoints.connect(&args.rpc_url).await?);
+    if args.use_batching {
+        if let Some(endpoint) = pool.endpoints().iter().find(|endpoint| endpoint.batch().is_none()) {
+            return Err(AppError::Other(format!(
+                "--use-batching needs HTTP endpoints, but {} is {}", endpoint.url(), endpoint.kind()
+            )));
+        }
+    }
+    let provider = pool.primary().provider().clone();
+    let transport = pool.primary().kind();
+    if pool.len() > 1 {
+        println!("Sending through {} endpoints ({:?} balancing)", pool.len(), args.endpoints.balance);
+    }
+    
+    // Get chain ID
+    let chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(|e| AppError::Provider(format!("Failed to get chain ID: {}", e)))?
+        .as_u64();
+    
+    println!("Connected to chain ID: {}", chain_id);
+    if chain_id != source.chain_id() {
+        return Err(AppError::Other(format!(
+            "Transactions are signed for chain ID {}, but the node is on {}",
+            source.chain_id(), chain_id
+        )));
+    }
+    
+    let tx_counter = Arc::new(AtomicUsize::new(0));
+    let confirmed_counter = Arc::new(AtomicUsize::new(0));
+    let failed_counter = Arc::new(AtomicUsize::new(0));
+    
+    let start_time = Instant::now();
+    let semaphore = Arc::new(Semaphore::new(args.concurrency));
+    let jitter = Jitter { seed: args.seed(), fraction: args.traffic.jitter };
+    let schedule = profile.map(|profile| Schedule::new(profile, jitter));
+    
+    let latency_sum = Arc::new(Mutex::new(Duration::from_secs(0)));
+    
+    let tracker = ConfirmationTracker::start(
+        provider.clone(),
+        Duration::from_millis(args.block_poll_ms),
+        source.needs_receipts(),
+        recorder,
+        chain_id,
+    ).await?;
+    // Dropped transactions are looked up in JSON-RPC batches where the endpoint allows it
+    let lookup_client = pool.primary().batch();
+    let dashboard = args.dashboard.then(|| Dashboard::start(provider.clone(), "tx-generator"));
+    let board = dashboard.as_ref().map(Dashboard::handle).unwrap_or_default();
+    let sampler = args.txpool.start(provider.clone(), board.clone())?;
+    
+    println!("Starting transaction generation...");
+    println!("Target: {} transactions", args.tx_count);
+    
+    let rpc_batches = Arc::new(AtomicUsize::new(0));
+    let rpc_batch_bytes = Arc::new(AtomicUsize::new(0));
+    
+    if args.use_batching {
+        // Batch mode: every batch is sent as one JSON-RPC batch request per endpoint it is spread over
+        let batch_count = (args.tx_count + args.batch_size - 1) / args.batch_size;
+        let mut handles = Vec::new();
+        
+        for batch_idx in 0..batch_count {
+            // Open loop: wait for the batch's slot, and take the permit inside the task
+            // so a full pipeline shows up as send lag instead of delaying the timetable
+            let (due, permit) = match &schedule {
+                Some(schedule) => (Some(schedule.wait(batch_idx * args.batch_size).await), None),
+                None => {
+                    let permit = semaphore.clone().acquire_owned().await
+                        .map_err(|e| AppError::Other(e.to_string()))?;
+                    (None, Some(permit))
+                }
+            };
+            
+            let semaphore = semaphore.clone();
+            let schedule = schedule.clone();
+            let pool = pool.clone();
+            let tx_counter = tx_counter.clone();
+            let confirmed_counter = confirmed_counter.clone();
+            let failed_counter = failed_counter.clone();
+            let latency_sum = latency_sum.clone();
+            let rpc_batches = rpc_batches.clone();
+            let rpc_batch_bytes = rpc_batch_bytes.clone();
+            let source = source.clone();
+            let tracker = tracker.handle();
+            let board = board.clone();
+            let batch_size = args.batch_size;
+            let tx_count = args.tx_count;
+            let target_tps = args.target_tps;
+            
+            let batch_start = batch_idx * batch_size;
+            let batch_end = std::cmp::min(batch_start + batch_size, tx_count);
+            let actual_batch_size = batch_end - batch_start;
+            
+            let handle = tokio::spawn(async move {
+                let permit = match permit {
+                    Some(permit) => permit,
+                    None => semaphore.acquire_owned().await.map_err(|e| e.to_string())?,
+                };
+                
+                // Latency is measured from the intended send time in open-loop mode
+                let start = match (&schedule, due) {
+                    (Some(schedule), Some(due)) => {
+                        schedule.record_send(batch_start, due);
+                        due
+                    }
+                    _ => Instant::now(),
+                };
+                let phase = schedule.as_ref().map_or(0, |schedule| schedule.phase_of(batch_start));
+                tx_counter.fetch_add(actual_batch_size, Ordering::SeqCst);
+                
+                if batch_idx % 10 == 0 {
+                    println!("Sending batch {}/{} ({} transactions)", 
+                             batch_idx + 1, batch_count, actual_batch_size);
+                }
+                
+                // Sign (or look up) every transaction in the batch
+                let mut expected = Vec::with_capacity(actual_batch_size);
+                for tx_idx in batch_start..batch_end {
+                    let tx = source.get(tx_idx).map_err(|e| e.to_string())?;
+                    expected.push((tx_idx, tx));
+                }
+                
+                board.started(actual_batch_size);
+                metrics::submitted(actual_batch_size);
+                
+                // Sticky balancing splits the batch by sender; each part goes out as its own request
+                let positions = expected.iter().enumerate()
+                    .map(|(position, (tx_idx, _))| (source.sender_index(*tx_idx), position))
+                    .collect();
+                let groups = match pool.split(positions) {
+                    Ok(groups) => groups,
+                    Err(e) => {
+                        eprintln!("Batch {} ({} transactions) not sent: {}", batch_idx + 1, actual_batch_size, e);
+                        for (tx_idx, tx) in &expected {
+                            board.failed(&e.to_string());
+                            tracker.rejected(tx, start, phase, &e.to_string());
+                            source.rejected(tx, &e.to_string());
+                            if let Some(schedule) = &schedule {
+                                schedule.record_result(*tx_idx, false, start.elapsed());
+                            }
+                        }
+                        metrics::rejected(actual_batch_size);
+                        failed_counter.fetch_add(actual_batch_size, Ordering::SeqCst);
+                        drop(permit);
+                        return Ok(());
+                    }
+                };
+                
+                for (_, tx) in &expected {
+                    tracker.submitted(tx, start, phase);
+                }
+                
+                let sends = groups.into_iter().map(|(lease, positions)| {
+                    let raw_txs: Vec<_> = positions.iter().map(|&position| expected[position].1.raw.clone()).collect();
+                    async move {
+                        let batch_client = lease.batch().expect("--use-batching endpoints are HTTP");
+                        let response = batch_client.send_raw_transactions(&raw_txs).await;
+                        (lease, positions, response)
+                    }
+                });
+                let responses = join_all(sends).await;
+                let elapsed = start.elapsed();
+                
+                // Map each item of the batch responses back to its transaction
+                for (lease, positions, response) in responses {
+                    match response {
+                        Ok(response) => {
+                            rpc_batches.fetch_add(1, Ordering::SeqCst);
+                            rpc_batch_bytes.fetch_add(response.request_bytes, Ordering::SeqCst);
+                            
+                            let rejected = response.results.iter().filter(|result| result.is_err()).count();
+                            lease.answered(positions.len() - rejected, rejected);
+                            
+                            for (&position, result) in positions.iter().zip(response.results) {
+                                let (tx_idx, tx) = &expected[position];
+                                if let Some(schedule) = &schedule {
+                                    schedule.record_result(*tx_idx, result.is_ok(), elapsed);
+                                }
+                                match result {
+                                    Ok(tx_hash) => {
+                                        if tx_hash != tx.hash {
+                                            eprintln!("Transaction {} returned hash {:?}, expected {:?}", tx_idx, tx_hash, tx.hash);
+                                        }
+                                        if tx_idx % 1000 == 0 && !board.is_live() {
+                                            println!("Transaction {} sent: {:?}", tx_idx, tx_hash);
+                                        }
+                                        board.accepted(1, elapsed);
+                                        metrics::accepted(1);
+                                        confirmed_counter.fetch_add(1, Ordering::SeqCst);
+                                    },
+                                    Err(e) => {
+                                        if !board.is_live() {
+                                            eprintln!("Transaction {} from {:?} (nonce {}) error: {}", tx_idx, tx.from, tx.nonce, e);
+                                        }
+                                        board.failed(&e.message);
+                                        metrics::rejected(1);
+                                        tracker.rejected(tx, start, phase, &e.message);
+                                        source.rejected(tx, &e.message);
+                                        failed_counter.fetch_add(1, Ordering::SeqCst);
+                                    }
+                                }
+                            }
+                        },
+                        Err(e) => {
+                            eprintln!("Batch {} ({} transactions to {}) error: {}",
+                                batch_idx + 1, positions.len(), lease.endpoint().url(), e);
+                            for &position in &positions {
+                                let (tx_idx, tx) = &expected[position];
+                                board.failed(&e.to_string());
+                                metrics::rejected(1);
+                                tracker.rejected(tx, start, phase, &e.to_string());
+                                source.rejected(tx, &e.to_string());
+                                if let Some(schedule) = &schedule {
+                                    schedule.record_result(*tx_idx, false, elapsed);
+                                }
+                            }
+                            failed_counter.fetch_add(positions.len(), Ordering::SeqCst);
+                            lease.failed(e);
+                        }
+                    }
+                }
+                
+                // Update latency stats; every transaction in the batch waited for the whole request
+                {
+                    let mut latency = latency_sum.lock().unwrap();
+                    *latency += elapsed * actual_batch_size as u32;
+                }
+                
+                // Closed-loop rate limiting if target TPS is set
+                if target_tps > 0 && schedule.is_none() {
+                    let target_batch_time = Duration::from_secs_f64(actual_batch_size as f64 / target_tps as f64);
+                    if elapsed < target_batch_time {
+                        time::sleep(target_batch_time - elapsed).await;
+                    }
+                }
+                
+                drop(permit);
+                
+                Ok::<(), String>(())
+            });
+            
+            handles.push(handle);
+        }
+        
+        // Wait for all batches to complete
+        for handle in handles {
+            handle.await
+                .map_err(|e| AppError::Other(e.to_string()))?
+                .map_err(AppError::Other)?;
+        }
+    } else {
+        // Individual transaction mode
+        let mut handles = Vec::new();
+        
+        for tx_idx in 0..args.tx_count {
+            let (due, permit) = match &schedule {
+                Some(schedule) => (Some(schedule.wait(tx_idx).await), None),
+                None => {
+                    let permit = semaphore.clone().acquire_owned().await
+                        .map_err(|e| AppError::Other(e.to_string()))?;
+                    (None, Some(permit))
+                }
+            };
+            
+            let semaphore = semaphore.clone();
+            let schedule = schedule.clone();
+            let pool = pool.clone();
+            let source = source.clone();
+            let tx_counter = tx_counter.clone();
+            let confirmed_counter = confirmed_counter.clone();
+            let failed_counter = failed_counter.clone();
+            let latency_sum = latency_sum.clone();
+            let target_tps = args.target_tps;
+            let tracker = tracker.handle();
+            let board = board.clone();
+            
+            let handle = tokio::spawn(async move {
+                let permit = match permit {
+                    Some(permit) => permit,
+                    None => semaphore.acquire_owned().await?,
+                };
+                
+                // Latency is measured from the intended send time in open-loop mode
+                let start = match (&schedule, due) {
+                    (Some(schedule), Some(due)) => {
+                        schedule.record_send(tx_idx, due);
+                        due
+                    }
+                    _ => Instant::now(),
+                };
+                let phase = schedule.as_ref().map_or(0, |schedule| schedule.phase_of(tx_idx));
+                tx_counter.fetch_add(1, Ordering::SeqCst);
+                
+                // Sign (or look up) and send transaction
+                let tx = source.get(tx_idx)?;
+                let lease = pool.acquire(source.sender_index(tx_idx)).map_err(AppError::from);
+                let accepted = match send_single_transaction(
+                    lease,
+                    &tracker,
+                    &board,
+                    &source,
+                    tx,
+                    start,
+                    phase
+                ).await {
+                    Ok(tx_hash) => {
+                        if tx_idx % 1000 == 0 && !board.is_live() {
+                            println!("Transaction {} sent: {:?}", tx_idx, tx_hash);
+                        }
+                        confirmed_counter.fetch_add(1, Ordering::SeqCst);
+                        true
+                    },
+                    Err(e) => {
+                        if !board.is_live() {
+                            eprintln!("Transaction error: {}", e);
+                        }
+                        failed_counter.fetch_add(1, Ordering::SeqCst);
+                        false
+                    }
+                };
+                
+                let elapsed = start.elapsed();
+                if let Some(schedule) = &schedule {
+                    schedule.record_result(tx_idx, accepted, elapsed);
+                }
+                
+                // Update latency stats
+                {
+                    let mut latency = latency_sum.lock().unwrap();
+                    *latency += elapsed;
+                }
+                
+                // Closed-loop rate limiting if target TPS is set
+                if target_tps > 0 && schedule.is_none() {
+                    let target_tx_time = Duration::from_secs_f64(1.0 / target_tps as f64);
+                    if elapsed < target_tx_time {
+                        time::sleep(target_tx_time - elapsed).await;
+                    }
+                }
+                
+                drop(permit);
+                
+                Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+            });
+            
+            handles.push(handle);
+        }
+        
+        // Wait for all transactions to complete
+        for handle in handles {
+            handle.await
+                .map_err(|e| AppError::Other(e.to_string()))?
+                .map_err(|e| AppError::Other(e.to_string()))?;
+        }
+    }
+    
+    let total_time = start_time.elapsed();
+    let tps = args.tx_count as f64 / total_time.as_secs_f64();
+    
+    let submitted = tx_counter.load(Ordering::SeqCst);
+    let confirmed = confirmed_counter.load(Ordering::SeqCst);
+    let failed = failed_counter.load(Ordering::SeqCst);
+    
+    let latency = *latency_sum.lock().unwrap();
+    let avg_latency = if submitted > 0 {
+        latency / submitted as u32
+    } else {
+        Duration::from_secs(0)
+    };
+    
+    // Fill the gaps no later transaction re-used before waiting for inclusion
+    let filled = source.repair_nonces().await?;
+    if filled > 0 {
+        println!("Filled {} nonce gaps left by rejected transactions", filled);
+    }
+    
+    let confirmation = tracker.finish(Duration::from_secs(args.confirm_timeout), lookup_client).await;
+    if let Some(dashboard) = dashboard {
+        dashboard.finish().await;
+    }
+    let txpool = match sampler {
+        Some(sampler) => sampler.finish().await,
+        None => None,
+    };
+    let nonces = source.nonce_report().await?;
+    let schedule = schedule.map(|schedule| {
+        let mut stats = schedule.stats();
+        stats.add_inclusion(&confirmation.phases);
+        stats
+    });
+    
+    Ok(TxStats {
+        transport,
+        endpoints: pool.stats(),
+        submitted,
+        confirmed,
+        failed,
+        avg_latency,
+        total_time,
+        tps,
+        rpc_batches: rpc_batches.load(Ordering::SeqCst),
+        rpc_batch_bytes: rpc_batch_bytes.load(Ordering::SeqCst),
+        confirmation,
+        schedule,
+        nonces,
+        txpool,
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    let mut args = Args::parse();
+    if args.nodes.node.is_some() {
+        let node = args.nodes.node_or("1")?;
+        args.rpc_url = node.rpc_url;
+        args.workload.contract_address.get_or_insert(format!("{:?}", node.contract));
+    }
+    if args.open_loop && args.target_tps == 0 {
+        return Err(AppError::Other("--open-loop needs a --target-tps above 0".to_string()));
+    }
+    args.traffic.validate()?;
+    let key_seed = args.traffic.seed;
+    let seed = *args.traffic.seed.get_or_insert_with(rand::random);
+    println!("Seed: {} (repeat this run with --seed {})", seed, seed);
+    let node = args.nodes.node.clone().unwrap_or_else(|| args.endpoints.urls(&args.rpc_url).join(","));
+    args.metrics.serve(&[("tool", "tx-generator"), ("node", &node), ("workload", &args.workload.workload)]).await?;
+    
+    // A replayed corpus brings its own transactions; otherwise they are signed from the accounts
+    let replay = match &args.replay_corpus {
+        Some(path) => {
+            let corpus = TxSource::load_corpus(path)?;
+            args.tx_count = corpus.len().unwrap_or(args.tx_count);
+            Some(corpus)
+        }
+        None => None,
+    };
+    
+    // Profiles and --open-loop both run on a fixed timetable; a profile sets the transaction count
+    let profile = if let Some(path) = &args.profile_file {
+        Some(Profile::load(path)?)
+    } else if let Some(spec) = &args.profile {
+        Some(Profile::parse(spec)?)
+    } else if args.open_loop {
+        Some(Profile::constant(args.target_tps, args.tx_count))
+    } else {
+        None
+    };
+    if let Some(profile) = profile.as_ref().filter(|_| !args.open_loop) {
+        if profile.tx_count() > args.tx_count && replay.is_some() {
+            return Err(AppError::Profile(format!(
+                "the profile sends {} transactions but the corpus only has {}",
+                profile.tx_count(), args.tx_count
+            )));
+        }
+        args.tx_count = profile.tx_count();
+        println!("Load profile: {} phases, {} transactions over {:.0?}",
+            profile.phases.len(), args.tx_count, profile.duration());
+    }
+    
+    let source = match replay {
+        Some(corpus) => corpus,
+        None => {
+            // Generate or load accounts
+            let (senders, receivers) = if let Some(accounts_file) = &args.accounts_file {
+                load_accounts(accounts_file)?
+            } else {
+                let (s, r) = generate_accounts(args.sender_count, args.receiver_count, key_seed).await?;
+                save_accounts(&s, &r, "accounts.json")?;
+                (s, r)
+            };
+            
+            // Generate genesis file if requested
+            if args.gen_genesis {
+                generate_genesis(&senders, "dev-genesis.json", "funded-genesis.json")?;
+                println!("To use this genesis, start RETH with: --genesis=funded-genesis.json");
+            }
+            
+            // Exit if we only needed to generate accounts
+            if args.gen_accounts {
+                println!("Account generation completed.");
+                return Ok(());
+            }
+            
+            // Token workloads deploy and seed their token once, before any run signs against it
+            let kinds = args.workload.kinds()?;
+            if kinds.iter().any(|kind| kind.is_token()) {
+                let provider = Arc::new(connect(&args.endpoints.urls(&args.rpc_url)[0]).await?);
+                let fees = args.fees(&provider).await?;
+                let approvals = kinds.contains(&Kind::Erc20TransferFrom);
+                let token = token::prepare(&args.workload, &provider, &senders, &fees, approvals).await?;
+                args.workload.token_address = Some(format!("{:?}", token));
+            }
+            
+            if args.search.find_max_tps {
+                let run = args.start_run("find-max-tps").await?;
+                let result = search::run(&args, &senders, &receivers, &run.recorder()).await;
+                return finish_run(run, &["tx_search.json"], &args.txpool, result);
+            }
+            if !args.compare.compare_endpoints.is_empty() {
+                let run = args.start_run("compare-endpoints").await?;
+                let result = compare::run(&args, profile, &senders, &receivers, &run.recorder()).await;
+                return finish_run(run, &["tx_transports.json"], &args.txpool, result);
+            }
+            
+            // Nonces come from the endpoint that also follows blocks
+            let provider = Arc::new(connect(&args.endpoints.urls(&args.rpc_url)[0]).await?);
+            let fees = args.fees(&provider).await?;
+            let workload = args.workload(&provider, &senders, receivers.len()).await?;
+            TxSource::live(&provider, &senders, &receivers, fees, workload, args.chain_id, args.start_nonce).await?
+        }
+    };
+    
+    if let Some(path) = &args.generate_corpus {
+        return source.write_corpus(path, args.tx_count);
+    }
+    
+    // Send transactions and measure performance
+    let run = args.start_run("send").await?;
+    let stats = match send_transactions(args.clone(), profile, source, run.recorder()).await {
+        Ok(stats) => stats,
+        Err(e) => return finish_run(run, &[], &args.txpool, Err(e)),
+    };
+    
+    // Print results
+    println!("\n=== Transaction Test Results ===");
+    println!("Transport: {}", stats.transport);
+    println!("Total transactions submitted: {}", stats.submitted);
+    println!("Transactions accepted by RPC: {}", stats.confirmed);
+    println!("Transactions failed: {}", stats.failed);
+    println!("Total time: {:.2?}", stats.total_time);
+    println!("Average transaction latency: {:.2?}", stats.avg_latency);
+    println!("Throughput: {:.2} TPS", stats.tps);
+    if args.use_batching && stats.rpc_batches > 0 {
+        println!("JSON-RPC batch requests: {} (avg {} bytes per request)",
+            stats.rpc_batches, stats.rpc_batch_bytes / stats.rpc_batches);
+    }
+    
+    if let Some(schedule) = &stats.schedule {
+        println!("Open-loop send lag: {} ({} of {} sends more than {:?} late)",
+            schedule.lag, schedule.late, schedule.sends, LATE_AFTER);
+    }
+    
+    if stats.endpoints.len() > 1 {
+        println!("\n=== Endpoints ===");
+        for endpoint in &stats.endpoints {
+            println!("{} ({}): {}", endpoint.url, endpoint.transport, endpoint);
+            if let Some(error) = &endpoint.last_error {
+                println!("    last error: {}", error);
+            }
+        }
+    }
+    
+    let inclusion = &stats.confirmation;
+    println!("\n=== Inclusion ===");
+    println!("Transactions included: {}/{} in {} blocks (followed by {})", inclusion.included, inclusion.tracked,
+        inclusion.blocks, if inclusion.head_subscription { "newHeads subscription" } else { "polling" });
+    println!("Never mined: {} ({} dropped, {} still pending)",
+        inclusion.dropped + inclusion.pending, inclusion.dropped, inclusion.pending);
+    println!("Submit-to-inclusion latency: {}", inclusion.latency);
+    println!("Inclusion throughput: {:.2} TPS", inclusion.inclusion_tps);
+    
+    if inclusion.kinds.iter().any(|kind| kind.kind != Kind::Transfer) {
+        println!("\n=== Workloads ===");
+        for kind in &inclusion.kinds {
+            let gas = match kind.avg_gas() {
+                Some(avg) => format!("avg gas {} ({} total)", avg, kind.gas_used),
+                None => "no receipts".to_string(),
+            };
+            println!("{}: {}/{} included, {} reverted, {}",
+                kind.kind, kind.included, kind.tracked, kind.reverted, gas);
+            println!("    inclusion latency {}", kind.latency);
+        }
+    }
+    
+    if inclusion.lanes.iter().any(|lane| lane.lane != Lane::Spread) {
+        println!("\n=== Contention ===");
+        for lane in &inclusion.lanes {
+            println!("{}: {}/{} included, {} rejected, over {} blocks (at most {} in one block)",
+                lane.lane, lane.included, lane.tracked, lane.rejected, lane.blocks, lane.max_per_block);
+            println!("    inclusion latency {}", lane.latency);
+        }
+    }
+    
+    if let Some(nonces) = &stats.nonces {
+        println!("\n=== Nonces ===");
+        println!("Rejected nonces re-issued: {}, filled: {}, still open: {}",
+            nonces.stats.reissued, nonces.stats.filled, nonces.stats.open_gaps);
+        println!("Senders with unmined nonces: {}", nonces.stuck.len());
+        for sender in nonces.stuck.iter().take(10) {
+            println!("    {:?}: mined up to nonce {}, issued up to {}, gaps {:?}",
+                sender.address, sender.mined, sender.issued, sender.gaps);
+        }
+    }
+    
+    if let Some(txpool) = &stats.txpool {
+        println!("\n=== Txpool ===");
+        println!("{}", txpool);
+    }
+    
+    if let Some(schedule) = stats.schedule.as_ref().filter(|schedule| schedule.phases.len() > 1) {
+        println!("\n=== Phases ===");
+        for phase in &schedule.phases {
+            println!("{}: {}/{} accepted ({:.2} TPS), {} failed, {} included",
+                phase.label, phase.accepted, phase.scheduled, phase.achieved_tps, phase.failed, phase.included);
+            println!("    send lag {}", phase.lag);
+            println!("    RPC latency {}", phase.rpc_latency);
+            println!("    inclusion latency {}", phase.inclusion);
+        }
+    }
+    
+    // Save statistics to file
+    let mut config = args.config();
+    config["transport"] = serde_json::json!(stats.transport);
+    let stats_json = serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "submitted": stats.submitted,
+        "confirmed": stats.confirmed,
+        "failed": stats.failed,
+        "total_time_ms": stats.total_time.as_millis(),
+        "avg_latency_ms": stats.avg_latency.as_millis(),
+        "tps": stats.tps,
+        "rpc_batches": stats.rpc_batches,
+        "rpc_batch_bytes": stats.rpc_batch_bytes,
+        "endpoints": stats.endpoints,
+        "inclusion": stats.confirmation.to_json(),
+        "schedule": stats.schedule.as_ref().map(ScheduleStats::to_json),
+        "nonces": stats.nonces.as_ref().map(|nonces| serde_json::json!({
+            "reissued": nonces.stats.reissued,
+            "filled": nonces.stats.filled,
+            "open_gaps": nonces.stats.open_gaps,
+            "stuck": nonces.stuck,
+        })),
+        "txpool": stats.txpool,
+        "config": config,
+    });
+    
+    let stats_file = File::create("tx_stats.json")?;
+    serde_json::to_writer_pretty(stats_file, &stats_json)?;
+    println!("Statistics saved to tx_stats.json");
+    
+    finish_run(run, &["tx_stats.json"], &args.txpool, Ok(()))
+}
\ No newline at end of file
@@ -0,0 +1,224 @@
+// Load profiles: ramps, staircases, spikes and duration-bounded soaks, expanded
+// into segments with a linear send rate that the open-loop schedule can follow
+
+use serde::Deserialize;
+use std::{fs, time::Duration};
+
+use crate::{AppError, Result};
+
+/// One phase as written in a profile file (`[[phase]]` tables) or on the command line.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)]
+pub enum PhaseSpec {
+    /// Linear ramp from `from` to `to` TPS over `secs` seconds
+    Ramp { from: u64, to: u64, secs: u64 },
+    /// Constant rate; a long hold is a soak run
+    Hold { tps: u64, secs: u64 },
+    /// Staircase from `from` to `to` TPS in `step` increments, holding each for `hold_secs`
+    Steps { from: u64, to: u64, step: u64, hold_secs: u64 },
+    /// `base` TPS with a `burst_secs` burst of `peak` TPS at the end of every `every_secs`
+    Spike { base: u64, peak: u64, secs: u64, every_secs: u64, burst_secs: u64 },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    #[serde(rename = "phase")]
+    phases: Vec<PhaseSpec>,
+}
+
+/// A stretch of the timetable whose rate changes linearly from `from_tps` to `to_tps`.
+#[derive(Debug, Clone)]
+pub struct Segment {
+    /// Index into `Profile::phases`
+    pub phase: usize,
+    pub from_tps: f64,
+    pub to_tps: f64,
+    pub secs: f64,
+    pub tx_count: usize,
+}
+
+/// Phases reported separately, and the segments that make them up.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub phases: Vec<String>,
+    pub segments: Vec<Segment>,
+}
+
+impl Profile {
+    /// Flat `tps` for `tx_count` transactions, as plain `--open-loop` runs.
+    pub fn constant(tps: usize, tx_count: usize) -> Self {
+        Profile {
+            phases: vec![format!("constant {} TPS", tps)],
+            segments: vec![Segment {
+                phase: 0,
+                from_tps: tps as f64,
+                to_tps: tps as f64,
+                secs: tx_count as f64 / tps as f64,
+                tx_count,
+            }],
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let file: ProfileFile = toml::from_str(&content)
+            .map_err(|e| AppError::Profile(format!("{}: {}", path, e)))?;
+        Self::from_phases(&file.phases)
+    }
+
+    /// Parses comma-separated phases, e.g. `ramp:100:1000:60,hold:1000:300`:
+    ///
+    /// - `ramp:FROM:TO:SECS`
+    /// - `hold:TPS:SECS`
+    /// - `steps:FROM:TO:STEP:HOLD_SECS`
+    /// - `spike:BASE:PEAK:SECS:EVERY_SECS:BURST_SECS`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let phases = spec.split(',')
+            .map(|phase| parse_phase(phase.trim()))
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_phases(&phases)
+    }
+
+    pub fn from_phases(specs: &[PhaseSpec]) -> Result<Self> {
+        if specs.is_empty() {
+            return Err(AppError::Profile("profile has no phases".to_string()));
+        }
+
+        let mut profile = Profile { phases: Vec::new(), segments: Vec::new() };
+        for spec in specs {
+            profile.push(spec)?;
+        }
+        Ok(profile)
+    }
+
+    /// Transactions sent over the whole profile.
+    pub fn tx_count(&self) -> usize {
+        self.segments.iter().map(|segment| segment.tx_count).sum()
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.segments.iter().map(|segment| segment.secs).sum())
+    }
+
+    fn push(&mut self, spec: &PhaseSpec) -> Result<()> {
+        match *spec {
+            PhaseSpec::Ramp { from, to, secs } => {
+                check_positive("ramp secs", secs)?;
+                let phase = self.phase(format!("ramp {}->{} TPS over {}s", from, to, secs));
+                self.segment(phase, from, to, secs as f64);
+            }
+            PhaseSpec::Hold { tps, secs } => {
+                check_positive("hold secs", secs)?;
+                let phase = self.phase(format!("hold {} TPS for {}s", tps, secs));
+                self.segment(phase, tps, tps, secs as f64);
+            }
+            PhaseSpec::Steps { from, to, step, hold_secs } => {
+                check_positive("steps step", step)?;
+                check_positive("steps hold_secs", hold_secs)?;
+                let mut tps = from;
+                loop {
+                    let phase = self.phase(format!("step {} TPS for {}s", tps, hold_secs));
+                    self.segment(phase, tps, tps, hold_secs as f64);
+                    if tps == to {
+                        break;
+                    }
+                    tps = if from < to {
+                        (tps + step).min(to)
+                    } else {
+                        tps.saturating_sub(step).max(to)
+                    };
+                }
+            }
+            PhaseSpec::Spike { base, peak, secs, every_secs, burst_secs } => {
+                check_positive("spike secs", secs)?;
+                check_positive("spike burst_secs", burst_secs)?;
+                if every_secs <= burst_secs {
+                    return Err(AppError::Profile(format!(
+                        "spike every_secs ({}) must be longer than burst_secs ({})",
+                        every_secs, burst_secs
+                    )));
+                }
+                let phase = self.phase(format!("spike {}/{} TPS for {}s", base, peak, secs));
+                let mut elapsed = 0;
+                while elapsed < secs {
+                    let quiet = (every_secs - burst_secs).min(secs - elapsed);
+                    self.segment(phase, base, base, quiet as f64);
+                    elapsed += quiet;
+                    let burst = burst_secs.min(secs - elapsed);
+                    if burst > 0 {
+                        self.segment(phase, peak, peak, burst as f64);
+                        elapsed += burst;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn phase(&mut self, label: String) -> usize {
+        self.phases.push(label);
+        self.phases.len() - 1
+    }
+
+    fn segment(&mut self, phase: usize, from_tps: u64, to_tps: u64, secs: f64) {
+        let (from_tps, to_tps) = (from_tps as f64, to_tps as f64);
+        self.segments.push(Segment {
+            phase,
+            from_tps,
+            to_tps,
+            secs,
+            tx_count: ((from_tps + to_tps) / 2.0 * secs).round() as usize,
+        });
+    }
+}
+
+impl Segment {
+    /// Seconds into the segment at which its `k`-th transaction is due, found by
+    /// inverting the number sent so far: `from * t + (to - from) * t^2 / (2 * secs)`.
+    pub fn offset_of(&self, k: usize) -> f64 {
+        let k = k as f64;
+        let a = (self.to_tps - self.from_tps) / (2.0 * self.secs);
+        let t = if a.abs() < f64::EPSILON {
+            k / self.from_tps
+        } else {
+            let discriminant = (self.from_tps * self.from_tps + 4.0 * a * k).max(0.0);
+            (discriminant.sqrt() - self.from_tps) / (2.0 * a)
+        };
+        t.clamp(0.0, self.secs)
+    }
+}
+
+fn parse_phase(spec: &str) -> Result<PhaseSpec> {
+    let mut parts = spec.split(':');
+    let kind = parts.next().unwrap_or_default();
+    let values = parts
+        .map(|value| value.parse::<u64>().map_err(|_| {
+            AppError::Profile(format!("{:?}: {:?} is not a whole number", spec, value))
+        }))
+        .collect::<Result<Vec<_>>>()?;
+
+    let phase = match (kind, values.as_slice()) {
+        ("ramp", &[from, to, secs]) => PhaseSpec::Ramp { from, to, secs },
+        ("hold", &[tps, secs]) => PhaseSpec::Hold { tps, secs },
+        ("steps", &[from, to, step, hold_secs]) => PhaseSpec::Steps { from, to, step, hold_secs },
+        ("spike", &[base, peak, secs, every_secs, burst_secs]) => {
+            PhaseSpec::Spike { base, peak, secs, every_secs, burst_secs }
+        }
+        _ => {
+            return Err(AppError::Profile(format!(
+                "{:?}: expected ramp:FROM:TO:SECS, hold:TPS:SECS, steps:FROM:TO:STEP:HOLD_SECS \
+                 or spike:BASE:PEAK:SECS:EVERY_SECS:BURST_SECS",
+                spec
+            )))
+        }
+    };
+    Ok(phase)
+}
+
+fn check_positive(field: &str, value: u64) -> Result<()> {
+    if value == 0 {
+        return Err(AppError::Profile(format!("{} must be above 0", field)));
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+// Open-loop send timetable: transactions are released on the profile's schedule no
+// matter how long earlier sends take, so slow responses cannot hide behind a lower send rate
+
+use serde_json::{json, Value};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time;
+
+use crate::confirmation::PhaseInclusion;
+use crate::latency::Percentiles;
+use crate::profile::Profile;
+use crate::traffic::Jitter;
+
+// Sends starting later than this after their slot count as late
+pub const LATE_AFTER: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Default)]
+struct PhaseRecord {
+    /// Actual minus intended send time, one sample per send
+    lags: Vec<Duration>,
+    rpc_latencies: Vec<Duration>,
+    accepted: usize,
+    failed: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    start: Instant,
+    profile: Arc<Profile>,
+    /// First transaction index and start offset in seconds of each segment
+    offsets: Arc<Vec<(usize, f64)>>,
+    jitter: Jitter,
+    records: Arc<Mutex<Vec<PhaseRecord>>>,
+}
+
+/// Results for one phase of the profile.
+#[derive(Debug, Default)]
+pub struct PhaseStats {
+    pub label: String,
+    pub secs: f64,
+    pub scheduled: usize,
+    pub accepted: usize,
+    pub failed: usize,
+    /// Accepted transactions over the phase's planned duration
+    pub achieved_tps: f64,
+    pub lag: Percentiles,
+    pub rpc_latency: Percentiles,
+    pub included: usize,
+    pub inclusion: Percentiles,
+}
+
+/// How closely the sends kept to the timetable, overall and per phase.
+#[derive(Debug, Default)]
+pub struct ScheduleStats {
+    pub sends: usize,
+    pub late: usize,
+    pub lag: Percentiles,
+    pub phases: Vec<PhaseStats>,
+}
+
+impl PhaseStats {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "label": self.label,
+            "secs": self.secs,
+            "scheduled": self.scheduled,
+            "accepted": self.accepted,
+            "failed": self.failed,
+            "achieved_tps": self.achieved_tps,
+            "lag_ms": self.lag.to_json(),
+            "rpc_latency_ms": self.rpc_latency.to_json(),
+            "included": self.included,
+            "inclusion_latency_ms": self.inclusion.to_json(),
+        })
+    }
+}
+
+impl ScheduleStats {
+    /// Fills in per-phase inclusion results once the confirmation tracker has finished.
+    pub fn add_inclusion(&mut self, inclusion: &[PhaseInclusion]) {
+        for (phase, included) in self.phases.iter_mut().zip(inclusion) {
+            phase.included = included.included;
+            phase.inclusion = included.latency;
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "sends": self.sends,
+            "late": self.late,
+            "lag_ms": self.lag.to_json(),
+            "phases": self.phases.iter().map(PhaseStats::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl Schedule {
+    pub fn new(profile: Profile, jitter: Jitter) -> Self {
+        let mut offsets = Vec::with_capacity(profile.segments.len());
+        let (mut first_tx, mut start) = (0, 0.0);
+        for segment in &profile.segments {
+            offsets.push((first_tx, start));
+            first_tx += segment.tx_count;
+            start += segment.secs;
+        }
+
+        let records = (0..profile.phases.len()).map(|_| PhaseRecord::default()).collect();
+        Schedule {
+            start: Instant::now(),
+            profile: Arc::new(profile),
+            offsets: Arc::new(offsets),
+            jitter,
+            records: Arc::new(Mutex::new(records)),
+        }
+    }
+
+    // Last segment starting at or before `tx_idx`; empty segments are skipped over
+    fn segment_of(&self, tx_idx: usize) -> usize {
+        self.offsets.partition_point(|(first_tx, _)| *first_tx <= tx_idx).saturating_sub(1)
+    }
+
+    pub fn phase_of(&self, tx_idx: usize) -> usize {
+        self.profile.segments[self.segment_of(tx_idx)].phase
+    }
+
+    /// Intended send time of the `tx_idx`-th transaction, moved by its jitter.
+    pub fn due(&self, tx_idx: usize) -> Instant {
+        let index = self.segment_of(tx_idx);
+        let (first_tx, start) = self.offsets[index];
+        let segment = &self.profile.segments[index];
+        let k = tx_idx - first_tx;
+        let gap = segment.offset_of(k + 1) - segment.offset_of(k);
+        let offset = start + segment.offset_of(k) + self.jitter.offset(tx_idx, gap);
+        self.start + Duration::from_secs_f64(offset.max(0.0))
+    }
+
+    /// Sleeps until the `tx_idx`-th transaction is due and returns its intended send time.
+    pub async fn wait(&self, tx_idx: usize) -> Instant {
+        let due = self.due(tx_idx);
+        time::sleep_until(due.into()).await;
+        due
+    }
+
+    /// Records that the send starting at `tx_idx`, intended for `due`, is starting now.
+    pub fn record_send(&self, tx_idx: usize, due: Instant) {
+        let lag = Instant::now().saturating_duration_since(due);
+        let phase = self.phase_of(tx_idx);
+        self.records.lock().unwrap()[phase].lags.push(lag);
+    }
+
+    /// Records the RPC outcome of one transaction, timed from its intended send time.
+    pub fn record_result(&self, tx_idx: usize, accepted: bool, latency: Duration) {
+        let phase = self.phase_of(tx_idx);
+        let mut records = self.records.lock().unwrap();
+        let record = &mut records[phase];
+        if accepted {
+            record.accepted += 1;
+        } else {
+            record.failed += 1;
+        }
+        record.rpc_latencies.push(latency);
+    }
+
+    pub fn stats(&self) -> ScheduleStats {
+        let records = std::mem::take(&mut *self.records.lock().unwrap());
+        let all_lags: Vec<Duration> = records.iter()
+            .flat_map(|record| record.lags.iter().copied())
+            .collect();
+
+        let phases = records.into_iter().enumerate().map(|(phase, record)| {
+            let segments = self.profile.segments.iter().filter(|segment| segment.phase == phase);
+            let (secs, scheduled) = segments.fold((0.0, 0), |(secs, txs), segment| {
+                (secs + segment.secs, txs + segment.tx_count)
+            });
+            PhaseStats {
+                label: self.profile.phases[phase].clone(),
+                secs,
+                scheduled,
+                accepted: record.accepted,
+                failed: record.failed,
+                achieved_tps: if secs > 0.0 { record.accepted as f64 / secs } else { 0.0 },
+                lag: Percentiles::from_samples(record.lags),
+                rpc_latency: Percentiles::from_samples(record.rpc_latencies),
+                ..PhaseStats::default()
+            }
+        }).collect();
+
+        ScheduleStats {
+            sends: all_lags.len(),
+            late: all_lags.iter().filter(|lag| **lag > LATE_AFTER).count(),
+            lag: Percentiles::from_samples(all_lags),
+            phases,
+        }
+    }
+}
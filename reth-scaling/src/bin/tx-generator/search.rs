@@ -0,0 +1,266 @@
+// Binary search for the highest constant rate the node sustains: each trial holds one
+// rate open-loop and passes only if every configured limit held
+
+use bridge_core::{connect, Account, Recorder, RpcProvider};
+use ethers::providers::Middleware;
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time;
+
+use crate::profile::Profile;
+use crate::source::TxSource;
+use crate::{send_transactions, AppError, Args, Result, TxStats};
+
+// How often the txpool is sampled during a trial
+const POOL_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct SearchArgs {
+    /// Search for the highest sustainable --target-tps instead of running one test
+    #[clap(long, conflicts_with_all = ["profile", "profile_file", "open_loop", "replay_corpus", "generate_corpus"])]
+    pub find_max_tps: bool,
+
+    /// Lowest rate tried; the search stops if even this rate breaks a limit
+    #[clap(long, default_value_t = 100)]
+    pub search_min_tps: usize,
+
+    /// Highest rate tried
+    #[clap(long, default_value_t = 5000)]
+    pub search_max_tps: usize,
+
+    /// Stop once the sustainable rate is known to within this many TPS
+    #[clap(long, default_value_t = 50)]
+    pub search_resolution: usize,
+
+    /// Length of each trial
+    #[clap(long, default_value_t = 30)]
+    pub trial_secs: u64,
+
+    /// Pause between trials so the node can settle
+    #[clap(long, default_value_t = 5)]
+    pub search_cooldown_secs: u64,
+
+    /// Limit: most the txpool's pending count may grow during a trial
+    #[clap(long, default_value_t = 1000)]
+    pub max_pool_growth: u64,
+
+    /// Limit: highest acceptable p99 submit-to-inclusion latency
+    #[clap(long, default_value_t = 5000)]
+    pub max_p99_inclusion_ms: u64,
+
+    /// Limit: highest acceptable fraction of transactions rejected by the RPC or never mined
+    #[clap(long, default_value_t = 0.01)]
+    pub max_error_rate: f64,
+}
+
+/// A limit a trial broke, with the measured value.
+#[derive(Debug, Clone)]
+enum Breach {
+    ErrorRate(f64),
+    P99Inclusion(Duration),
+    PoolGrowth(u64),
+}
+
+impl std::fmt::Display for Breach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Breach::ErrorRate(rate) => write!(f, "error rate {:.2}%", rate * 100.0),
+            Breach::P99Inclusion(p99) => write!(f, "p99 inclusion latency {:.2?}", p99),
+            Breach::PoolGrowth(growth) => write!(f, "txpool pending grew by {}", growth),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Trial {
+    tps: usize,
+    stats: TxStats,
+    /// None when the node does not serve txpool_status
+    pool_growth: Option<u64>,
+    breaches: Vec<Breach>,
+}
+
+impl Trial {
+    fn passed(&self) -> bool {
+        self.breaches.is_empty()
+    }
+
+    fn to_json(&self) -> Value {
+        let inclusion = &self.stats.confirmation;
+        json!({
+            "tps": self.tps,
+            "passed": self.passed(),
+            "accepted": self.stats.confirmed,
+            "failed": self.stats.failed,
+            "included": inclusion.included,
+            "not_included": inclusion.dropped + inclusion.pending,
+            "p99_inclusion_ms": inclusion.latency.p99.as_millis(),
+            "pool_growth": self.pool_growth,
+            "breaches": self.breaches.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        })
+    }
+}
+
+pub async fn run(args: &Args, senders: &[Account], receivers: &[Account], recorder: &Recorder) -> Result<()> {
+    let search = &args.search;
+    if search.search_min_tps == 0 || search.search_min_tps > search.search_max_tps {
+        return Err(AppError::Other(
+            "--search-min-tps must be above 0 and no higher than --search-max-tps".to_string(),
+        ));
+    }
+
+    let provider = Arc::new(connect(&args.endpoints.urls(&args.rpc_url)[0]).await?);
+
+    // Check both ends first, then bisect between the last passing and first failing rate
+    let mut trials: Vec<Trial> = Vec::new();
+    let mut best = None;
+    let (mut low, mut high) = (search.search_min_tps, search.search_max_tps);
+    let mut next = Some(low);
+
+    while let Some(tps) = next {
+        if !trials.is_empty() {
+            time::sleep(Duration::from_secs(search.search_cooldown_secs)).await;
+        }
+
+        println!("\n=== Trial at {} TPS for {}s ===", tps, search.trial_secs);
+        let recorder = recorder.trial(trials.len() as u32 + 1);
+        let trial = run_trial(args, &provider, tps, senders, receivers, recorder).await?;
+        if trial.passed() {
+            println!("Trial at {} TPS passed", tps);
+            best = Some(tps);
+            low = tps;
+        } else {
+            let breaches: Vec<String> = trial.breaches.iter().map(ToString::to_string).collect();
+            println!("Trial at {} TPS failed: {}", tps, breaches.join(", "));
+            high = tps;
+        }
+        let passed = trial.passed();
+        trials.push(trial);
+
+        next = if !passed && best.is_none() {
+            // Even the minimum rate is too much
+            None
+        } else if trials.len() == 1 && high > low {
+            Some(high)
+        } else if high - low > search.search_resolution {
+            Some(low + (high - low) / 2)
+        } else {
+            None
+        };
+    }
+
+    // The limit that broke at the lowest failing rate is the one holding the node back
+    let first_failure = trials.iter()
+        .filter(|trial| !trial.passed())
+        .min_by_key(|trial| trial.tps);
+
+    println!("\n=== Max TPS search ===");
+    for trial in &trials {
+        println!("{:>6} TPS  {}", trial.tps, if trial.passed() { "pass" } else { "FAIL" });
+    }
+    match best {
+        Some(tps) => println!("Sustainable rate: {} TPS", tps),
+        None => println!("Sustainable rate: below {} TPS", search.search_min_tps),
+    }
+    match first_failure {
+        Some(trial) => println!("First limit broken at {} TPS: {}", trial.tps, trial.breaches[0]),
+        None => println!("No limit broken up to {} TPS", search.search_max_tps),
+    }
+
+    let report = json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "sustainable_tps": best,
+        "first_breach": first_failure.map(|trial| json!({
+            "tps": trial.tps,
+            "limit": trial.breaches[0].to_string(),
+        })),
+        "limits": {
+            "max_pool_growth": search.max_pool_growth,
+            "max_p99_inclusion_ms": search.max_p99_inclusion_ms,
+            "max_error_rate": search.max_error_rate,
+        },
+        "trial_secs": search.trial_secs,
+        "trials": trials.iter().map(Trial::to_json).collect::<Vec<_>>(),
+    });
+    let report_file = File::create("tx_search.json")?;
+    serde_json::to_writer_pretty(report_file, &report)?;
+    println!("Search report saved to tx_search.json");
+
+    Ok(())
+}
+
+async fn run_trial(
+    args: &Args,
+    provider: &Arc<RpcProvider>,
+    tps: usize,
+    senders: &[Account],
+    receivers: &[Account],
+    recorder: Recorder,
+) -> Result<Trial> {
+    let search = &args.search;
+    let profile = Profile::constant(tps, tps * search.trial_secs as usize);
+
+    let mut trial_args = args.clone();
+    trial_args.tx_count = profile.tx_count();
+    trial_args.target_tps = tps;
+
+    // Sample the pool while the trial runs and keep the peak
+    let baseline = pool_pending(provider).await;
+    let sampling = Arc::new(AtomicBool::new(true));
+    let sampler = tokio::spawn(sample_pool_peak(provider.clone(), sampling.clone()));
+
+    // Fees are re-resolved per trial so feeHistory-derived fees follow the base fee
+    let fees = args.fees(provider).await?;
+    let workload = args.workload(provider, senders, receivers.len()).await?;
+    let source = TxSource::live(provider, senders, receivers, fees, workload, args.chain_id, None).await?;
+    let stats = send_transactions(trial_args, Some(profile), source, recorder).await;
+
+    sampling.store(false, Ordering::SeqCst);
+    let peak = sampler.await.map_err(|e| AppError::Other(e.to_string()))?;
+    let stats = stats?;
+
+    let pool_growth = baseline.zip(peak).map(|(baseline, peak)| peak.saturating_sub(baseline));
+    if pool_growth.is_none() {
+        eprintln!("txpool_status unavailable, skipping the pool growth limit");
+    }
+
+    let inclusion = &stats.confirmation;
+    let scheduled = stats.submitted.max(1);
+    let errors = stats.failed + inclusion.dropped + inclusion.pending;
+
+    let mut breaches = Vec::new();
+    let error_rate = errors as f64 / scheduled as f64;
+    if error_rate > search.max_error_rate {
+        breaches.push(Breach::ErrorRate(error_rate));
+    }
+    if inclusion.latency.p99 > Duration::from_millis(search.max_p99_inclusion_ms) {
+        breaches.push(Breach::P99Inclusion(inclusion.latency.p99));
+    }
+    if let Some(growth) = pool_growth.filter(|growth| *growth > search.max_pool_growth) {
+        breaches.push(Breach::PoolGrowth(growth));
+    }
+
+    Ok(Trial { tps, stats, pool_growth, breaches })
+}
+
+async fn pool_pending(provider: &RpcProvider) -> Option<u64> {
+    provider.txpool_status().await.ok().map(|status| status.pending.as_u64())
+}
+
+async fn sample_pool_peak(provider: Arc<RpcProvider>, sampling: Arc<AtomicBool>) -> Option<u64> {
+    let mut peak = None;
+    while sampling.load(Ordering::SeqCst) {
+        if let Some(pending) = pool_pending(&provider).await {
+            peak = Some(peak.map_or(pending, |peak: u64| peak.max(pending)));
+        }
+        time::sleep(POOL_SAMPLE_INTERVAL).await;
+    }
+    peak
+}
@@ -0,0 +1,291 @@
+// Where the transactions of a run come from: signed on the fly from the loaded
+// accounts, or read back from a pre-signed corpus file
+
+use bridge_core::{Account, Fees, NonceManager, NonceStats, RpcProvider, StuckSender};
+use ethers::{
+    providers::Middleware,
+    signers::{LocalWallet, Signer},
+    types::{Address, Bytes, TransactionRequest, H256, U256},
+    utils::keccak256,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::traffic::Lane;
+use crate::workload::{Kind, Workload};
+use crate::{AppError, Result};
+
+// Bumped whenever the corpus line format changes
+const CORPUS_VERSION: u32 = 1;
+
+/// A signed transaction ready for `eth_sendRawTransaction`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTx {
+    pub from: Address,
+    pub nonce: u64,
+    pub hash: H256,
+    pub raw: Bytes,
+    /// Older corpora only hold transfers
+    #[serde(default)]
+    pub kind: Kind,
+    /// Older corpora only hold spread traffic
+    #[serde(default)]
+    pub lane: Lane,
+    /// Recipient and value, for the run's transaction records; older corpora have neither
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+}
+
+/// First line of a corpus file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CorpusHeader {
+    pub version: u32,
+    pub chain_id: u64,
+    pub count: usize,
+    /// Older corpora only carry `gas_price` and load as legacy
+    #[serde(flatten)]
+    pub fees: Fees,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workload: Option<String>,
+    pub created: String,
+}
+
+/// How rejected nonces of a live run were repaired, and the senders left stuck.
+#[derive(Debug)]
+pub struct NonceReport {
+    pub stats: NonceStats,
+    pub stuck: Vec<StuckSender>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TxSource {
+    /// The workload decides who sends transaction `i` to whom (round-robin by default,
+    /// so sender `i % senders`) and what it does. Nonces come from the nonce manager:
+    /// the sender's next one unless a rejected nonce is re-issued.
+    Live {
+        chain_id: u64,
+        wallets: Arc<Vec<LocalWallet>>,
+        receivers: Arc<Vec<Address>>,
+        nonces: Arc<NonceManager<RpcProvider>>,
+        fees: Arc<Fees>,
+        workload: Arc<Workload>,
+    },
+    Corpus {
+        chain_id: u64,
+        txs: Arc<Vec<SignedTx>>,
+    },
+}
+
+impl TxSource {
+    /// Signs from `senders` to `receivers`. The chain ID and starting nonces come from the
+    /// node unless given, so a corpus can be generated without one.
+    pub async fn live(
+        provider: &Arc<RpcProvider>,
+        senders: &[Account],
+        receivers: &[Account],
+        fees: Fees,
+        workload: Workload,
+        chain_id: Option<u64>,
+        start_nonce: Option<u64>,
+    ) -> Result<Self> {
+        let chain_id = match chain_id {
+            Some(chain_id) => chain_id,
+            None => provider.get_chainid().await
+                .map_err(|e| AppError::Provider(format!("Failed to get chain ID: {}", e)))?
+                .as_u64(),
+        };
+        println!("Signing for chain ID: {}", chain_id);
+
+        let wallets = senders.iter()
+            .map(|sender| sender.wallet(chain_id))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let receivers = receivers.iter()
+            .map(|receiver| receiver.address())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        if wallets.is_empty() || receivers.is_empty() {
+            return Err(AppError::Other("At least one sender and one receiver are needed".to_string()));
+        }
+
+        // Start from the pending nonce so transactions still in the pool from an
+        // earlier run or search trial are not reused
+        let nonces = NonceManager::new(provider.clone());
+        for wallet in &wallets {
+            match start_nonce {
+                Some(nonce) => nonces.track_from(wallet.address(), nonce),
+                None => {
+                    nonces.track(wallet.address()).await?;
+                }
+            }
+        }
+
+        Ok(TxSource::Live {
+            chain_id,
+            wallets: Arc::new(wallets),
+            receivers: Arc::new(receivers),
+            nonces: Arc::new(nonces),
+            fees: Arc::new(fees),
+            workload: Arc::new(workload),
+        })
+    }
+
+    /// Reads a corpus written by `write_corpus`.
+    pub fn load_corpus(path: &str) -> Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header: CorpusHeader = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)?,
+            None => return Err(AppError::Other(format!("{} is empty", path))),
+        };
+        if header.version != CORPUS_VERSION {
+            return Err(AppError::Other(format!(
+                "{} is corpus version {}, expected {}",
+                path, header.version, CORPUS_VERSION
+            )));
+        }
+
+        let mut txs = Vec::with_capacity(header.count);
+        for line in lines {
+            txs.push(serde_json::from_str(&line?)?);
+        }
+        if txs.len() != header.count {
+            return Err(AppError::Other(format!(
+                "{} has {} transactions, header says {}",
+                path, txs.len(), header.count
+            )));
+        }
+
+        println!("Loaded {} pre-signed transactions for chain ID {} from {} ({}; {})",
+            txs.len(), header.chain_id, path, header.fees, header.workload.as_deref().unwrap_or("transfer"));
+        Ok(TxSource::Corpus { chain_id: header.chain_id, txs: Arc::new(txs) })
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            TxSource::Live { chain_id, .. } | TxSource::Corpus { chain_id, .. } => *chain_id,
+        }
+    }
+
+    /// Transactions available, if limited.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            TxSource::Live { .. } => None,
+            TxSource::Corpus { txs, .. } => Some(txs.len()),
+        }
+    }
+
+    /// Whether the run has transactions other than plain transfers, whose gas used is only
+    /// known from their receipts.
+    pub fn needs_receipts(&self) -> bool {
+        match self {
+            TxSource::Live { workload, .. } => !workload.is_plain(),
+            TxSource::Corpus { txs, .. } => txs.iter().any(|tx| tx.kind != Kind::Transfer),
+        }
+    }
+
+    /// Index of the account sending transaction `tx_idx`, for pinning senders to endpoints.
+    pub fn sender_index(&self, tx_idx: usize) -> usize {
+        match self {
+            TxSource::Live { workload, .. } => workload.sender_of(tx_idx),
+            // Derived from the address so every transaction of a sender maps to the same index
+            TxSource::Corpus { txs, .. } => txs[tx_idx].from.to_low_u64_be() as usize,
+        }
+    }
+
+    /// The `tx_idx`-th transaction of the run.
+    pub fn get(&self, tx_idx: usize) -> Result<SignedTx> {
+        match self {
+            TxSource::Live { wallets, receivers, nonces, fees, workload, .. } => {
+                let sender_idx = workload.sender_of(tx_idx);
+                let wallet = &wallets[sender_idx];
+                let nonce = nonces.next(wallet.address())?;
+                let to = receivers[workload.receiver_of(tx_idx)];
+                let kind = workload.kind_of(tx_idx);
+                let request = workload.request(kind, tx_idx, sender_idx, to).nonce(nonce);
+                let (to, value) = (request.to.as_ref().and_then(|to| to.as_address()).copied(), request.value);
+                let (raw, hash) = sign(wallet, request, fees)?;
+                Ok(SignedTx { from: wallet.address(), nonce, hash, raw, kind, lane: workload.lane_of(tx_idx), to, value })
+            }
+            TxSource::Corpus { txs, .. } => txs.get(tx_idx).cloned().ok_or_else(|| {
+                AppError::Other(format!("Corpus has no transaction {}", tx_idx))
+            }),
+        }
+    }
+
+    /// Records a rejected submission so a live source can re-issue its nonce.
+    pub fn rejected(&self, tx: &SignedTx, reason: &str) {
+        if let TxSource::Live { nonces, .. } = self {
+            nonces.rejected(tx.from, tx.nonce, reason);
+        }
+    }
+
+    /// Fills the nonce gaps left by rejected transactions that were never re-issued,
+    /// so the transactions queued behind them can be mined.
+    pub async fn repair_nonces(&self) -> Result<usize> {
+        match self {
+            TxSource::Live { nonces, wallets, fees, .. } => Ok(nonces.fill_gaps(wallets, fees).await?),
+            TxSource::Corpus { .. } => Ok(0),
+        }
+    }
+
+    /// Nonce repair counts and the senders whose mined nonce is still behind; live sources only.
+    pub async fn nonce_report(&self) -> Result<Option<NonceReport>> {
+        match self {
+            TxSource::Live { nonces, .. } => Ok(Some(NonceReport {
+                stats: nonces.stats(),
+                stuck: nonces.stuck(Duration::ZERO).await?,
+            })),
+            TxSource::Corpus { .. } => Ok(None),
+        }
+    }
+
+    /// Signs `count` transactions into a JSONL corpus: a header line, then one
+    /// transaction per line.
+    pub fn write_corpus(&self, path: &str, count: usize) -> Result<()> {
+        let (fees, workload) = match self {
+            TxSource::Live { fees, workload, .. } => (Fees::clone(fees), workload.to_string()),
+            TxSource::Corpus { .. } => {
+                return Err(AppError::Other("A corpus can only be generated from accounts".to_string()))
+            }
+        };
+
+        let mut out = BufWriter::new(File::create(path)?);
+        let header = CorpusHeader {
+            version: CORPUS_VERSION,
+            chain_id: self.chain_id(),
+            count,
+            fees,
+            workload: Some(workload),
+            created: chrono::Utc::now().to_rfc3339(),
+        };
+        serde_json::to_writer(&mut out, &header)?;
+        writeln!(out)?;
+
+        for tx_idx in 0..count {
+            serde_json::to_writer(&mut out, &self.get(tx_idx)?)?;
+            writeln!(out)?;
+            if tx_idx % 10000 == 0 && tx_idx > 0 {
+                println!("Signed {}/{} transactions", tx_idx, count);
+            }
+        }
+        out.flush()?;
+
+        println!("Corpus of {} transactions saved to {}", count, path);
+        Ok(())
+    }
+}
+
+// Sign a transaction locally, returning the raw transaction and its hash
+fn sign(wallet: &LocalWallet, request: TransactionRequest, fees: &Fees) -> Result<(Bytes, H256)> {
+    let tx = fees.typed(request.chain_id(wallet.chain_id()));
+    let signature = wallet.sign_transaction_sync(&tx)
+        .map_err(|e| AppError::Other(format!("Failed to sign transaction: {}", e)))?;
+    let raw = tx.rlp_signed(&signature);
+    let hash = H256::from(keccak256(&raw));
+    Ok((raw, hash))
+}
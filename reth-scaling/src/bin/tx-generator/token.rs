@@ -0,0 +1,196 @@
+// ERC-20 workloads: deploys LoadToken from its forge artifact (or takes an existing
+// token), mints the senders a balance and approves the transferFrom spenders
+
+use bridge_core::{Account, Fees, RpcProvider};
+use ethers::{
+    abi::{encode, AbiEncode, Token},
+    contract::abigen,
+    middleware::SignerMiddleware,
+    providers::Middleware,
+    signers::{LocalWallet, Signer},
+    types::{Address, Bytes, TransactionReceipt, TransactionRequest, U256},
+    utils::format_ether,
+};
+use futures::future::join_all;
+use std::{fs, sync::Arc, time::Duration};
+
+use crate::workload::WorkloadArgs;
+use crate::{AppError, Result};
+
+abigen!(
+    LoadToken,
+    r#"[
+        function balanceOf(address account) external view returns (uint256)
+        function allowance(address owner, address spender) external view returns (uint256)
+        function transfer(address to, uint256 value) external returns (bool)
+        function transferFrom(address from, address to, uint256 value) external returns (bool)
+        function approve(address spender, uint256 value) external returns (bool)
+        function mintBatch(address[] recipients, uint256 amount) external
+    ]"#
+);
+
+// Recipients per mintBatch transaction
+const MINT_BATCH_SIZE: usize = 200;
+
+// Balance and allowance reads, and approvals, in flight at once
+const SETUP_CONCURRENCY: usize = 100;
+
+/// Spender that sender `owner` approves for transferFrom traffic: the next sender, so
+/// sender `i` pulls tokens from sender `i - 1`.
+pub fn spender_of(owner: usize, senders: usize) -> usize {
+    (owner + 1) % senders
+}
+
+/// Deploys LoadToken unless --token-address names a token, then tops up every sender
+/// to --token-seed tokens and, for transferFrom traffic, approves each sender's spender.
+/// Deployment and minting are sent by the first sender.
+pub async fn prepare(
+    args: &WorkloadArgs,
+    provider: &Arc<RpcProvider>,
+    senders: &[Account],
+    fees: &Fees,
+    approvals: bool,
+) -> Result<Address> {
+    let chain_id = provider.get_chainid().await
+        .map_err(|e| AppError::Provider(format!("Failed to get chain ID: {}", e)))?
+        .as_u64();
+    let wallets = senders.iter()
+        .map(|sender| sender.wallet(chain_id))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let deployer = wallets.first()
+        .ok_or_else(|| AppError::Other("At least one sender is needed".to_string()))?;
+
+    let token = match &args.token_address {
+        Some(address) => bridge_core::parse_address(address)?,
+        None => deploy(&args.token_artifact, provider, deployer, fees).await?,
+    };
+    println!("Token: {:?}", token);
+
+    if args.token_seed > 0 {
+        let amount = U256::exp10(18) * args.token_seed;
+        seed(token, provider, deployer, &wallets, amount, fees).await?;
+    }
+    if approvals {
+        approve(token, provider, &wallets, fees).await?;
+    }
+    Ok(token)
+}
+
+async fn deploy(artifact: &str, provider: &Arc<RpcProvider>, deployer: &LocalWallet, fees: &Fees) -> Result<Address> {
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(artifact).map_err(|e| {
+        AppError::Other(format!(
+            "Failed to read token artifact {} ({}); run `FOUNDRY_PROFILE=token forge build` in reth-contract or pass --token-address",
+            artifact, e
+        ))
+    })?)?;
+    let bytecode = json["bytecode"]["object"].as_str()
+        .and_then(|object| hex::decode(object.trim_start_matches("0x")).ok())
+        .ok_or_else(|| AppError::Other(format!("{} has no bytecode.object", artifact)))?;
+
+    let mut data = bytecode;
+    data.extend(encode(&[Token::String("Load Token".to_string()), Token::String("LOAD".to_string())]));
+    let client = SignerMiddleware::new(provider.clone(), deployer.clone());
+    let receipt = send_and_wait(&client, TransactionRequest::new().data(data), fees, "token deployment").await?;
+    receipt.contract_address
+        .ok_or_else(|| AppError::Other("Token deployment receipt has no contract address".to_string()))
+}
+
+// Mints to the senders holding less than `amount`
+async fn seed(
+    token: Address,
+    provider: &Arc<RpcProvider>,
+    minter: &LocalWallet,
+    wallets: &[LocalWallet],
+    amount: U256,
+    fees: &Fees,
+) -> Result<()> {
+    let contract = LoadToken::new(token, provider.clone());
+    let mut short = Vec::new();
+    for chunk in wallets.chunks(SETUP_CONCURRENCY) {
+        let reads = chunk.iter().map(|wallet| {
+            let call = contract.balance_of(wallet.address());
+            async move { call.call().await }
+        });
+        let balances = join_all(reads).await;
+        for (wallet, balance) in chunk.iter().zip(balances) {
+            let balance = balance.map_err(|e| AppError::Provider(format!("Failed to read token balance: {}", e)))?;
+            if balance < amount {
+                short.push(wallet.address());
+            }
+        }
+    }
+    if short.is_empty() {
+        println!("All {} senders hold at least {} tokens", wallets.len(), format_ether(amount));
+        return Ok(());
+    }
+
+    let client = SignerMiddleware::new(provider.clone(), minter.clone());
+    for (i, recipients) in short.chunks(MINT_BATCH_SIZE).enumerate() {
+        let call = MintBatchCall { recipients: recipients.to_vec(), amount };
+        let request = TransactionRequest::new().to(token).data(call.encode());
+        send_and_wait(&client, request, fees, "token mint").await?;
+        println!("Minted {} tokens to {}/{} senders", format_ether(amount),
+            i * MINT_BATCH_SIZE + recipients.len(), short.len());
+    }
+    Ok(())
+}
+
+// Each sender approves its spender for an unlimited amount, unless it already has
+async fn approve(token: Address, provider: &Arc<RpcProvider>, wallets: &[LocalWallet], fees: &Fees) -> Result<()> {
+    let contract = LoadToken::new(token, provider.clone());
+    let mut approved = 0;
+    for (chunk_idx, chunk) in wallets.chunks(SETUP_CONCURRENCY).enumerate() {
+        let approvals = chunk.iter().enumerate().map(|(i, owner)| {
+            let spender = wallets[spender_of(chunk_idx * SETUP_CONCURRENCY + i, wallets.len())].address();
+            let contract = &contract;
+            async move {
+                let allowance = contract.allowance(owner.address(), spender).call().await
+                    .map_err(|e| AppError::Provider(format!("Failed to read allowance: {}", e)))?;
+                if allowance >= U256::MAX / 2 {
+                    return Ok(false);
+                }
+                let client = SignerMiddleware::new(provider.clone(), owner.clone());
+                let call = ApproveCall { spender, value: U256::MAX };
+                let request = TransactionRequest::new().to(token).data(call.encode());
+                send_and_wait(&client, request, fees, "token approval").await.map(|_| true)
+            }
+        });
+        for result in join_all(approvals).await {
+            if result? {
+                approved += 1;
+            }
+        }
+    }
+    println!("Approved {} transferFrom spenders ({} already approved)", approved, wallets.len() - approved);
+    Ok(())
+}
+
+// Sends a setup transaction and waits for it to be mined successfully
+async fn send_and_wait(
+    client: &SignerMiddleware<Arc<RpcProvider>, LocalWallet>,
+    request: TransactionRequest,
+    fees: &Fees,
+    what: &str,
+) -> Result<TransactionReceipt> {
+    let request = request.from(client.address()).chain_id(client.signer().chain_id());
+    let receipt = client.send_transaction(fees.typed(request), None).await
+        .map_err(|e| AppError::Provider(format!("Failed to send {}: {}", what, e)))?
+        .interval(Duration::from_millis(500))
+        .await
+        .map_err(|e| AppError::Provider(format!("Failed to confirm {}: {}", what, e)))?
+        .ok_or_else(|| AppError::Other(format!("{} was dropped", what)))?;
+    if receipt.status.is_some_and(|status| status.is_zero()) {
+        return Err(AppError::Other(format!("{} reverted (tx {:?})", what, receipt.transaction_hash)));
+    }
+    Ok(receipt)
+}
+
+/// Calldata of a token transfer.
+pub fn transfer_data(to: Address, value: U256) -> Bytes {
+    TransferCall { to, value }.encode().into()
+}
+
+/// Calldata of a token transferFrom.
+pub fn transfer_from_data(from: Address, to: Address, value: U256) -> Bytes {
+    TransferFromCall { from, to, value }.encode().into()
+}
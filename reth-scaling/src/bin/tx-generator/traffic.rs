@@ -0,0 +1,345 @@
+// Seeded choices for every transaction of a run: its sender and receiver, whether it
+// joins the hot sender or hot receivers, amount, payload size and send-time jitter.
+// Each is a pure function of --seed and the transaction index, so concurrent tasks,
+// corpora and reruns against another reth build all see the same traffic
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::{AppError, Result};
+
+/// Independent draws for the different choices made for one transaction.
+#[derive(Debug, Clone, Copy)]
+pub enum Stream {
+    Sender = 1,
+    Receiver = 2,
+    Amount = 3,
+    Payload = 4,
+    Jitter = 5,
+    Lane = 6,
+}
+
+/// Uniform in `[0, 1)` for choice `stream` of transaction `tx_idx`; the same inputs
+/// always give the same value.
+pub fn draw(seed: u64, tx_idx: usize, stream: Stream) -> f64 {
+    let x = mix(seed ^ mix(((tx_idx as u64) << 8) | stream as u64));
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// splitmix64 finalizer
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct TrafficArgs {
+    /// Seed for generated keys, sender and receiver choice, amounts, payload sizes and
+    /// jitter; a random one is picked and printed when not given
+    #[clap(long)]
+    pub seed: Option<u64>,
+
+    /// How senders are picked: round-robin, uniform, zipf[:EXPONENT] (sender 0 hottest)
+    /// or one[:INDEX] (every transaction from one sender)
+    #[clap(long, default_value = "round-robin")]
+    pub sender_dist: Distribution,
+
+    /// How receivers are picked, as --sender-dist; one[:INDEX] makes every transfer
+    /// contend for one account
+    #[clap(long, default_value = "round-robin")]
+    pub receiver_dist: Distribution,
+
+    /// Move each open-loop send by up to this fraction of the gap to its neighbours
+    /// (0 to 1), so arrivals are not perfectly even
+    #[clap(long, default_value_t = 0.0)]
+    pub jitter: f64,
+
+    /// Share of transactions (0 to 1) sent by sender 0 alone, which builds a deep nonce
+    /// queue; the other transactions then come from the remaining senders
+    #[clap(long, default_value_t = 0.0)]
+    pub hot_sender_share: f64,
+
+    /// Share of transactions (0 to 1) sent to the --hot-receivers; the other transactions
+    /// then go to the remaining receivers
+    #[clap(long, default_value_t = 0.0)]
+    pub hot_receiver_share: f64,
+
+    /// Number of hot receivers, the first receivers of the accounts file
+    #[clap(long, default_value_t = 1)]
+    pub hot_receivers: usize,
+}
+
+impl TrafficArgs {
+    pub fn validate(&self) -> Result<()> {
+        let share = |value: f64, flag: &str| {
+            if (0.0..=1.0).contains(&value) {
+                Ok(())
+            } else {
+                Err(AppError::Other(format!("{} must be between 0 and 1", flag)))
+            }
+        };
+        share(self.jitter, "--jitter")?;
+        share(self.hot_sender_share, "--hot-sender-share")?;
+        share(self.hot_receiver_share, "--hot-receiver-share")?;
+        if self.hot_sender_share + self.hot_receiver_share > 1.0 {
+            return Err(AppError::Other("--hot-sender-share and --hot-receiver-share add up to more than 1".to_string()));
+        }
+        if self.hot_receivers == 0 {
+            return Err(AppError::Other("--hot-receivers must be at least 1".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Which contention a transaction belongs to. The lanes are disjoint: spread traffic
+/// neither comes from the hot sender nor goes to a hot receiver.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Lane {
+    #[default]
+    Spread,
+    HotSender,
+    HotReceiver,
+}
+
+impl fmt::Display for Lane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Lane::Spread => "spread",
+            Lane::HotSender => "hot-sender",
+            Lane::HotReceiver => "hot-receiver",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// Transaction `i` uses account `i % accounts`
+    RoundRobin,
+    Uniform,
+    /// Account `k` is picked with weight `1 / (k + 1)^exponent`
+    Zipf(f64),
+    One(usize),
+}
+
+impl FromStr for Distribution {
+    type Err = String;
+
+    fn from_str(spec: &str) -> std::result::Result<Self, String> {
+        let (name, param) = match spec.split_once(':') {
+            Some((name, param)) => (name, Some(param)),
+            None => (spec, None),
+        };
+        let invalid = || format!("invalid distribution {:?}", spec);
+        match (name, param) {
+            ("round-robin", None) => Ok(Distribution::RoundRobin),
+            ("uniform", None) => Ok(Distribution::Uniform),
+            ("zipf", None) => Ok(Distribution::Zipf(1.0)),
+            ("zipf", Some(exponent)) => match exponent.parse::<f64>() {
+                Ok(exponent) if exponent > 0.0 => Ok(Distribution::Zipf(exponent)),
+                _ => Err(invalid()),
+            },
+            ("one", None) => Ok(Distribution::One(0)),
+            ("one", Some(index)) => index.parse().map(Distribution::One).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Distribution::RoundRobin => write!(f, "round-robin"),
+            Distribution::Uniform => write!(f, "uniform"),
+            Distribution::Zipf(exponent) => write!(f, "zipf:{}", exponent),
+            Distribution::One(index) => write!(f, "one:{}", index),
+        }
+    }
+}
+
+/// `MIN` or `MIN:MAX`, drawn uniformly per transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Range {
+    pub fn pick(self, u: f64) -> u64 {
+        self.min + ((self.max - self.min + 1) as f64 * u) as u64
+    }
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(spec: &str) -> std::result::Result<Self, String> {
+        let parse = |value: &str| value.trim().parse::<u64>().map_err(|_| format!("invalid range {:?}", spec));
+        let (min, max) = match spec.split_once(':') {
+            Some((min, max)) => (parse(min)?, parse(max)?),
+            None => (parse(spec)?, parse(spec)?),
+        };
+        if min > max {
+            return Err(format!("range {:?} has MIN above MAX", spec));
+        }
+        Ok(Range { min, max })
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}:{}", self.min, self.max)
+        }
+    }
+}
+
+/// Picks one of `count` accounts per transaction.
+#[derive(Debug, Clone)]
+pub struct Picker {
+    distribution: Distribution,
+    count: usize,
+    /// Cumulative Zipf weights, ending at 1
+    cdf: Vec<f64>,
+}
+
+impl Picker {
+    pub fn new(distribution: Distribution, count: usize, what: &str) -> Result<Self> {
+        if count == 0 {
+            return Err(AppError::Other(format!("At least one {} is needed", what)));
+        }
+        if let Distribution::One(index) = distribution {
+            if index >= count {
+                return Err(AppError::Other(format!("{} {} does not exist; there are {}", what, index, count)));
+            }
+        }
+        let cdf = match distribution {
+            Distribution::Zipf(exponent) => {
+                let weights: Vec<f64> = (1..=count).map(|rank| (rank as f64).powf(-exponent)).collect();
+                let total: f64 = weights.iter().sum();
+                weights.iter()
+                    .scan(0.0, |sum, weight| {
+                        *sum += weight / total;
+                        Some(*sum)
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        Ok(Picker { distribution, count, cdf })
+    }
+
+    pub fn pick(&self, seed: u64, tx_idx: usize, stream: Stream) -> usize {
+        match self.distribution {
+            Distribution::RoundRobin => tx_idx % self.count,
+            Distribution::Uniform => ((draw(seed, tx_idx, stream) * self.count as f64) as usize).min(self.count - 1),
+            Distribution::Zipf(_) => {
+                let u = draw(seed, tx_idx, stream);
+                self.cdf.partition_point(|cumulative| *cumulative <= u).min(self.count - 1)
+            }
+            Distribution::One(index) => index,
+        }
+    }
+}
+
+/// Sender and receiver choice for a run.
+#[derive(Debug, Clone)]
+pub struct Traffic {
+    pub seed: u64,
+    hot_sender_share: f64,
+    hot_receiver_share: f64,
+    /// Accounts below these indices are hot and left out of spread traffic
+    hot_senders: usize,
+    hot_receivers: usize,
+    senders: Picker,
+    receivers: Picker,
+}
+
+impl Traffic {
+    pub fn new(args: &TrafficArgs, seed: u64, senders: usize, receivers: usize) -> Result<Self> {
+        let hot_senders = usize::from(args.hot_sender_share > 0.0);
+        let hot_receivers = if args.hot_receiver_share > 0.0 { args.hot_receivers } else { 0 };
+        if hot_senders >= senders || hot_receivers >= receivers {
+            return Err(AppError::Other(format!(
+                "{} hot senders and {} hot receivers leave none of the {} senders and {} receivers for spread traffic",
+                hot_senders, hot_receivers, senders, receivers
+            )));
+        }
+        Ok(Traffic {
+            seed,
+            hot_sender_share: args.hot_sender_share,
+            hot_receiver_share: args.hot_receiver_share,
+            hot_senders,
+            hot_receivers,
+            senders: Picker::new(args.sender_dist, senders - hot_senders, "sender")?,
+            receivers: Picker::new(args.receiver_dist, receivers - hot_receivers, "receiver")?,
+        })
+    }
+
+    pub fn lane(&self, tx_idx: usize) -> Lane {
+        if self.hot_senders + self.hot_receivers == 0 {
+            return Lane::Spread;
+        }
+        let u = draw(self.seed, tx_idx, Stream::Lane);
+        if u < self.hot_sender_share {
+            Lane::HotSender
+        } else if u < self.hot_sender_share + self.hot_receiver_share {
+            Lane::HotReceiver
+        } else {
+            Lane::Spread
+        }
+    }
+
+    pub fn sender(&self, tx_idx: usize) -> usize {
+        match self.lane(tx_idx) {
+            Lane::HotSender => 0,
+            _ => self.hot_senders + self.senders.pick(self.seed, tx_idx, Stream::Sender),
+        }
+    }
+
+    pub fn receiver(&self, tx_idx: usize) -> usize {
+        match self.lane(tx_idx) {
+            Lane::HotReceiver => {
+                let u = draw(self.seed, tx_idx, Stream::Receiver);
+                ((u * self.hot_receivers as f64) as usize).min(self.hot_receivers - 1)
+            }
+            _ => self.hot_receivers + self.receivers.pick(self.seed, tx_idx, Stream::Receiver),
+        }
+    }
+}
+
+impl fmt::Display for Traffic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seed {}, senders {}, receivers {}", self.seed, self.senders.distribution, self.receivers.distribution)?;
+        if self.hot_senders > 0 {
+            write!(f, ", {:.0}% from the hot sender", self.hot_sender_share * 100.0)?;
+        }
+        if self.hot_receivers > 0 {
+            write!(f, ", {:.0}% to {} hot receivers", self.hot_receiver_share * 100.0, self.hot_receivers)?;
+        }
+        Ok(())
+    }
+}
+
+/// Seeded send-time jitter for the open-loop schedule.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Jitter {
+    pub seed: u64,
+    /// Fraction of the gap between sends, 0 for none
+    pub fraction: f64,
+}
+
+impl Jitter {
+    /// Offset in seconds of the `tx_idx`-th send, whose gap to the next send is `gap` seconds.
+    pub fn offset(&self, tx_idx: usize, gap: f64) -> f64 {
+        if self.fraction > 0.0 {
+            (draw(self.seed, tx_idx, Stream::Jitter) - 0.5) * self.fraction * gap
+        } else {
+            0.0
+        }
+    }
+}
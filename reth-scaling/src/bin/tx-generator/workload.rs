@@ -0,0 +1,386 @@
+// What each test transaction does: a plain transfer, a MonetSmartContract call, a
+// calldata-heavy message, a storage-writing contract creation, an ERC-20 transfer,
+// or a weighted mix
+
+use bridge_core::{
+    bindings::{SendETHToDestinationChainCall, SendMessageToDestinationChainCall},
+    monet_contract, parse_address, RpcProvider,
+};
+use clap::ValueEnum;
+use ethers::{
+    abi::AbiEncode,
+    providers::Middleware,
+    types::{transaction::eip2718::TypedTransaction, Address, Bytes, TransactionRequest, U256},
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc};
+
+use crate::token;
+use crate::traffic::{draw, Lane, Range, Stream, Traffic};
+use crate::{AppError, Result};
+
+const WEI_PER_GWEI: u64 = 1_000_000_000;
+
+// Headroom over eth_estimateGas, since contract state moves on during the run
+const GAS_MARGIN_PERCENT: u64 = 20;
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    /// 21k-gas ETH transfer to a receiver
+    #[default]
+    Transfer,
+    /// MonetSmartContract.sendETHToDestinationChain to a receiver
+    SendEth,
+    /// MonetSmartContract.sendMessageToDestinationChain with a --payload-bytes payload
+    Message,
+    /// Contract creation whose init code writes --storage-slots fresh storage slots
+    Storage,
+    /// ERC-20 transfer of the load token to a receiver
+    Erc20Transfer,
+    /// ERC-20 transferFrom of the load token from the previous sender to a receiver
+    Erc20TransferFrom,
+}
+
+impl Kind {
+    fn needs_contract(self) -> bool {
+        matches!(self, Kind::SendEth | Kind::Message)
+    }
+
+    pub fn is_token(self) -> bool {
+        matches!(self, Kind::Erc20Transfer | Kind::Erc20TransferFrom)
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Transfer => "transfer",
+            Kind::SendEth => "send-eth",
+            Kind::Message => "message",
+            Kind::Storage => "storage",
+            Kind::Erc20Transfer => "erc20-transfer",
+            Kind::Erc20TransferFrom => "erc20-transfer-from",
+        })
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct WorkloadArgs {
+    /// Transaction kind (transfer, send-eth, message, storage, erc20-transfer,
+    /// erc20-transfer-from), or a weighted mix of them such as transfer:6,send-eth:3,message:1
+    #[clap(long, default_value = "transfer")]
+    pub workload: String,
+
+    /// MonetSmartContract address for send-eth and message; taken from --node if given
+    #[clap(long)]
+    pub contract_address: Option<String>,
+
+    /// Destination chain ID for send-eth and message; the contract's first supported
+    /// destination chain by default
+    #[clap(long)]
+    pub dest_chain_id: Option<u32>,
+
+    /// Payload size of message transactions, e.g. to probe --txpool.max-tx-input-bytes;
+    /// MIN:MAX draws a size per transaction from --seed
+    #[clap(long, default_value = "1024")]
+    pub payload_bytes: Range,
+
+    /// Value of transfers and send-eth calls, and token amount of erc20 transfers, in
+    /// gwei (or 1e-9 tokens); MIN:MAX draws an amount per transaction from --seed
+    #[clap(long, default_value = "1000000")]
+    pub amount_gwei: Range,
+
+    /// Message type of message transactions; its fee is read from the contract
+    #[clap(long, default_value_t = 0)]
+    pub message_type: u8,
+
+    /// Storage slots written by each storage transaction (at most 65536)
+    #[clap(long, default_value_t = 20)]
+    pub storage_slots: usize,
+
+    /// ERC-20 token for the erc20 kinds instead of deploying LoadToken
+    #[clap(long)]
+    pub token_address: Option<String>,
+
+    /// Forge artifact LoadToken is deployed from
+    #[clap(long, default_value = "reth-contract/out-token/LoadToken.sol/LoadToken.json")]
+    pub token_artifact: String,
+
+    /// Whole tokens every sender is topped up to before the run by LoadToken.mintBatch;
+    /// 0 leaves balances alone, e.g. for a token that cannot be minted
+    #[clap(long, default_value_t = 1000)]
+    pub token_seed: u64,
+}
+
+impl WorkloadArgs {
+    /// Kinds in the mix, in the order given.
+    pub fn kinds(&self) -> Result<Vec<Kind>> {
+        Ok(parse_mix(&self.workload)?.into_iter().map(|(kind, _)| kind).collect())
+    }
+}
+
+/// The kinds of a run with their calldata, value and gas limit, and the order they take turns in.
+#[derive(Debug)]
+pub struct Workload {
+    spec: String,
+    /// Kinds in turn: transaction `i` is `cycle[i % cycle.len()]`
+    cycle: Vec<Kind>,
+    /// Who sends transaction `i` to whom; its amount and payload size are drawn from the same seed
+    traffic: Traffic,
+    amount_gwei: Range,
+    payload_bytes: Range,
+    message_type: u8,
+    contract: Address,
+    dest_chain_id: u32,
+    message_fee: U256,
+    /// Payload of the largest message, shorter messages send a prefix of it
+    payload: Bytes,
+    storage_code: Bytes,
+    token: Address,
+    /// Senders by index, for the owners transferFrom pulls from
+    senders: Vec<Address>,
+    gas: Vec<(Kind, U256)>,
+}
+
+impl Workload {
+    /// Parses the mix and prepares every kind in it. Contract kinds read the destination
+    /// chain and message fee from the contract, and token kinds need the token prepared
+    /// by `token::prepare`. Gas limits of all but transfers are estimated as sent by the
+    /// first sender, with the largest amount and payload.
+    pub async fn resolve(
+        args: &WorkloadArgs,
+        traffic: Traffic,
+        provider: &Arc<RpcProvider>,
+        senders: &[Address],
+    ) -> Result<Self> {
+        let mix = parse_mix(&args.workload)?;
+        let kinds: Vec<Kind> = mix.iter().map(|(kind, _)| *kind).collect();
+
+        let mut workload = Workload {
+            spec: args.workload.clone(),
+            cycle: interleave(&mix),
+            traffic,
+            amount_gwei: args.amount_gwei,
+            payload_bytes: args.payload_bytes,
+            message_type: args.message_type,
+            contract: Address::zero(),
+            dest_chain_id: 0,
+            message_fee: U256::zero(),
+            payload: Bytes::default(),
+            storage_code: Bytes::default(),
+            token: Address::zero(),
+            senders: senders.to_vec(),
+            gas: vec![(Kind::Transfer, U256::from(21_000))],
+        };
+        let from = *senders.first()
+            .ok_or_else(|| AppError::Other("At least one sender is needed".to_string()))?;
+
+        if kinds.iter().any(|kind| kind.needs_contract()) {
+            let contract = args.contract_address.as_deref().ok_or_else(|| {
+                AppError::Other("send-eth and message workloads need --contract-address or --node".to_string())
+            })?;
+            workload.contract = parse_address(contract)?;
+            let monet = monet_contract(workload.contract, provider.clone());
+            workload.dest_chain_id = match args.dest_chain_id {
+                Some(chain_id) => chain_id,
+                None => monet.get_all_supported_destination_chains().call().await
+                    .map_err(|e| AppError::Provider(format!("Failed to read destination chains: {}", e)))?
+                    .first()
+                    .copied()
+                    .ok_or_else(|| AppError::Other(format!(
+                        "Contract {:?} has no destination chains; pass --dest-chain-id", workload.contract
+                    )))?,
+            };
+            if kinds.contains(&Kind::Message) {
+                workload.message_fee = monet
+                    .get_required_fee_for_destination_chain(workload.dest_chain_id, args.message_type)
+                    .call().await
+                    .map_err(|e| AppError::Provider(format!(
+                        "Failed to read the fee of message type {} to chain {}: {}",
+                        args.message_type, workload.dest_chain_id, e
+                    )))?;
+                workload.payload = payload(args.payload_bytes.max as usize);
+            }
+        }
+        if kinds.contains(&Kind::Storage) {
+            workload.storage_code = storage_init_code(args.storage_slots)?;
+        }
+        if kinds.iter().any(|kind| kind.is_token()) {
+            let token = args.token_address.as_deref()
+                .ok_or_else(|| AppError::Other("The token has not been deployed".to_string()))?;
+            workload.token = parse_address(token)?;
+        }
+
+        // A fresh receiver, since a first token balance costs more than a later one
+        let receiver = Address::random();
+        for kind in kinds.into_iter().filter(|kind| *kind != Kind::Transfer) {
+            let amount = U256::from(args.amount_gwei.max) * WEI_PER_GWEI;
+            let request = workload.build(kind, 0, receiver, amount, args.payload_bytes.max as usize).from(from);
+            let estimate = provider.estimate_gas(&TypedTransaction::Legacy(request), None).await
+                .map_err(|e| AppError::Provider(format!("Failed to estimate gas of {} transactions: {}", kind, e)))?;
+            workload.gas.push((kind, estimate * (100 + GAS_MARGIN_PERCENT) / 100));
+        }
+        Ok(workload)
+    }
+
+    /// Only plain transfers, whose gas needs no receipts to know.
+    pub fn is_plain(&self) -> bool {
+        self.cycle.iter().all(|kind| *kind == Kind::Transfer)
+    }
+
+    pub fn kind_of(&self, tx_idx: usize) -> Kind {
+        self.cycle[tx_idx % self.cycle.len()]
+    }
+
+    /// Hot sender, hot receiver or spread traffic.
+    pub fn lane_of(&self, tx_idx: usize) -> Lane {
+        self.traffic.lane(tx_idx)
+    }
+
+    /// Index of the sender of transaction `tx_idx`.
+    pub fn sender_of(&self, tx_idx: usize) -> usize {
+        self.traffic.sender(tx_idx)
+    }
+
+    /// Index of the receiver of transaction `tx_idx`.
+    pub fn receiver_of(&self, tx_idx: usize) -> usize {
+        self.traffic.receiver(tx_idx)
+    }
+
+    /// Unsigned transaction `tx_idx` of `kind` from sender number `sender`, with its gas
+    /// limit but without nonce or fees. `to` receives the value of transfers, send-eth
+    /// calls and token transfers.
+    pub fn request(&self, kind: Kind, tx_idx: usize, sender: usize, to: Address) -> TransactionRequest {
+        let seed = self.traffic.seed;
+        let amount = U256::from(self.amount_gwei.pick(draw(seed, tx_idx, Stream::Amount))) * WEI_PER_GWEI;
+        let payload_len = self.payload_bytes.pick(draw(seed, tx_idx, Stream::Payload)) as usize;
+        self.build(kind, sender, to, amount, payload_len)
+    }
+
+    fn build(&self, kind: Kind, sender: usize, to: Address, amount: U256, payload_len: usize) -> TransactionRequest {
+        let request = match kind {
+            Kind::Transfer => TransactionRequest::new().to(to).value(amount),
+            Kind::SendEth => TransactionRequest::new()
+                .to(self.contract)
+                .value(amount)
+                .data(SendETHToDestinationChainCall { chain_id: self.dest_chain_id, recipient: to }.encode()),
+            Kind::Message => TransactionRequest::new()
+                .to(self.contract)
+                .value(self.message_fee)
+                .data(SendMessageToDestinationChainCall {
+                    chain_id: self.dest_chain_id,
+                    message_type: self.message_type,
+                    payload: self.payload.0.slice(..payload_len).into(),
+                }.encode()),
+            Kind::Storage => TransactionRequest::new().data(self.storage_code.clone()),
+            Kind::Erc20Transfer => TransactionRequest::new()
+                .to(self.token)
+                .data(token::transfer_data(to, amount)),
+            Kind::Erc20TransferFrom => {
+                // Sender `i` was approved by sender `i - 1`
+                let owners = self.senders.len();
+                let owner = self.senders[(sender + owners - 1) % owners];
+                TransactionRequest::new()
+                    .to(self.token)
+                    .data(token::transfer_from_data(owner, to, amount))
+            }
+        };
+        match self.gas.iter().find(|(gas_kind, _)| *gas_kind == kind) {
+            Some((_, gas)) => request.gas(*gas),
+            None => request,
+        }
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec)?;
+        if self.cycle.iter().any(|kind| *kind != Kind::Storage && *kind != Kind::Message) {
+            write!(f, ", {} gwei", self.amount_gwei)?;
+        }
+        if self.cycle.contains(&Kind::Message) {
+            write!(f, ", {} byte payloads", self.payload_bytes)?;
+        }
+        for (kind, gas) in &self.gas {
+            if self.cycle.contains(kind) {
+                write!(f, ", {} gas limit {}", kind, gas)?;
+            }
+        }
+        if self.cycle.iter().any(|kind| kind.needs_contract()) {
+            write!(f, ", contract {:?} to chain {}", self.contract, self.dest_chain_id)?;
+        }
+        if self.cycle.iter().any(|kind| kind.is_token()) {
+            write!(f, ", token {:?}", self.token)?;
+        }
+        write!(f, ", {}", self.traffic)
+    }
+}
+
+// `kind` or `kind:weight,kind:weight,...`
+fn parse_mix(spec: &str) -> Result<Vec<(Kind, usize)>> {
+    let mut mix: Vec<(Kind, usize)> = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let (name, weight) = match part.split_once(':') {
+            Some((name, weight)) => (name, weight.parse().map_err(|_| {
+                AppError::Other(format!("Invalid weight in workload {:?}", part))
+            })?),
+            None => (part, 1),
+        };
+        let kind = Kind::from_str(name, true)
+            .map_err(|_| AppError::Other(format!("Unknown workload kind {:?}", name)))?;
+        if mix.iter().any(|(existing, _)| *existing == kind) {
+            return Err(AppError::Other(format!("Workload kind {} given twice", kind)));
+        }
+        if weight > 0 {
+            mix.push((kind, weight));
+        }
+    }
+    if mix.is_empty() {
+        return Err(AppError::Other(format!("Workload {:?} has no kind with a weight above 0", spec)));
+    }
+    Ok(mix)
+}
+
+// One turn of the mix with weights reduced by their common divisor and the kinds spread
+// evenly, so 3:1 sends A A B A rather than long runs of each kind
+fn interleave(mix: &[(Kind, usize)]) -> Vec<Kind> {
+    let divisor = mix.iter().fold(0, |divisor, (_, weight)| gcd(divisor, *weight));
+    let weights: Vec<usize> = mix.iter().map(|(_, weight)| weight / divisor).collect();
+    let total: usize = weights.iter().sum();
+
+    // Each turn goes to the kind furthest behind its share
+    let mut credit = vec![0isize; mix.len()];
+    let mut cycle = Vec::with_capacity(total);
+    for _ in 0..total {
+        for (credit, weight) in credit.iter_mut().zip(&weights) {
+            *credit += *weight as isize;
+        }
+        let (next, _) = credit.iter().enumerate().max_by_key(|(i, credit)| (**credit, -(*i as isize))).unwrap();
+        credit[next] -= total as isize;
+        cycle.push(mix[next].0);
+    }
+    cycle
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+// Non-zero bytes, so the payload pays the full calldata price
+fn payload(len: usize) -> Bytes {
+    (0..len).map(|i| (i % 255) as u8 + 1).collect::<Vec<u8>>().into()
+}
+
+// Init code that stores 1 in slots 0..slots and deploys no code: PUSH1 1, PUSH2 slot, SSTORE per slot
+fn storage_init_code(slots: usize) -> Result<Bytes> {
+    if slots == 0 || slots > 1 << 16 {
+        return Err(AppError::Other("--storage-slots must be between 1 and 65536".to_string()));
+    }
+    let mut code = Vec::with_capacity(slots * 6 + 1);
+    for slot in 0..slots {
+        code.extend_from_slice(&[0x60, 0x01, 0x61, (slot >> 8) as u8, slot as u8, 0x55]);
+    }
+    code.push(0x00);
+    Ok(code.into())
+}
@@ -0,0 +1,299 @@
+use bridge_core::{connect, AccountStore, HeadWatcher, RpcProvider, TopologyArgs};
+use clap::Parser;
+use ethers::prelude::*;
+use eyre::Result;
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{sync::watch, time::timeout};
+
+// Column order of the CSV; keep in step with `BlockRecord::write`
+const HEADER: &str = "observed_at_ms,node,chain_id,number,timestamp,block_time_secs,tx_count,test_tx_count,\
+test_tx_share,gas_used,gas_limit,gas_used_share,base_fee_wei";
+
+// Blocks at least this share of their gas limit count as full in the summary
+const FULL_BLOCK: f64 = 0.95;
+
+/// Follows one or more chains and records every block: its timing, transaction count,
+/// gas against the gas limit, base fee, and how many transactions came from test accounts
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    topology: TopologyArgs,
+
+    /// Node to follow, by name or number; may be repeated (defaults to node 1)
+    #[arg(long = "node")]
+    nodes: Vec<String>,
+
+    /// RPC endpoint to follow instead of a configured node; may be repeated
+    #[arg(long = "rpc-url", conflicts_with = "nodes")]
+    rpc_urls: Vec<String>,
+
+    /// Accounts file whose senders and receivers are the test accounts; may be repeated
+    /// (defaults to the account file of each followed node)
+    #[arg(long = "accounts-file")]
+    accounts_files: Vec<String>,
+
+    /// CSV file every block is written to, one row per block
+    #[arg(long, default_value = "blocks.csv")]
+    output: String,
+
+    /// Start at this block instead of the one after the current head
+    #[arg(long)]
+    from_block: Option<u64>,
+
+    /// Stop after this many seconds; runs until Ctrl-C otherwise
+    #[arg(long)]
+    duration_secs: Option<u64>,
+
+    /// How often to poll for new blocks when the endpoint has no subscriptions
+    #[arg(long, default_value_t = 1000)]
+    poll_ms: u64,
+}
+
+struct Chain {
+    label: String,
+    chain_id: u64,
+    provider: Arc<RpcProvider>,
+    heads: HeadWatcher,
+    /// Addresses whose transactions count as ours
+    accounts: Arc<HashSet<Address>>,
+}
+
+#[derive(Debug)]
+struct BlockRecord {
+    observed_at_ms: u128,
+    number: u64,
+    timestamp: u64,
+    /// Seconds since the parent block, by block timestamps
+    block_time: Option<u64>,
+    tx_count: usize,
+    test_tx_count: usize,
+    gas_used: U256,
+    gas_limit: U256,
+    base_fee: Option<U256>,
+}
+
+impl BlockRecord {
+    fn gas_used_share(&self) -> f64 {
+        if self.gas_limit.is_zero() {
+            return 0.0;
+        }
+        self.gas_used.as_u128() as f64 / self.gas_limit.as_u128() as f64
+    }
+
+    fn test_tx_share(&self) -> f64 {
+        if self.tx_count == 0 {
+            return 0.0;
+        }
+        self.test_tx_count as f64 / self.tx_count as f64
+    }
+
+    fn write(&self, out: &mut impl Write, label: &str, chain_id: u64) -> std::io::Result<()> {
+        writeln!(out, "{},{},{},{},{},{},{},{},{:.4},{},{},{:.4},{}",
+            self.observed_at_ms,
+            label,
+            chain_id,
+            self.number,
+            self.timestamp,
+            self.block_time.map(|secs| secs.to_string()).unwrap_or_default(),
+            self.tx_count,
+            self.test_tx_count,
+            self.test_tx_share(),
+            self.gas_used,
+            self.gas_limit,
+            self.gas_used_share(),
+            self.base_fee.map(|fee| fee.to_string()).unwrap_or_default(),
+        )
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    let poll_interval = Duration::from_millis(cli.poll_ms);
+
+    // Followed chains, each with the test accounts of its own account file unless given
+    let mut chains = Vec::new();
+    let given_accounts = load_accounts(&cli.accounts_files)?;
+    if !cli.rpc_urls.is_empty() {
+        for url in &cli.rpc_urls {
+            let provider = Arc::new(connect(url).await?);
+            let heads = HeadWatcher::start(provider.clone(), poll_interval).await?;
+            chains.push(chain(url.clone(), provider, heads, given_accounts.clone()).await?);
+        }
+    } else {
+        let selectors = if cli.nodes.is_empty() { vec!["1".to_string()] } else { cli.nodes.clone() };
+        for selector in &selectors {
+            let node = cli.topology.node(selector)?;
+            let provider = Arc::new(node.provider().await?);
+            let heads = node.watch_heads(&provider, poll_interval).await?;
+            let accounts = if cli.accounts_files.is_empty() {
+                match AccountStore::load(&node.account_file) {
+                    Ok(store) => Arc::new(addresses(&store)?),
+                    Err(e) => {
+                        println!("No test accounts for {} ({}); counting none as ours", node.name, e);
+                        Arc::new(HashSet::new())
+                    }
+                }
+            } else {
+                given_accounts.clone()
+            };
+            chains.push(chain(node.name.clone(), provider, heads, accounts).await?);
+        }
+    }
+
+    let mut out = BufWriter::new(File::create(&cli.output)?);
+    writeln!(out, "{}", HEADER)?;
+    let out = Arc::new(Mutex::new(out));
+
+    for chain in &chains {
+        println!("Following {} (chain ID {}) from block {}, {} test accounts, heads by {}",
+            chain.label, chain.chain_id, cli.from_block.unwrap_or(chain.heads.head() + 1),
+            chain.accounts.len(), if chain.heads.subscribed() { "subscription" } else { "polling" });
+    }
+    println!("Writing blocks to {}; stop with Ctrl-C", cli.output);
+
+    let (stop, stopped) = watch::channel(false);
+    let followers: Vec<_> = chains.into_iter()
+        .map(|chain| (chain.label.clone(), tokio::spawn(follow(chain, cli.from_block, out.clone(), stopped.clone()))))
+        .collect();
+
+    match cli.duration_secs {
+        Some(secs) => {
+            let _ = timeout(Duration::from_secs(secs), tokio::signal::ctrl_c()).await;
+        }
+        None => tokio::signal::ctrl_c().await?,
+    }
+    let _ = stop.send(true);
+
+    // A chain that failed does not keep the others from being summarized
+    for (label, follower) in followers {
+        match follower.await {
+            Ok(Ok((chain, records))) => summarize(&chain, &records),
+            Ok(Err(e)) => println!("\n=== {} ===\nStopped following: {}", label, e),
+            Err(e) => println!("\n=== {} ===\nFollower failed: {}", label, e),
+        }
+    }
+    out.lock().unwrap().flush()?;
+    Ok(())
+}
+
+async fn chain(label: String, provider: Arc<RpcProvider>, heads: HeadWatcher, accounts: Arc<HashSet<Address>>) -> Result<Chain> {
+    let chain_id = provider.get_chainid().await?.as_u64();
+    Ok(Chain { label, chain_id, provider, heads, accounts })
+}
+
+fn load_accounts(paths: &[String]) -> Result<Arc<HashSet<Address>>> {
+    let mut accounts = HashSet::new();
+    for path in paths {
+        accounts.extend(addresses(&AccountStore::load(path)?)?);
+    }
+    Ok(Arc::new(accounts))
+}
+
+fn addresses(store: &AccountStore) -> Result<HashSet<Address>> {
+    store.senders.iter()
+        .chain(&store.receivers)
+        .map(|account| Ok(account.address()?))
+        .collect()
+}
+
+// Records every block from `from_block` (or the next one) until stopped, catching up on
+// any heads skipped in between
+async fn follow(
+    mut chain: Chain,
+    from_block: Option<u64>,
+    out: Arc<Mutex<BufWriter<File>>>,
+    mut stopped: watch::Receiver<bool>,
+) -> Result<(Chain, Vec<BlockRecord>)> {
+    let mut next = from_block.unwrap_or(chain.heads.head() + 1);
+    let mut parent_timestamp = match next.checked_sub(1) {
+        Some(parent) => chain.provider.get_block(parent).await?.map(|block| block.timestamp.as_u64()),
+        None => None,
+    };
+    let mut records = Vec::new();
+
+    loop {
+        while next <= chain.heads.head() {
+            let block = match chain.provider.get_block_with_txs(next).await {
+                Ok(Some(block)) => block,
+                Ok(None) => break,
+                Err(e) => {
+                    println!("{}: failed to get block {}: {}", chain.label, next, e);
+                    break;
+                }
+            };
+            let timestamp = block.timestamp.as_u64();
+            let record = BlockRecord {
+                observed_at_ms: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis(),
+                number: next,
+                timestamp,
+                block_time: parent_timestamp.map(|parent| timestamp.saturating_sub(parent)),
+                tx_count: block.transactions.len(),
+                test_tx_count: block.transactions.iter().filter(|tx| chain.accounts.contains(&tx.from)).count(),
+                gas_used: block.gas_used,
+                gas_limit: block.gas_limit,
+                base_fee: block.base_fee_per_gas,
+            };
+            {
+                let mut out = out.lock().unwrap();
+                record.write(&mut *out, &chain.label, chain.chain_id)?;
+                out.flush()?;
+            }
+            parent_timestamp = Some(timestamp);
+            records.push(record);
+            next += 1;
+        }
+
+        tokio::select! {
+            _ = chain.heads.next() => {}
+            _ = stopped.changed() => break,
+        }
+    }
+    Ok((chain, records))
+}
+
+fn summarize(chain: &Chain, records: &[BlockRecord]) {
+    println!("\n=== {} (chain ID {}) ===", chain.label, chain.chain_id);
+    let (Some(first), Some(last)) = (records.first(), records.last()) else {
+        println!("No blocks observed");
+        return;
+    };
+    let empty = records.iter().filter(|record| record.tx_count == 0).count();
+    println!("Blocks: {} (#{} to #{}), {} empty", records.len(), first.number, last.number, empty);
+
+    let block_times: Vec<u64> = records.iter().filter_map(|record| record.block_time).collect();
+    if !block_times.is_empty() {
+        println!("Block time: mean {:.2}s, min {}s, max {}s",
+            block_times.iter().sum::<u64>() as f64 / block_times.len() as f64,
+            block_times.iter().min().unwrap_or(&0),
+            block_times.iter().max().unwrap_or(&0));
+    }
+
+    let txs: usize = records.iter().map(|record| record.tx_count).sum();
+    let ours: usize = records.iter().map(|record| record.test_tx_count).sum();
+    let span = last.timestamp.saturating_sub(first.timestamp) + first.block_time.unwrap_or(0);
+    let tps = if span > 0 { txs as f64 / span as f64 } else { 0.0 };
+    println!("Transactions: {} ({:.1} per block, {:.1} TPS by block timestamps)",
+        txs, txs as f64 / records.len() as f64, tps);
+    println!("From test accounts: {} ({:.1}%)",
+        ours, if txs > 0 { ours as f64 / txs as f64 * 100.0 } else { 0.0 });
+
+    let shares: Vec<f64> = records.iter().map(BlockRecord::gas_used_share).collect();
+    let full = shares.iter().filter(|share| **share >= FULL_BLOCK).count();
+    println!("Gas used: mean {:.1}%, max {:.1}% of the {} gas limit, {} blocks at least {:.0}% full",
+        shares.iter().sum::<f64>() / shares.len() as f64 * 100.0,
+        shares.iter().cloned().fold(0.0, f64::max) * 100.0,
+        last.gas_limit, full, FULL_BLOCK * 100.0);
+
+    let base_fees: Vec<U256> = records.iter().filter_map(|record| record.base_fee).collect();
+    if let (Some(min), Some(max)) = (base_fees.iter().min(), base_fees.iter().max()) {
+        println!("Base fee: {} to {} wei, {} wei at the last block", min, max, base_fees[base_fees.len() - 1]);
+    }
+}
@@ -1,13 +1,22 @@
+use bridge_core::bindings::ReceiveEthFromSourceChainInBatchCall;
+use bridge_core::{NodeArgs, RpcProvider};
+use clap::Parser;
 use ethers::{
     prelude::*,
-    providers::{Provider, Http, Middleware},
+    providers::Middleware,
     types::{Transaction, H256, U256, Block},
-    abi::{Function, Token},
+    abi::AbiDecode,
+    contract::EthCall,
 };
 use eyre::Result;
-use std::{env, time::Duration, sync::Arc};
-use serde_json::Value;
-use tokio::time::sleep;
+use std::{time::Duration, sync::Arc};
+
+/// Watches a chain for receiveETHfromSourceChainInBatch transactions
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+}
 
 #[derive(Debug)]
 struct BatchTransferInfo {
@@ -25,33 +34,27 @@ async fn main() -> Result<()> {
     dotenv::dotenv().ok();
 
     // Get RPC URL and contract address from env
-    let rpc_url = env::var("NODE4_RPC")?;
-    let contract_addr = env::var("NODE4_CONTRACT")?
-        .parse::<Address>()?;
+    let node = Cli::parse().nodes.node_or("4")?;
+    let contract_addr = node.contract;
 
-    println!("Connecting to RPC endpoint: {}", rpc_url);
+    println!("Connecting to RPC endpoint: {}", node.rpc_url);
     println!("Monitoring contract: {:#x}", contract_addr);
 
-    let provider = Provider::<Http>::try_from(rpc_url)?;
-    let client = Arc::new(provider);
-
-    // Load contract ABI
-    let abi_content = include_str!("abi.json");
-    let abi_json: Value = serde_json::from_str(abi_content)?;
-    let abi: ethers::abi::Abi = serde_json::from_value(abi_json["abi"].clone())?;
+    let client = Arc::new(node.provider().await?);
 
-    let func = abi.function("receiveETHfromSourceChainInBatch")
-        .expect("Function not found in ABI");
-    let func_sig = func.short_signature();
+    let func_sig = ReceiveEthFromSourceChainInBatchCall::selector();
 
     println!("\nStarting block monitoring...");
     println!("Looking for batch transfers (function signature: 0x{})...", hex::encode(func_sig));
 
-    let mut block_number = client.get_block_number().await?;
-    println!("Starting from block: {}", block_number);
+    // New blocks arrive over newHeads on WebSocket/IPC, otherwise by polling every second
+    let mut heads = node.watch_heads(&client, Duration::from_secs(1)).await?;
+    let mut block_number = U64::from(heads.head());
+    println!("Starting from block: {} ({})", block_number,
+        if heads.subscribed() { "newHeads subscription" } else { "polling" });
     
     loop {
-        let latest_block = client.get_block_number().await?;
+        let latest_block = U64::from(heads.head());
         
         while block_number <= latest_block {
             print!("\rChecking block {} for batch transfers...", block_number);
@@ -59,42 +62,37 @@ async fn main() -> Result<()> {
             if let Some(block) = client.get_block_with_txs(block_number).await? {
                 if !block.transactions.is_empty() {
                     println!("\nBlock {} has {} transactions", block_number, block.transactions.len());
-                    process_block(&client, &block, contract_addr, func_sig, func).await?;
+                    process_block(&client, &block, contract_addr).await?;
                 }
             }
             block_number += 1.into();
         }
 
-        // Clear line before sleeping
+        // Clear line before waiting
         print!("\rWaiting for new blocks...");
-        sleep(Duration::from_millis(1000)).await;
+        heads.next().await;
     }
 }
 
 async fn process_block(
-    client: &Provider<Http>,
+    client: &RpcProvider,
     block: &Block<Transaction>,
     contract_addr: Address,
-    func_sig: [u8; 4],
-    func: &Function,
 ) -> Result<()> {
     let block_number = block.number.unwrap_or_default();
 
     for tx in &block.transactions {
         if tx.to == Some(contract_addr) {
-            if tx.input.0.len() >= 4 && tx.input.0[0..4] == func_sig {
-                // Found a matching transaction, decode it
-                if let Ok(decoded) = func.decode_input(&tx.input.0[4..]) {
-                    // Get recipients array length (3rd parameter)
-                    if let Some(Token::Array(recipients)) = decoded.get(2) {
-                        // Get gas usage from receipt
-                        if let Some(receipt) = client.get_transaction_receipt(tx.hash).await? {
-                            println!("\nBlock {} - Found batch transfer:", block_number);
-                            println!("  Transaction: {:#x}", tx.hash);
-                            println!("  Number of transfers in batch: {}", recipients.len());
-                            println!("  Gas used: {}", receipt.gas_used.unwrap_or_default());
-                        }
-                    }
+            // Decoding fails unless the selector is receiveETHfromSourceChainInBatch
+            if let Ok(call) = ReceiveEthFromSourceChainInBatchCall::decode(&tx.input) {
+                // Get gas usage from receipt
+                if let Some(receipt) = client.get_transaction_receipt(tx.hash).await? {
+                    println!("\nBlock {} - Found batch transfer:", block_number);
+                    println!("  Transaction: {:#x}", tx.hash);
+                    println!("  Source chain: {}, first message ID: {}",
+                        call.source_chain_id, call.source_chain_first_message_id);
+                    println!("  Number of transfers in batch: {}", call.recipients.len());
+                    println!("  Gas used: {}", receipt.gas_used.unwrap_or_default());
                 }
             }
         }
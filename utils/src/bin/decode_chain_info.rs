@@ -1,50 +1,43 @@
-use ethers::{
-    prelude::*,
-    providers::{Http, Provider},
-    types::{Address, U256},
-};
-use std::env;
+use bridge_core::{monet_contract, NodeArgs};
+use clap::Parser;
 use dotenv::dotenv;
 use eyre::Result;
 use std::sync::Arc;
-use serde_json::Value;
+
+/// Prints getDestinationChainInfo from a chain's contract
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
     
     // Get RPC and contract address from env
-    let rpc_url = env::var("NODE2_RPC")
-        .expect("RPC must be set in .env file");
-    let contract_addr = env::var("NODE2_CONTRACT")
-        .expect("CONTRACT must be set in .env file")
-        .parse::<Address>()?;
+    let node = Cli::parse().nodes.node_or("2")?;
 
     // Create provider
-    let provider = Provider::<Http>::try_from(rpc_url)?;
-    let client = Arc::new(provider);
-
-    // Load contract ABI
-    let contract_json: Value = serde_json::from_str(include_str!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json"))?;
-    let abi: ethers::abi::Abi = serde_json::from_value(contract_json["abi"].clone())?;
+    let client = Arc::new(node.provider().await?);
     
     // Create contract instance
-    let contract = Contract::new(contract_addr, abi, client);
+    let contract = monet_contract(node.contract, client);
 
     // Call getDestinationChainInfo
     let chain_id: u32 = 9012;
-    let result: (String, Address, Vec<U256>) = contract
-        .method("getDestinationChainInfo", chain_id)?
+    let (rpc_url, contract_address, supported_types) = contract
+        .get_destination_chain_info(chain_id)
         .call()
         .await?;
 
-    // Decode and print results
+    // Print results
     println!("Chain ID: {}", chain_id);
-    println!("RPC URL: {}", result.0);
-    println!("Contract Address: {:?}", result.1);
-    println!("Additional Info:");
-    for (i, value) in result.2.iter().enumerate() {
-        println!("  Value {}: {}", i, value);
+    println!("RPC URL: {}", rpc_url);
+    println!("Contract Address: {:?}", contract_address);
+    println!("Supported message types:");
+    for (i, value) in supported_types.iter().enumerate() {
+        println!("  Type {}: {}", i, value);
     }
 
     Ok(())
@@ -1,3 +1,4 @@
+use bridge_core::connect;
 use ethers::{
     core::types::TransactionRequest,
     prelude::*,
@@ -16,8 +17,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let rpc_url = &args[1];
 
-    // Set up the provider (RPC URL)
-    let provider = Provider::<Http>::try_from(rpc_url)?;
+    // Set up the provider (HTTP, WebSocket or IPC endpoint)
+    let provider = connect(rpc_url).await?;
 
     // Get the chain ID from the provider
     let chain_id = provider.get_chainid().await?;
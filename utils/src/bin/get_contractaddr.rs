@@ -1,18 +1,24 @@
+use bridge_core::NodeArgs;
+use clap::Parser;
 use ethers::{
-    providers::{Provider, Http, Middleware},
+    providers::Middleware,
     types::{TransactionReceipt, H256},
 };
-use std::env;
 use dotenv::dotenv;
 
+/// Prints the contract address created by a deployment transaction
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
     
-    // Set up the provider using NODE1_RPC from env
-    let rpc_url = env::var("NODE5_RPC")
-        .expect("RPC_URL must be set in .env file");
-    let provider = Provider::<Http>::try_from(rpc_url)?;
+    // Set up the provider for the selected node (NODE5 by default)
+    let provider = Cli::parse().nodes.node_or("5")?.provider().await?;
 
     // Transaction hash - convert from hex string to H256
     let tx_hash = "0x4a71686eaa3482577d652a6ded7cf00a93e8dcf788d7207cc9bfeefd23ee28f4"
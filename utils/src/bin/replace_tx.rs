@@ -0,0 +1,167 @@
+use bridge_core::replace::{pool_summary, pool_transactions};
+use bridge_core::{connect, AccountStore, HeadWatcher, NodeArgs, ReplaceMode, ReplaceOutcome, Replacement};
+use clap::Parser;
+use ethers::prelude::*;
+use eyre::Result;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::timeout;
+
+/// Speeds up or cancels the transactions an account has stuck in the node's txpool
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+
+    /// RPC endpoint instead of the selected node's
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    /// Private key of a stuck account; may be repeated
+    #[arg(long, conflicts_with = "accounts_file")]
+    key: Vec<String>,
+
+    /// Accounts file whose senders are checked (defaults to the node's account file)
+    #[arg(long)]
+    accounts_file: Option<String>,
+
+    /// Resend each transaction with higher fees, or replace it with a zero-value self-transfer
+    #[arg(long, value_enum, default_value_t = ReplaceMode::SpeedUp)]
+    mode: ReplaceMode,
+
+    /// Fee increase over the stuck transaction, and again on every re-bump
+    #[arg(long, default_value_t = 20)]
+    bump_percent: u64,
+
+    /// Bump a replacement again if it is not mined within this many seconds
+    #[arg(long, default_value_t = 30)]
+    rebump_secs: u64,
+
+    /// Give up following the replacements after this many seconds
+    #[arg(long, default_value_t = 300)]
+    timeout_secs: u64,
+
+    /// Only list the pool transactions of the accounts
+    #[arg(long)]
+    list: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+
+    let rpc_url = match &cli.rpc_url {
+        Some(url) => url.clone(),
+        None => cli.nodes.node_or("1")?.rpc_url,
+    };
+    let client = Arc::new(connect(&rpc_url).await?);
+    let chain_id = client.get_chainid().await?.as_u64();
+    println!("Connected to {} (chain ID {})", rpc_url, chain_id);
+
+    let wallets: Vec<LocalWallet> = if !cli.key.is_empty() {
+        cli.key.iter()
+            .map(|key| Ok(key.trim_start_matches("0x").parse::<LocalWallet>()?.with_chain_id(chain_id)))
+            .collect::<Result<_>>()?
+    } else {
+        let store = match &cli.accounts_file {
+            Some(path) => AccountStore::load(path)?,
+            None => cli.nodes.node_or("1")?.accounts()?,
+        };
+        store.senders.iter()
+            .map(|account| account.wallet(chain_id))
+            .collect::<std::result::Result<_, _>>()?
+    };
+    let addresses: Vec<Address> = wallets.iter().map(|wallet| wallet.address()).collect();
+    println!("Checking the txpool for {} accounts...", addresses.len());
+
+    if cli.list {
+        let entries = pool_summary(&*client, &addresses).await?;
+        for entry in &entries {
+            println!("{:?} nonce {} [{}] {}",
+                entry.from, entry.nonce, if entry.queued { "queued" } else { "pending" }, entry.summary);
+        }
+        println!("{} transactions in the pool", entries.len());
+        return Ok(());
+    }
+
+    let stuck = pool_transactions(&*client, &addresses).await?;
+    if stuck.is_empty() {
+        println!("No pending or queued transactions found");
+        return Ok(());
+    }
+
+    // Replace every stuck transaction at its own nonce
+    let mut replacements = Vec::new();
+    for pool_tx in &stuck {
+        let tx = &pool_tx.tx;
+        let wallet = wallets.iter().find(|wallet| wallet.address() == tx.from)
+            .expect("pool transactions are filtered by these addresses");
+        match Replacement::send(&*client, wallet, tx, cli.mode, cli.bump_percent).await {
+            Ok(replacement) => {
+                println!("{:?} nonce {}{}: replaced {:?} with {:?} ({})",
+                    tx.from, tx.nonce, if pool_tx.queued { " (queued)" } else { "" },
+                    tx.hash, replacement.hashes[0], replacement.fees());
+                replacements.push((replacement, wallet, Instant::now()));
+            }
+            Err(e) => println!("{:?} nonce {}: {}", tx.from, tx.nonce, e),
+        }
+    }
+
+    // Follow the replacements until mined, bumping the slow ones again; outcomes are
+    // checked on every new block, and at least every 2 seconds for the re-bumps
+    let mut heads = HeadWatcher::start(client.clone(), Duration::from_secs(2)).await?;
+    let start = Instant::now();
+    let (mut replaced, mut original, mut other) = (0, 0, 0);
+    while !replacements.is_empty() && start.elapsed() < Duration::from_secs(cli.timeout_secs) {
+        let mut remaining = Vec::new();
+        for (mut replacement, wallet, mut sent_at) in replacements {
+            match replacement.outcome(&*client).await? {
+                Some(ReplaceOutcome::Replaced { hash, block }) => {
+                    println!("✓ {:?} nonce {}: replacement {:?} mined in block {}",
+                        replacement.from, replacement.nonce, hash, block);
+                    replaced += 1;
+                }
+                Some(ReplaceOutcome::Original { block }) => {
+                    println!("✓ {:?} nonce {}: original {:?} mined in block {}",
+                        replacement.from, replacement.nonce, replacement.original, block);
+                    original += 1;
+                }
+                Some(ReplaceOutcome::Other) => {
+                    println!("✓ {:?} nonce {}: used by another transaction", replacement.from, replacement.nonce);
+                    other += 1;
+                }
+                None => {
+                    if sent_at.elapsed() >= Duration::from_secs(cli.rebump_secs) {
+                        match replacement.bump(&*client, wallet, cli.bump_percent).await {
+                            Ok(hash) => println!("{:?} nonce {}: bumped to {:?} ({})",
+                                replacement.from, replacement.nonce, hash, replacement.fees()),
+                            Err(e) => println!("{:?} nonce {}: {}", replacement.from, replacement.nonce, e),
+                        }
+                        sent_at = Instant::now();
+                    }
+                    remaining.push((replacement, wallet, sent_at));
+                }
+            }
+        }
+        replacements = remaining;
+        if !replacements.is_empty() {
+            let _ = timeout(Duration::from_secs(2), heads.next()).await;
+        }
+    }
+
+    println!("\nReplacement Summary:");
+    println!("Stuck transactions found: {}", stuck.len());
+    println!("Mined as replacement: {}", replaced);
+    println!("Mined as original: {}", original);
+    println!("Nonce used by another transaction: {}", other);
+    println!("Still not mined: {}", replacements.len());
+    for (replacement, _, _) in &replacements {
+        println!("  {:?} nonce {} (last sent {:?})",
+            replacement.from, replacement.nonce, replacement.hashes.last());
+    }
+
+    Ok(())
+}
@@ -1,33 +1,37 @@
+use bridge_core::{MonetSmartContractCalls, MonetSmartContractEvents, NodeArgs};
+use clap::Parser;
 use ethers::{
     prelude::*,
-    providers::{Http, Provider},
-    types::{Transaction, TransactionReceipt, H256, U256},
-    abi::{Abi, Event, RawLog, ParamType, Token},
+    types::{Transaction, TransactionReceipt, H256},
+    abi::AbiDecode,
+    contract::parse_log,
 };
 use eyre::Result;
 use std::sync::Arc;
-use serde_json::Value;
-use std::fs;
-use std::path::Path;
 use std::str::FromStr;  // Added for H256::from_str
-use std::env;
 use dotenv::dotenv;
 use hex;
 
+/// Decodes a MonetSmartContract transaction and its events
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
 
     dotenv().ok();
     
-    // Set up the provider using NODE1_RPC from env
-    let rpc_url = env::var("NODE5_RPC")
-        .expect("RPC_URL must be set in .env file");
+    // Set up the provider for the selected node (NODE5 by default)
+    let node = Cli::parse().nodes.node_or("5")?;
+    let rpc_url = node.rpc_url.clone();
 
     let tx_hash = "0xaef4da2369b368594dc7c171635243afefe868464f010b664c392fa9924ee503";
 
     // Create provider
-    let provider = Provider::<Http>::try_from(rpc_url.clone())?;
-    let client = Arc::new(provider);
+    let client = Arc::new(node.provider().await?);
 
     // Get transaction hash
     let tx_hash = tx_hash.parse::<H256>()?;
@@ -43,19 +47,13 @@ async fn main() -> Result<()> {
     let receipt = client.get_transaction_receipt(tx_hash).await?
         .ok_or_else(|| eyre::eyre!("Transaction receipt not found"))?;
 
-    // Load contract ABI
-    let abi_path = Path::new("src/bin/abi.json");
-    let abi_content = fs::read_to_string(abi_path)?;
-    let abi_json: Value = serde_json::from_str(&abi_content)?;
-    let abi: ethers::abi::Abi = serde_json::from_value(abi_json["abi"].clone())?;
-
     println!("\nTransaction Details:");
     println!("------------------");
     print_transaction_details(&tx, &receipt)?;
 
     println!("\nFunction Call:");
     println!("--------------");
-    if let Some(input) = decode_function_call(&tx.input, &abi) {
+    if let Some(input) = decode_function_call(&tx.input) {
         println!("{}", input);
     } else {
         println!("Could not decode function call");
@@ -63,7 +61,7 @@ async fn main() -> Result<()> {
 
     println!("\nEvents:");
     println!("-------");
-    decode_logs(&receipt.logs, &abi, &tx)?;
+    decode_logs(&receipt.logs, &tx)?;
 
     Ok(())
 }
@@ -131,52 +129,13 @@ fn print_transaction_details(tx: &Transaction, receipt: &TransactionReceipt) ->
     Ok(())
 }
 
-fn decode_function_call(input: &Bytes, abi: &ethers::abi::Abi) -> Option<String> {
-    if input.len() < 4 {
-        return None;
-    }
-
-    // Get function selector (first 4 bytes)
-    let selector = &input[0..4];
-
-    // Find matching function
-    for function in abi.functions() {
-        if function.short_signature() == selector {
-            // Try to decode parameters
-            match function.decode_input(&input[4..]) {
-                Ok(tokens) => {
-                    let params: Vec<String> = tokens.iter()
-                        .zip(function.inputs.iter())
-                        .map(|(token, param)| {
-                            match token {
-                                Token::Uint(val) if param.name == "chainID" => {
-                                    // Convert U256 to bytes
-                                    let mut bytes = [0u8; 32];
-                                    val.to_big_endian(&mut bytes);
-                                    let le_val = u32::from_le_bytes(bytes[28..32].try_into().unwrap());
-                                    let be_val = u32::from_be_bytes(bytes[28..32].try_into().unwrap());
-                                    format!("{}: {} (BE: {}, LE: {}, raw: {:?})", 
-                                        param.name, val, be_val, le_val, &bytes[28..32])
-                                },
-                                _ => format!("{}: {}", param.name, token)
-                            }
-                        })
-                        .collect();
-                    
-                    return Some(format!("{}({})", 
-                        function.name,
-                        params.join(", ")
-                    ));
-                }
-                Err(_) => return Some(format!("{}(...)", function.name)),
-            }
-        }
-    }
-
-    None
+fn decode_function_call(input: &Bytes) -> Option<String> {
+    MonetSmartContractCalls::decode(input)
+        .ok()
+        .map(|call| format!("{:#?}", call))
 }
 
-fn decode_logs(logs: &[Log], abi: &ethers::abi::Abi, tx: &Transaction) -> Result<()> {
+fn decode_logs(logs: &[Log], tx: &Transaction) -> Result<()> {
     for log in logs {
         println!("\nLog from contract at {:#x}:", log.address);
         
@@ -187,45 +146,18 @@ fn decode_logs(logs: &[Log], abi: &ethers::abi::Abi, tx: &Transaction) -> Result
             println!("    Event emitted from: {:#x}", log.address);
         }
 
-        for event in abi.events() {
-            let event_sig = event.signature();
-            if log.topics[0] == event_sig {
-                println!("Event: {}", event.name);
-                
-                let raw_log = RawLog {
-                    topics: log.topics.clone(),
-                    data: log.data.to_vec(),
-                };
-
-                match event.parse_log(raw_log) {
-                    Ok(parsed) => {
-                        println!("  Contract Address: {:#x}", log.address);
-                        println!("  Block Number: {}", log.block_number.unwrap_or_default());
-                        println!("  Transaction Index: {}", log.transaction_index.unwrap_or_default());
-                        println!("  Log Index: {}", log.log_index.unwrap_or_default());
-                        println!("  Parameters:");
-                        
-                        for (param, value) in event.inputs.iter().zip(parsed.params) {
-                            let formatted_value = match &value.value {
-                                Token::Uint(val) => {
-                                    match param.kind {
-                                        ParamType::Uint(32) => format!("{}", val.as_u32()),
-                                        ParamType::Uint(256) => format!("{}", val),
-                                        _ => val.to_string(),
-                                    }
-                                },
-                                Token::Address(addr) => format!("{:#x}", addr),
-                                _ => value.value.to_string(),
-                            };
-                            println!("    {}: {}", param.name, formatted_value);
-                        }
-                    }
-                    Err(e) => {
-                        println!("  Failed to parse log: {}", e);
-                    }
-                }
+        match parse_log::<MonetSmartContractEvents>(log.clone()) {
+            Ok(event) => {
+                println!("  Contract Address: {:#x}", log.address);
+                println!("  Block Number: {}", log.block_number.unwrap_or_default());
+                println!("  Transaction Index: {}", log.transaction_index.unwrap_or_default());
+                println!("  Log Index: {}", log.log_index.unwrap_or_default());
+                println!("  Event: {:#?}", event);
+            }
+            Err(e) => {
+                println!("  Failed to parse log: {}", e);
             }
         }
     }
     Ok(())
-} 
\ No newline at end of file
+}
@@ -0,0 +1,53 @@
+use bridge_core::{connect, DashboardHandle, NodeArgs, TxpoolArgs};
+use clap::Parser;
+use eyre::Result;
+use std::{sync::Arc, time::Duration};
+use tokio::time::timeout;
+
+/// Samples a node's txpool alongside a load run of another tool; join the samples with
+/// that tool's own timeline on `unix_ms`
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+
+    /// RPC endpoint instead of the selected node's
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    #[command(flatten)]
+    txpool: TxpoolArgs,
+
+    /// Stop after this many seconds; runs until Ctrl-C otherwise
+    #[arg(long)]
+    duration_secs: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    let mut cli = Cli::parse();
+
+    let rpc_url = match &cli.rpc_url {
+        Some(url) => url.clone(),
+        None => cli.nodes.node_or("1")?.rpc_url,
+    };
+    let client = Arc::new(connect(&rpc_url).await?);
+    println!("Connected to {}", rpc_url);
+
+    // Samples carry no send counts here, since the load comes from another process
+    cli.txpool.txpool_sample_ms.get_or_insert(1000);
+    let sampler = cli.txpool.start(client, DashboardHandle::default())?
+        .expect("the sample interval is set");
+    match cli.duration_secs {
+        Some(secs) => {
+            let _ = timeout(Duration::from_secs(secs), tokio::signal::ctrl_c()).await;
+        }
+        None => tokio::signal::ctrl_c().await?,
+    }
+
+    if let Some(summary) = sampler.finish().await {
+        println!("\n{}", summary);
+    }
+    Ok(())
+}
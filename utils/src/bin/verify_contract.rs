@@ -1,25 +1,38 @@
+use bridge_core::{connect, contract::monet_deployed_bytecode, NodeArgs};
+use clap::Parser;
 use ethers::{
     prelude::*,
-    providers::{Http, Provider},
     types::H160,
 };
 use std::sync::Arc;
-use serde_json::Value;
-use std::fs;
 use eyre::Result;
 
+/// Compares the code deployed on a chain with the local forge build
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    nodes: NodeArgs,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // 1. Hardcoded inputs
-    let rpc_url = "http://34.21.80.98:8845";  // Example RPC URL
-    let contract_address = "0xe1cb87e107b1727422f01a98428eb58c2cd3a53d";  // Example contract address
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+
+    // 1. Selected node, or the hardcoded example inputs
+    let (rpc_url, contract_address) = if cli.nodes.node.is_some() {
+        let node = cli.nodes.node_or("1")?;
+        (node.rpc_url, format!("{:#x}", node.contract))
+    } else {
+        ("http://34.21.80.98:8845".to_string(), "0xe1cb87e107b1727422f01a98428eb58c2cd3a53d".to_string())
+    };
 
     println!("Verifying contract deployment...");
     println!("RPC URL: {}", rpc_url);
     println!("Contract Address: {}", contract_address);
 
     // 2. Get deployed bytecode from network
-    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let provider = connect(&rpc_url).await?;
     let client = Arc::new(provider);
     
     // Get chain ID first
@@ -49,23 +62,21 @@ async fn main() -> Result<()> {
     println!("\n✅ Code found at address!");
     
     // 3. Get local compiled bytecode
-    let contract_json: Value = serde_json::from_slice(
-        include_bytes!("../../../reth-contract/out/MonetSmartContract.sol/MonetSmartContract.json")
-    )?;
-    
-    let local_bytecode = contract_json["deployedBytecode"]["object"]
-        .as_str()
-        .ok_or_else(|| eyre::eyre!("Bytecode not found in contract JSON"))?;
+    let local_code = monet_deployed_bytecode();
+    if local_code.is_empty() {
+        return Err(eyre::eyre!("Bytecode not found in contract JSON"));
+    }
+    let local_bytecode = hex::encode(&local_code);
 
     // 4. Compare bytecodes
     println!("\nDeployed bytecode length: {} bytes", deployed_code.len());
-    println!("Local bytecode length: {} bytes", local_bytecode.len() / 2 - 1); // divide by 2 because hex, -1 for '0x' prefix
+    println!("Local bytecode length: {} bytes", local_code.len());
 
     // Optional: Print first few bytes of each for comparison
     println!("\nFirst 64 bytes of deployed code: 0x{}", &hex::encode(&deployed_code)[..64]);
-    println!("First 64 bytes of local code:    0x{}", &local_bytecode[2..66]);
+    println!("First 64 bytes of local code:    0x{}", &local_bytecode[..64]);
 
-    if deployed_code == hex::decode(&local_bytecode[2..])? {
+    if deployed_code == local_code {
         println!("\n✅ Verification successful!");
         println!("The deployed contract matches the local compiled bytecode.");
     } else {
@@ -74,9 +85,7 @@ async fn main() -> Result<()> {
         
         // Optional: Print first mismatch location for debugging
         let deployed_hex = hex::encode(&deployed_code);
-        let local_hex = &local_bytecode[2..]; // skip '0x' prefix
-        
-        for (i, (d, l)) in deployed_hex.chars().zip(local_hex.chars()).enumerate() {
+        for (i, (d, l)) in deployed_hex.chars().zip(local_bytecode.chars()).enumerate() {
             if d != l {
                 println!("First mismatch at position {}: deployed '{}' vs local '{}'", i, d, l);
                 break;